@@ -41,6 +41,15 @@ fn byte_vector(c: &mut Criterion) {
             BatchSize::SmallInput,
         )
     });
+    c.bench_function("TLS Serialize Byte Vector to Slice", |b| {
+        b.iter_batched(
+            || (TlsByteVecU32::from(vec![77u8; 65535]), vec![0u8; 65539]),
+            |(long_vector, mut out)| {
+                let _written = long_vector.tls_serialize_to_slice(&mut out).unwrap();
+            },
+            BatchSize::SmallInput,
+        )
+    });
     c.bench_function("TLS Deserialize Byte Vector", |b| {
         b.iter_batched(
             || {
@@ -86,8 +95,36 @@ fn slice(c: &mut Criterion) {
         )
     });
 }
+fn nested_vector(c: &mut Criterion) {
+    use tls_codec::*;
+    type Nested = TlsVecU32<TlsVecU32<TlsVecU32<TlsVecU16<u16>>>>;
+    fn nested() -> Nested {
+        let inner = TlsVecU32::from(vec![TlsVecU16::from(vec![77u16; 16]); 16]);
+        TlsVecU32::from(vec![TlsVecU32::from(vec![inner; 16]); 16])
+    }
+    c.bench_function("TLS Serialize Nested Vector", |b| {
+        b.iter_batched(
+            nested,
+            |nested| {
+                let _serialized_nested = nested.tls_serialize_detached().unwrap();
+            },
+            BatchSize::SmallInput,
+        )
+    });
+    c.bench_function("TLS Serialize Nested Vector without Patching", |b| {
+        b.iter_batched(
+            || (nested(), Vec::new()),
+            |(nested, mut serialized_nested)| {
+                nested.tls_serialize(&mut serialized_nested).unwrap();
+            },
+            BatchSize::SmallInput,
+        )
+    });
+}
+
 fn benchmark(c: &mut Criterion) {
     vector(c);
+    nested_vector(c);
     slice(c);
     byte_vector(c);
     byte_slice(c);
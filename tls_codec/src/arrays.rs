@@ -1,45 +1,136 @@
-//! Implement the TLS codec for some byte arrays.
-
-use super::{Deserialize, Error, Serialize, Size};
-use std::io::{Read, Write};
-
-macro_rules! impl_array {
-    ($($len:literal),*) => {
-        $(
-            impl Serialize for [u8; $len] {
-                fn tls_serialize<W: Write>(&self, writer: &mut W) -> Result<usize, Error> {
-                    let written = writer.write(self)?;
-                    if written == $len {
-                        Ok(written)
-                    } else {
-                        Err(Error::InvalidWriteLength(format!("Expected to write {} bytes but only {} were written.", $len, written)))
-                    }
-                }
-            }
-
-            impl Deserialize for [u8; $len] {
-                fn tls_deserialize<R: Read>(bytes: &mut R) -> Result<Self, Error> {
-                    let mut out = [0u8; $len];
-                    bytes.read_exact(&mut out)?;
-                    Ok(out)
-                }
-            }
-
-            impl Size for [u8; $len] {
-                #[inline]
-                fn tls_serialized_len(&self) -> usize {
-                    $len
-                }
-            }
-        )*
-    };
-}
-
-impl_array!(
-    1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26,
-    27, 28, 29, 30, 31, 32, 33, 34, 35, 36, 37, 38, 39, 40, 41, 42, 43, 44, 45, 46, 47, 48, 49, 50,
-    51, 52, 53, 54, 55, 56, 57, 58, 59, 60, 61, 62, 63, 64, 65, 66, 67, 68, 69, 70, 71, 72, 73, 74,
-    75, 76, 77, 78, 79, 80, 81, 82, 83, 84, 85, 86, 87, 88, 89, 90, 91, 92, 93, 94, 95, 96, 97, 98,
-    99, 100, 101, 102, 103, 104, 105, 106, 107, 108, 109, 110, 111, 112, 113, 114, 115, 116, 117,
-    118, 119, 120, 121, 122, 123, 124, 125, 126, 127, 128
-);
+//! Implement the TLS codec for arrays `[T; N]`.
+//!
+//! The elements are encoded one after another without a length prefix.
+//! Arrays of `u8` read and write all bytes at once, and are referenced by a
+//! [`VectoredWriter`] instead of being copied.
+
+#[cfg(feature = "async")]
+use super::{AsyncDeserialize, AsyncReader, AsyncSerialize, AsyncWriter};
+use super::{
+    Deserialize, DeserializeBytes, DeserializeWithContext, Error, FixedSize, Reader, Serialize,
+    SerializeWithContext, Size, VectoredWriter, Writer,
+};
+use crate::error::add_len;
+use alloc::format;
+
+/// Decode the `N` elements of an array with `deserialize`.
+///
+/// Errors are annotated with the index of the element.
+#[inline]
+pub(crate) fn deserialize_array<T: Size, const N: usize>(
+    mut deserialize: impl FnMut() -> Result<T, Error>,
+) -> Result<[T; N], Error> {
+    let mut elements: [Option<T>; N] = core::array::from_fn(|_| None);
+    let mut offset = 0;
+    for (index, element) in elements.iter_mut().enumerate() {
+        let value = deserialize().map_err(|e| e.with_index(offset, index))?;
+        offset += value.tls_serialized_len();
+        *element = Some(value);
+    }
+    Ok(unwrap_elements(elements))
+}
+
+/// Get the elements of an array in which all of them are set.
+#[inline]
+pub(crate) fn unwrap_elements<T, const N: usize>(elements: [Option<T>; N]) -> [T; N] {
+    elements.map(|element| element.expect("all elements of the array are decoded"))
+}
+
+impl<T: Size, const N: usize> Size for [T; N] {
+    #[inline]
+    fn tls_serialized_len(&self) -> usize {
+        match T::tls_fixed_len() {
+            Some(len) => len * N,
+            None => self.iter().map(Size::tls_serialized_len).sum(),
+        }
+    }
+
+    #[inline]
+    fn tls_serialized_len_checked(&self) -> Result<usize, Error> {
+        match T::tls_fixed_len() {
+            Some(len) => len.checked_mul(N).ok_or_else(|| {
+                Error::EncodingError(format!("the serialized length overflows: {} * {}", len, N))
+            }),
+            None => self
+                .iter()
+                .try_fold(0, |acc, e| add_len(acc, e.tls_serialized_len_checked()?)),
+        }
+    }
+
+    #[inline]
+    fn tls_fixed_len() -> Option<usize> {
+        T::tls_fixed_len()?.checked_mul(N)
+    }
+}
+
+impl<T: FixedSize, const N: usize> FixedSize for [T; N] {
+    const TLS_LEN: usize = T::TLS_LEN * N;
+}
+
+impl<T: Serialize, const N: usize> Serialize for [T; N] {
+    #[inline]
+    fn tls_serialize<W: Writer>(&self, writer: &mut W) -> Result<usize, Error> {
+        T::tls_serialize_elements(self, writer)
+    }
+
+    #[inline]
+    fn tls_serialize_to_checked_slice(&self, out: &mut [u8]) -> Result<usize, Error> {
+        T::tls_serialize_elements_to_slice(self, out)
+    }
+
+    #[inline]
+    fn tls_serialize_vectored<'v>(
+        &'v self,
+        writer: &mut VectoredWriter<'v>,
+    ) -> Result<usize, Error> {
+        T::tls_serialize_elements_vectored(self, writer)
+    }
+}
+
+impl<T: Deserialize, const N: usize> Deserialize for [T; N] {
+    #[inline]
+    fn tls_deserialize<R: Reader>(bytes: &mut R) -> Result<Self, Error> {
+        T::tls_deserialize_array(bytes)
+    }
+}
+
+impl<'a, T: DeserializeBytes<'a>, const N: usize> DeserializeBytes<'a> for [T; N] {
+    #[inline]
+    fn tls_deserialize_bytes(bytes: &'a [u8]) -> Result<(Self, &'a [u8]), Error> {
+        T::tls_deserialize_bytes_array(bytes)
+    }
+}
+
+impl<C: ?Sized, T: SerializeWithContext<C>, const N: usize> SerializeWithContext<C> for [T; N] {
+    fn tls_serialize_with_context<W: Writer>(
+        &self,
+        writer: &mut W,
+        context: &C,
+    ) -> Result<usize, Error> {
+        self.iter().try_fold(0, |written, e| {
+            Ok(written + e.tls_serialize_with_context(writer, context)?)
+        })
+    }
+}
+
+impl<C: ?Sized, T: DeserializeWithContext<C>, const N: usize> DeserializeWithContext<C> for [T; N] {
+    fn tls_deserialize_with_context<R: Reader>(bytes: &mut R, context: &C) -> Result<Self, Error> {
+        deserialize_array(|| T::tls_deserialize_with_context(bytes, context))
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T: AsyncSerialize, const N: usize> AsyncSerialize for [T; N] {
+    #[inline]
+    async fn tls_serialize_async<W: AsyncWriter>(&self, writer: &mut W) -> Result<usize, Error> {
+        T::tls_serialize_elements_async(self, writer).await
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T: AsyncDeserialize, const N: usize> AsyncDeserialize for [T; N] {
+    #[inline]
+    async fn tls_deserialize_async<R: AsyncReader>(bytes: &mut R) -> Result<Self, Error> {
+        T::tls_deserialize_array_async(bytes).await
+    }
+}
@@ -0,0 +1,228 @@
+//! The `AsyncReader` and `AsyncWriter` abstractions used by the async codec.
+//!
+//! They are implemented for `&[u8]` and `Vec<u8>`.
+//! With the `futures-io` and `tokio` features, [`FuturesIo`] and [`TokioIo`]
+//! adapt the async IO traits of these crates.
+
+use alloc::vec::Vec;
+use core::future::Future;
+
+use crate::{DecodeBudget, Error, LimitedReader};
+
+/// An async source of bytes to deserialize from.
+///
+/// Readers are `Send` and return `Send` futures, such that decoding from
+/// them can be spawned as a task.
+/// Implementations can use `async fn`.
+pub trait AsyncReader: Send {
+    /// Read exactly `buf.len()` bytes into `buf`.
+    ///
+    /// Returns [`Error::EndOfStream`] if not enough bytes are available.
+    fn read_bytes(&mut self, buf: &mut [u8]) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Get the [`DecodeBudget`] that limits decoding from this reader.
+    ///
+    /// The default implementation returns `None`, i.e. decoding is not
+    /// limited.
+    #[inline]
+    fn budget(&mut self) -> Option<&mut DecodeBudget> {
+        None
+    }
+}
+
+/// An async sink for serialized bytes.
+///
+/// Writers are `Send` and return `Send` futures like [`AsyncReader`].
+pub trait AsyncWriter: Send {
+    /// Write all of `bytes`.
+    fn write_bytes(&mut self, bytes: &[u8]) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Get the position of the next byte written, if this writer can
+    /// overwrite bytes that were already written with
+    /// [`AsyncWriter::patch_bytes`].
+    ///
+    /// See [`Writer::position`](crate::Writer::position).
+    /// The default implementation returns `None`.
+    #[inline]
+    fn position(&self) -> Option<usize> {
+        None
+    }
+
+    /// Overwrite the bytes at `position`, as returned by
+    /// [`AsyncWriter::position`], with `bytes`.
+    ///
+    /// The default implementation returns an error.
+    fn patch_bytes(&mut self, _position: usize, _bytes: &[u8]) -> Result<(), Error> {
+        Err(Error::EncodingError(
+            "the writer can't patch written bytes".into(),
+        ))
+    }
+}
+
+impl AsyncReader for &[u8] {
+    #[inline]
+    async fn read_bytes(&mut self, buf: &mut [u8]) -> Result<(), Error> {
+        if buf.len() > self.len() {
+            return Err(Error::EndOfStream);
+        }
+        let (head, tail) = self.split_at(buf.len());
+        buf.copy_from_slice(head);
+        *self = tail;
+        Ok(())
+    }
+}
+
+impl<R: AsyncReader + ?Sized> AsyncReader for &mut R {
+    #[inline]
+    async fn read_bytes(&mut self, buf: &mut [u8]) -> Result<(), Error> {
+        (**self).read_bytes(buf).await
+    }
+
+    #[inline]
+    fn budget(&mut self) -> Option<&mut DecodeBudget> {
+        (**self).budget()
+    }
+}
+
+impl<'a, R: AsyncReader + ?Sized> AsyncReader for LimitedReader<'a, R> {
+    #[inline]
+    async fn read_bytes(&mut self, buf: &mut [u8]) -> Result<(), Error> {
+        self.reader.read_bytes(buf).await
+    }
+
+    #[inline]
+    fn budget(&mut self) -> Option<&mut DecodeBudget> {
+        Some(&mut self.budget)
+    }
+}
+
+impl AsyncWriter for Vec<u8> {
+    #[inline]
+    async fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        self.extend_from_slice(bytes);
+        Ok(())
+    }
+
+    #[inline]
+    fn position(&self) -> Option<usize> {
+        Some(self.len())
+    }
+
+    #[inline]
+    fn patch_bytes(&mut self, position: usize, bytes: &[u8]) -> Result<(), Error> {
+        crate::io::patch(self, position, bytes)
+    }
+}
+
+impl<W: AsyncWriter + ?Sized> AsyncWriter for &mut W {
+    #[inline]
+    async fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        (**self).write_bytes(bytes).await
+    }
+
+    #[inline]
+    fn position(&self) -> Option<usize> {
+        (**self).position()
+    }
+
+    #[inline]
+    fn patch_bytes(&mut self, position: usize, bytes: &[u8]) -> Result<(), Error> {
+        (**self).patch_bytes(position, bytes)
+    }
+}
+
+#[cfg(any(feature = "futures-io", feature = "tokio"))]
+fn write_error(e: std::io::Error) -> Error {
+    Error::EncodingError(alloc::format!("io error: {:?}", e))
+}
+
+/// An adapter that implements [`AsyncReader`] and [`AsyncWriter`] for
+/// [`futures_io::AsyncRead`] and [`futures_io::AsyncWrite`].
+///
+/// ```
+/// # futures::executor::block_on(async {
+/// use tls_codec::{AsyncDeserialize, FuturesIo, TlsVecU8};
+///
+/// let mut reader = FuturesIo(futures::io::Cursor::new(vec![2u8, 7, 8]));
+/// let v = TlsVecU8::<u8>::tls_deserialize_async(&mut reader).await.unwrap();
+/// assert_eq!(&[7, 8], v.as_slice());
+/// # });
+/// ```
+#[cfg(feature = "futures-io")]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct FuturesIo<T>(pub T);
+
+#[cfg(feature = "futures-io")]
+impl<T: futures_io::AsyncRead + Unpin + Send> AsyncReader for FuturesIo<T> {
+    async fn read_bytes(&mut self, buf: &mut [u8]) -> Result<(), Error> {
+        let mut filled = 0;
+        while filled < buf.len() {
+            let read = core::future::poll_fn(|cx| {
+                core::pin::Pin::new(&mut self.0).poll_read(cx, &mut buf[filled..])
+            })
+            .await;
+            match read {
+                Ok(0) => return Err(Error::EndOfStream),
+                Ok(read) => filled += read,
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "futures-io")]
+impl<T: futures_io::AsyncWrite + Unpin + Send> AsyncWriter for FuturesIo<T> {
+    async fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        let mut written = 0;
+        while written < bytes.len() {
+            let write = core::future::poll_fn(|cx| {
+                core::pin::Pin::new(&mut self.0).poll_write(cx, &bytes[written..])
+            })
+            .await;
+            match write {
+                Ok(0) => return Err(write_error(std::io::ErrorKind::WriteZero.into())),
+                Ok(write) => written += write,
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(write_error(e)),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An adapter that implements [`AsyncReader`] and [`AsyncWriter`] for
+/// [`tokio::io::AsyncRead`] and [`tokio::io::AsyncWrite`].
+///
+/// ```
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// use tls_codec::{AsyncSerialize, TlsVecU8, TokioIo};
+///
+/// let mut writer = TokioIo(Vec::new());
+/// TlsVecU8::<u8>::from_slice(&[7, 8]).tls_serialize_async(&mut writer).await.unwrap();
+/// assert_eq!(vec![2u8, 7, 8], writer.0);
+/// # });
+/// ```
+#[cfg(feature = "tokio")]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TokioIo<T>(pub T);
+
+#[cfg(feature = "tokio")]
+impl<T: tokio::io::AsyncRead + Unpin + Send> AsyncReader for TokioIo<T> {
+    #[inline]
+    async fn read_bytes(&mut self, buf: &mut [u8]) -> Result<(), Error> {
+        tokio::io::AsyncReadExt::read_exact(&mut self.0, buf).await?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<T: tokio::io::AsyncWrite + Unpin + Send> AsyncWriter for TokioIo<T> {
+    #[inline]
+    async fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        tokio::io::AsyncWriteExt::write_all(&mut self.0, bytes)
+            .await
+            .map_err(write_error)
+    }
+}
@@ -0,0 +1,471 @@
+//! Errors that are thrown by this crate.
+//!
+//! Decoding errors from derived structs, vectors, tuples, and the values of
+//! options are wrapped in an [`Error::Context`] that records the byte offset in
+//! the input where the failing value starts, and the [`Path`] through the types
+//! to that value.
+//! Errors of tags, i.e. of options and derived enums, are returned as is.
+
+use alloc::{boxed::Box, string::String, vec::Vec};
+use core::fmt::{self, Display};
+
+use crate::DecodeLimit;
+
+/// Errors that are thrown by this crate.
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum Error {
+    /// An error occurred during encoding.
+    EncodingError(String),
+
+    /// The length of a vector is invalid.
+    ///
+    /// When decoding, `expected` is the length in bytes of the vector's
+    /// content as given by its length prefix, and `actual` is the number of
+    /// bytes its elements took up.
+    /// If the content of a vector of fixed-size elements isn't a multiple of
+    /// the element length, `expected` is the nearest multiple and `actual` is
+    /// the length given by the prefix.
+    /// When encoding, `expected` is the maximum number of bytes the length
+    /// prefix can encode, and `actual` is the length of the content.
+    InvalidVectorLength {
+        /// The expected length in bytes.
+        expected: usize,
+        /// The actual length in bytes.
+        actual: usize,
+    },
+
+    /// The length in bytes of a vector's content is outside of the bounds
+    /// of its [`Bounded`](crate::Bounded) length prefix.
+    VectorLengthOutOfBounds {
+        /// The minimum length in bytes.
+        min: usize,
+        /// The maximum length in bytes.
+        max: usize,
+        /// The actual length in bytes.
+        actual: usize,
+    },
+
+    /// Error writing everything out.
+    InvalidWriteLength(String),
+
+    /// Invalid input when trying to decode a primitive integer.
+    InvalidInput,
+
+    /// An error occurred during decoding.
+    DecodingError(String),
+
+    /// Reached the end of a byte stream.
+    EndOfStream,
+
+    /// Decoding exceeded a limit in [`DecodeLimits`](crate::DecodeLimits).
+    LimitExceeded {
+        /// The limit that was exceeded.
+        limit: DecodeLimit,
+        /// The maximum allowed by the limit.
+        max: usize,
+        /// The value that exceeded the limit.
+        actual: usize,
+    },
+
+    /// The decoded value of an enum doesn't match any of its variants.
+    UnknownEnumValue {
+        /// The decoded value.
+        value: u64,
+    },
+
+    /// The tag of an `Option` is neither `0` for `None` nor `1` for `Some`.
+    InvalidOptionTag {
+        /// The decoded tag.
+        tag: u8,
+    },
+
+    /// A decoded `bool` is neither `0` nor `1`.
+    InvalidBool {
+        /// The decoded value.
+        value: u8,
+    },
+
+    /// The content of a string isn't valid UTF-8.
+    InvalidUtf8 {
+        /// The number of bytes of valid UTF-8 at the start of the content.
+        valid_up_to: usize,
+    },
+
+    /// A variable-length integer is encoded with more bytes than needed.
+    NonMinimalVarInt {
+        /// The decoded value.
+        value: u64,
+    },
+
+    /// Bytes were left in the input after decoding a value that must take up
+    /// all of it.
+    TrailingData {
+        /// The number of bytes left in the input.
+        remaining: usize,
+    },
+
+    /// An `error` occurred while decoding the value at `path`, which starts
+    /// at byte `offset` of the input.
+    ///
+    /// Use [`Error::kind`] to get the underlying error.
+    Context {
+        /// The byte offset in the input where the failing value starts.
+        offset: usize,
+        /// The path to the failing value.
+        path: Path,
+        /// The underlying error.
+        error: Box<Error>,
+    },
+}
+
+impl Error {
+    /// Get the underlying error without [`Error::Context`].
+    pub fn kind(&self) -> &Error {
+        match self {
+            Error::Context { error, .. } => error.kind(),
+            _ => self,
+        }
+    }
+
+    /// Get the byte offset in the input where the failing value starts.
+    ///
+    /// This is `0` for errors without [`Error::Context`].
+    pub fn offset(&self) -> usize {
+        match self {
+            Error::Context { offset, .. } => *offset,
+            _ => 0,
+        }
+    }
+
+    /// Get the path to the failing value, if known.
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            Error::Context { path, .. } => Some(path),
+            _ => None,
+        }
+    }
+
+    /// Move the error `offset` bytes further into the input.
+    ///
+    /// This is used by containers when the failing value starts `offset`
+    /// bytes after the start of the container.
+    pub fn with_offset(self, offset: usize) -> Self {
+        self.with_segment(offset, None)
+    }
+
+    /// Add the `field` that starts `offset` bytes into its parent to the path.
+    pub fn with_field(self, offset: usize, field: &'static str) -> Self {
+        self.with_segment(offset, Some(PathSegment::Field(field)))
+    }
+
+    /// Add the vector element at `index` that starts `offset` bytes into the
+    /// vector to the path.
+    pub fn with_index(self, offset: usize, index: usize) -> Self {
+        self.with_segment(offset, Some(PathSegment::Index(index)))
+    }
+
+    /// Set the name of the type at the root of the path.
+    ///
+    /// Each derived struct sets its name here such that the outermost one is
+    /// kept.
+    pub fn with_type(self, name: &'static str) -> Self {
+        match self {
+            Error::Context {
+                offset,
+                mut path,
+                error,
+            } => {
+                path.root = Some(name);
+                Error::Context {
+                    offset,
+                    path,
+                    error,
+                }
+            }
+            error => Error::Context {
+                offset: 0,
+                path: Path {
+                    root: Some(name),
+                    segments: Vec::new(),
+                },
+                error: Box::new(error),
+            },
+        }
+    }
+
+    /// Get the TLS alert to send for this error.
+    ///
+    /// Unknown enum values are an
+    /// [`AlertDescription::IllegalParameter`], while other malformed input,
+    /// e.g. a length that doesn't match the content, is an
+    /// [`AlertDescription::DecodeError`].
+    /// Encoding errors are an [`AlertDescription::InternalError`].
+    ///
+    /// ```
+    /// use tls_codec::{AlertDescription, Deserialize, Error};
+    ///
+    /// let error = Option::<u8>::tls_deserialize(&mut &[2u8][..]).unwrap_err();
+    /// assert_eq!(Error::InvalidOptionTag { tag: 2 }, error);
+    /// assert_eq!(AlertDescription::DecodeError, error.alert_description());
+    /// assert_eq!(50, error.alert_description() as u8);
+    /// ```
+    pub fn alert_description(&self) -> AlertDescription {
+        match self {
+            Error::EncodingError(_) | Error::InvalidWriteLength(_) => {
+                AlertDescription::InternalError
+            }
+            Error::UnknownEnumValue { .. } => AlertDescription::IllegalParameter,
+            Error::InvalidVectorLength { .. }
+            | Error::VectorLengthOutOfBounds { .. }
+            | Error::InvalidInput
+            | Error::DecodingError(_)
+            | Error::EndOfStream
+            | Error::LimitExceeded { .. }
+            | Error::InvalidOptionTag { .. }
+            | Error::InvalidBool { .. }
+            | Error::InvalidUtf8 { .. }
+            | Error::NonMinimalVarInt { .. }
+            | Error::TrailingData { .. } => AlertDescription::DecodeError,
+            Error::Context { error, .. } => error.alert_description(),
+        }
+    }
+
+    fn with_segment(self, offset: usize, segment: Option<PathSegment>) -> Self {
+        match self {
+            Error::Context {
+                offset: inner_offset,
+                mut path,
+                error,
+            } => {
+                path.segments.extend(segment);
+                Error::Context {
+                    offset: offset + inner_offset,
+                    path,
+                    error,
+                }
+            }
+            error => Error::Context {
+                offset,
+                path: Path {
+                    root: None,
+                    segments: segment.into_iter().collect(),
+                },
+                error: Box::new(error),
+            },
+        }
+    }
+}
+
+/// The TLS alert descriptions as defined in
+/// [RFC 8446](https://www.rfc-editor.org/rfc/rfc8446#section-6).
+///
+/// [`Error::alert_description`] maps errors to the alert to send.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+#[non_exhaustive]
+pub enum AlertDescription {
+    /// `close_notify(0)`
+    CloseNotify = 0,
+    /// `unexpected_message(10)`
+    UnexpectedMessage = 10,
+    /// `bad_record_mac(20)`
+    BadRecordMac = 20,
+    /// `record_overflow(22)`
+    RecordOverflow = 22,
+    /// `handshake_failure(40)`
+    HandshakeFailure = 40,
+    /// `bad_certificate(42)`
+    BadCertificate = 42,
+    /// `unsupported_certificate(43)`
+    UnsupportedCertificate = 43,
+    /// `certificate_revoked(44)`
+    CertificateRevoked = 44,
+    /// `certificate_expired(45)`
+    CertificateExpired = 45,
+    /// `certificate_unknown(46)`
+    CertificateUnknown = 46,
+    /// `illegal_parameter(47)`
+    IllegalParameter = 47,
+    /// `unknown_ca(48)`
+    UnknownCa = 48,
+    /// `access_denied(49)`
+    AccessDenied = 49,
+    /// `decode_error(50)`
+    DecodeError = 50,
+    /// `decrypt_error(51)`
+    DecryptError = 51,
+    /// `protocol_version(70)`
+    ProtocolVersion = 70,
+    /// `insufficient_security(71)`
+    InsufficientSecurity = 71,
+    /// `internal_error(80)`
+    InternalError = 80,
+    /// `inappropriate_fallback(86)`
+    InappropriateFallback = 86,
+    /// `user_canceled(90)`
+    UserCanceled = 90,
+    /// `missing_extension(109)`
+    MissingExtension = 109,
+    /// `unsupported_extension(110)`
+    UnsupportedExtension = 110,
+    /// `unrecognized_name(112)`
+    UnrecognizedName = 112,
+    /// `bad_certificate_status_response(113)`
+    BadCertificateStatusResponse = 113,
+    /// `unknown_psk_identity(115)`
+    UnknownPskIdentity = 115,
+    /// `certificate_required(116)`
+    CertificateRequired = 116,
+    /// `no_application_protocol(120)`
+    NoApplicationProtocol = 120,
+}
+
+impl From<AlertDescription> for u8 {
+    fn from(alert: AlertDescription) -> Self {
+        alert as u8
+    }
+}
+
+/// Add the serialized length `len` to `total`.
+///
+/// Returns [`Error::EncodingError`] if the sum overflows `usize`.
+#[inline]
+pub fn add_len(total: usize, len: usize) -> Result<usize, Error> {
+    total.checked_add(len).ok_or_else(|| {
+        Error::EncodingError(alloc::format!(
+            "the serialized length overflows: {} + {}",
+            total,
+            len
+        ))
+    })
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::EncodingError(e) => write!(f, "encoding error: {}", e),
+            Error::InvalidVectorLength { expected, actual } => write!(
+                f,
+                "invalid vector length: expected {} bytes but got {}",
+                expected, actual
+            ),
+            Error::VectorLengthOutOfBounds { min, max, actual } => write!(
+                f,
+                "vector length of {} bytes is outside of {}..{}",
+                actual, min, max
+            ),
+            Error::InvalidWriteLength(e) => write!(f, "invalid write length: {}", e),
+            Error::InvalidInput => f.write_str("invalid input"),
+            Error::DecodingError(e) => write!(f, "decoding error: {}", e),
+            Error::EndOfStream => f.write_str("unexpected end of stream"),
+            Error::LimitExceeded { limit, max, actual } => write!(
+                f,
+                "decode limit exceeded: {} of {} is more than {}",
+                limit, actual, max
+            ),
+            Error::UnknownEnumValue { value } => write!(f, "unknown enum value {}", value),
+            Error::InvalidOptionTag { tag } => write!(f, "invalid option tag {}", tag),
+            Error::InvalidBool { value } => write!(f, "invalid bool value {}", value),
+            Error::InvalidUtf8 { valid_up_to } => write!(
+                f,
+                "invalid UTF-8 in string after {} valid bytes",
+                valid_up_to
+            ),
+            Error::NonMinimalVarInt { value } => {
+                write!(
+                    f,
+                    "variable-length integer {} isn't minimally encoded",
+                    value
+                )
+            }
+            Error::TrailingData { remaining } => {
+                write!(f, "{} bytes of trailing data after the value", remaining)
+            }
+            Error::Context {
+                offset,
+                path,
+                error,
+            } => write!(f, "{} at byte {} in `{}`", error, offset, path),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        match e.kind() {
+            std::io::ErrorKind::UnexpectedEof => Self::EndOfStream,
+            _ => Self::DecodingError(alloc::format!("io error: {:?}", e)),
+        }
+    }
+}
+
+impl From<core::array::TryFromSliceError> for Error {
+    fn from(_: core::array::TryFromSliceError) -> Self {
+        Self::InvalidInput
+    }
+}
+
+impl From<core::num::TryFromIntError> for Error {
+    fn from(_e: core::num::TryFromIntError) -> Self {
+        Self::InvalidInput
+    }
+}
+
+impl From<core::convert::Infallible> for Error {
+    fn from(e: core::convert::Infallible) -> Self {
+        match e {}
+    }
+}
+
+/// The path through the types to a value that failed to decode, e.g.
+/// `Welcome.secrets[3].encrypted_group_secrets`.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct Path {
+    root: Option<&'static str>,
+    // The segments are stored from the innermost to the outermost.
+    segments: Vec<PathSegment>,
+}
+
+impl Path {
+    /// Get the name of the outermost derived type, if known.
+    pub fn root(&self) -> Option<&'static str> {
+        self.root
+    }
+
+    /// Get an iterator over the segments of the path, from the outermost to
+    /// the innermost.
+    pub fn segments(&self) -> impl Iterator<Item = &PathSegment> {
+        self.segments.iter().rev()
+    }
+}
+
+impl Display for Path {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(root) = self.root {
+            f.write_str(root)?;
+        }
+        for (i, segment) in self.segments().enumerate() {
+            match segment {
+                PathSegment::Field(field) if i == 0 && self.root.is_none() => f.write_str(field)?,
+                PathSegment::Field(field) => write!(f, ".{}", field)?,
+                PathSegment::Index(index) => write!(f, "[{}]", index)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A segment of a [`Path`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum PathSegment {
+    /// A named or positional field of a struct or tuple.
+    Field(&'static str),
+
+    /// An element of a vector.
+    Index(usize),
+}
@@ -0,0 +1,507 @@
+//! The `Reader` and `Writer` abstractions used by the codec.
+//!
+//! With the `std` feature these are implemented for every [`std::io::Read`]
+//! and [`std::io::Write`].
+//! Without `std` they are implemented for `&[u8]` and `Vec<u8>`.
+//! [`VecWriter`] writes to a `Vec<u8>` and patches the length prefixes of
+//! vectors after writing their elements.
+//! [`VectoredWriter`] collects the output as a list of slices for vectored IO.
+
+use alloc::vec::Vec;
+
+use crate::{DecodeBudget, Error};
+
+/// The maximum number of bytes of a byte vector that are read at once.
+///
+/// Byte vectors are read in chunks such that a bogus length prefix doesn't
+/// allocate more memory than the input provides.
+pub(crate) const READ_CHUNK_LEN: usize = 4096;
+
+/// Get the first `len` bytes of `out` to serialize a value into.
+///
+/// Returns [`Error::InvalidWriteLength`] if `out` is too short.
+#[inline]
+pub fn slice_prefix(out: &mut [u8], len: usize) -> Result<&mut [u8], Error> {
+    let available = out.len();
+    out.get_mut(..len).ok_or_else(|| {
+        Error::InvalidWriteLength(alloc::format!(
+            "{} bytes should be written but the buffer only holds {}",
+            len,
+            available
+        ))
+    })
+}
+
+/// A [`Writer`] that writes into a byte slice.
+pub(crate) struct SliceWriter<'a> {
+    pub(crate) out: &'a mut [u8],
+    pub(crate) written: usize,
+}
+
+impl<'a> SliceWriter<'a> {
+    pub(crate) fn new(out: &'a mut [u8]) -> Self {
+        Self { out, written: 0 }
+    }
+}
+
+impl<'a> Writer for SliceWriter<'a> {
+    #[inline]
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        let available = self.out.len() - self.written;
+        let out = self.out.get_mut(self.written..self.written + bytes.len());
+        let out = out.ok_or_else(|| {
+            Error::InvalidWriteLength(alloc::format!(
+                "{} bytes should be written but the buffer only holds {}",
+                bytes.len(),
+                available
+            ))
+        })?;
+        out.copy_from_slice(bytes);
+        self.written += bytes.len();
+        Ok(())
+    }
+
+    #[inline]
+    fn position(&self) -> Option<usize> {
+        Some(self.written)
+    }
+
+    #[inline]
+    fn patch_bytes(&mut self, position: usize, bytes: &[u8]) -> Result<(), Error> {
+        patch(&mut self.out[..self.written], position, bytes)
+    }
+}
+
+/// A [`Writer`] that appends to a byte vector.
+///
+/// In contrast to writing to a `Vec<u8>` directly, vectors reserve their
+/// length prefix, write their elements, and then patch the prefix.
+/// This avoids computing the length of every nested vector before
+/// serializing it.
+/// [`Serialize::tls_serialize_detached`](crate::Serialize::tls_serialize_detached)
+/// uses this writer.
+///
+/// ```
+/// use tls_codec::{Serialize, TlsVecU16, TlsVecU8, VecWriter};
+///
+/// let v = TlsVecU16::from(vec![TlsVecU8::<u8>::from_slice(&[1, 2]), TlsVecU8::from_slice(&[3])]);
+/// let mut writer = VecWriter::default();
+/// v.tls_serialize(&mut writer).unwrap();
+/// assert_eq!(vec![0u8, 5, 2, 1, 2, 1, 3], writer.0);
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct VecWriter(pub Vec<u8>);
+
+impl Writer for VecWriter {
+    #[inline]
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        self.0.extend_from_slice(bytes);
+        Ok(())
+    }
+
+    #[inline]
+    fn position(&self) -> Option<usize> {
+        Some(self.0.len())
+    }
+
+    #[inline]
+    fn patch_bytes(&mut self, position: usize, bytes: &[u8]) -> Result<(), Error> {
+        patch(&mut self.0, position, bytes)
+    }
+}
+
+/// The default for [`VectoredWriter::min_borrow_len`].
+const DEFAULT_MIN_BORROW_LEN: usize = 256;
+
+/// A [`Writer`] that collects a serialization as a list of slices for
+/// vectored IO, e.g. with [`std::io::Write::write_vectored`].
+///
+/// Bytes written with [`Writer::write_bytes`] are copied into a scratch
+/// buffer.
+/// Byte vectors and byte slices that are serialized with
+/// [`Serialize::tls_serialize_vectored`](crate::Serialize::tls_serialize_vectored)
+/// reference their content instead if it is at least
+/// [`VectoredWriter::min_borrow_len`] bytes long.
+///
+/// ```
+/// use tls_codec::{Serialize, TlsByteVecU32, VectoredWriter};
+///
+/// let payload = (7u16, TlsByteVecU32::from(vec![1u8; 1024]));
+/// let mut writer = VectoredWriter::new();
+/// payload.tls_serialize_vectored(&mut writer).unwrap();
+///
+/// let slices: Vec<&[u8]> = writer.slices().collect();
+/// assert_eq!(2, slices.len());
+/// assert_eq!(&[0, 7, 0, 0, 4, 0], slices[0]);
+/// assert!(core::ptr::eq(payload.1.as_slice(), slices[1]));
+/// ```
+#[derive(Debug, Clone)]
+pub struct VectoredWriter<'a> {
+    pub(crate) scratch: Vec<u8>,
+    // The slices of the output in order.
+    segments: Vec<Segment<'a>>,
+    min_borrow_len: usize,
+}
+
+/// A slice of the output of a [`VectoredWriter`].
+#[derive(Debug, Clone)]
+enum Segment<'a> {
+    /// The range `start..end` of the scratch buffer.
+    Scratch(usize, usize),
+    /// Borrowed bytes.
+    Borrowed(&'a [u8]),
+}
+
+impl<'a> Default for VectoredWriter<'a> {
+    fn default() -> Self {
+        Self {
+            scratch: Vec::new(),
+            segments: Vec::new(),
+            min_borrow_len: DEFAULT_MIN_BORROW_LEN,
+        }
+    }
+}
+
+impl<'a> VectoredWriter<'a> {
+    /// Create a new, empty writer.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only reference byte vectors that are at least `len` bytes long, and
+    /// copy shorter ones into the scratch buffer.
+    pub fn with_min_borrow_len(mut self, len: usize) -> Self {
+        self.min_borrow_len = len;
+        self
+    }
+
+    /// Get the minimum length of byte vectors that are referenced instead of
+    /// copied.
+    pub fn min_borrow_len(&self) -> usize {
+        self.min_borrow_len
+    }
+
+    /// Write `bytes` by reference if they are at least
+    /// [`VectoredWriter::min_borrow_len`] bytes long, and copy them
+    /// otherwise.
+    pub fn write_borrowed(&mut self, bytes: &'a [u8]) {
+        if bytes.len() < self.min_borrow_len {
+            self.write_scratch(bytes);
+        } else {
+            self.segments.push(Segment::Borrowed(bytes));
+        }
+    }
+
+    /// Get the total number of bytes written.
+    pub fn len(&self) -> usize {
+        self.slices().map(<[u8]>::len).sum()
+    }
+
+    /// Check if nothing was written.
+    pub fn is_empty(&self) -> bool {
+        self.slices().all(<[u8]>::is_empty)
+    }
+
+    /// Get an iterator over the slices of the output in order.
+    pub fn slices(&self) -> impl Iterator<Item = &[u8]> {
+        self.segments.iter().map(move |segment| match segment {
+            Segment::Scratch(start, end) => &self.scratch[*start..*end],
+            Segment::Borrowed(bytes) => *bytes,
+        })
+    }
+
+    /// Get the slices of the output in order for
+    /// [`std::io::Write::write_vectored`].
+    #[cfg(feature = "std")]
+    pub fn io_slices(&self) -> Vec<std::io::IoSlice<'_>> {
+        self.slices().map(std::io::IoSlice::new).collect()
+    }
+
+    /// Copy the output into a single byte vector.
+    pub fn to_vec(&self) -> Vec<u8> {
+        self.slices().flatten().copied().collect()
+    }
+
+    fn write_scratch(&mut self, bytes: &[u8]) {
+        let start = self.scratch.len();
+        self.scratch.extend_from_slice(bytes);
+        let end = self.scratch.len();
+        match self.segments.last_mut() {
+            Some(Segment::Scratch(_, last_end)) if *last_end == start => *last_end = end,
+            _ => self.segments.push(Segment::Scratch(start, end)),
+        }
+    }
+}
+
+impl<'a> Writer for VectoredWriter<'a> {
+    #[inline]
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        self.write_scratch(bytes);
+        Ok(())
+    }
+
+    /// The position in the scratch buffer.
+    #[inline]
+    fn position(&self) -> Option<usize> {
+        Some(self.scratch.len())
+    }
+
+    #[inline]
+    fn patch_bytes(&mut self, position: usize, bytes: &[u8]) -> Result<(), Error> {
+        patch(&mut self.scratch, position, bytes)
+    }
+}
+
+/// Overwrite the `written` bytes at `position` with `bytes`.
+#[inline]
+pub(crate) fn patch(written: &mut [u8], position: usize, bytes: &[u8]) -> Result<(), Error> {
+    let len = written.len();
+    written
+        .get_mut(position..position + bytes.len())
+        .ok_or_else(|| {
+            Error::EncodingError(alloc::format!(
+                "can't patch {} bytes at {} after {} bytes were written",
+                bytes.len(),
+                position,
+                len
+            ))
+        })?
+        .copy_from_slice(bytes);
+    Ok(())
+}
+
+/// A source of bytes to deserialize from.
+pub trait Reader {
+    /// Read exactly `buf.len()` bytes into `buf`.
+    ///
+    /// Returns [`Error::EndOfStream`] if not enough bytes are available.
+    fn read_bytes(&mut self, buf: &mut [u8]) -> Result<(), Error>;
+
+    /// Get the [`DecodeBudget`] that limits decoding from this reader.
+    ///
+    /// The default implementation returns `None`, i.e. decoding is not
+    /// limited.
+    /// See [`LimitedReader`](crate::LimitedReader) for a reader that enforces
+    /// [`DecodeLimits`](crate::DecodeLimits).
+    #[inline]
+    fn budget(&mut self) -> Option<&mut DecodeBudget> {
+        None
+    }
+
+    /// Note that the next `len` bytes are the content of a vector, which may
+    /// be read in pieces.
+    ///
+    /// This allows decoding partial input to report how many bytes of the
+    /// vector are missing instead of only those of the piece that ran out.
+    #[doc(hidden)]
+    #[inline]
+    fn expect_bytes(&mut self, _len: usize) {}
+
+    /// Read `len` bytes into a [`bytes::Bytes`].
+    ///
+    /// The default implementation copies the bytes.
+    /// [`BytesIo`](crate::BytesIo) returns a slice of the underlying
+    /// [`bytes::Bytes`] without copying.
+    #[cfg(feature = "bytes")]
+    fn read_shared_bytes(&mut self, len: usize) -> Result<bytes::Bytes, Error> {
+        let mut buf = Vec::new();
+        while buf.len() < len {
+            let start = buf.len();
+            buf.resize(core::cmp::min(len, start + READ_CHUNK_LEN), 0);
+            self.read_bytes(&mut buf[start..])?;
+        }
+        Ok(buf.into())
+    }
+}
+
+/// A sink for serialized bytes.
+pub trait Writer {
+    /// Write all of `bytes`.
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), Error>;
+
+    /// Get the position of the next byte written, if this writer can
+    /// overwrite bytes that were already written with
+    /// [`Writer::patch_bytes`].
+    ///
+    /// Vectors use this to reserve their length prefix, write their elements,
+    /// and then patch the prefix instead of computing the length of their
+    /// elements up front.
+    /// For writers that return `None`, e.g. any `std::io::Write`, vectors
+    /// compute the length of their elements and stream them after the prefix
+    /// without buffering.
+    /// The default implementation returns `None`.
+    #[inline]
+    fn position(&self) -> Option<usize> {
+        None
+    }
+
+    /// Overwrite the bytes at `position`, as returned by
+    /// [`Writer::position`], with `bytes`.
+    ///
+    /// The default implementation returns an error.
+    fn patch_bytes(&mut self, _position: usize, _bytes: &[u8]) -> Result<(), Error> {
+        Err(Error::EncodingError(
+            "the writer can't patch written bytes".into(),
+        ))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: std::io::Read + ?Sized> Reader for R {
+    #[inline]
+    fn read_bytes(&mut self, buf: &mut [u8]) -> Result<(), Error> {
+        self.read_exact(buf).map_err(Error::from)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W: std::io::Write + ?Sized> Writer for W {
+    #[inline]
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        self.write_all(bytes)
+            .map_err(|e| Error::EncodingError(alloc::format!("io error: {:?}", e)))
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl Reader for &[u8] {
+    #[inline]
+    fn read_bytes(&mut self, buf: &mut [u8]) -> Result<(), Error> {
+        if buf.len() > self.len() {
+            return Err(Error::EndOfStream);
+        }
+        let (head, tail) = self.split_at(buf.len());
+        buf.copy_from_slice(head);
+        *self = tail;
+        Ok(())
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl<R: Reader + ?Sized> Reader for &mut R {
+    #[inline]
+    fn read_bytes(&mut self, buf: &mut [u8]) -> Result<(), Error> {
+        (**self).read_bytes(buf)
+    }
+
+    #[inline]
+    fn budget(&mut self) -> Option<&mut DecodeBudget> {
+        (**self).budget()
+    }
+
+    #[inline]
+    fn expect_bytes(&mut self, len: usize) {
+        (**self).expect_bytes(len)
+    }
+
+    #[cfg(feature = "bytes")]
+    #[inline]
+    fn read_shared_bytes(&mut self, len: usize) -> Result<bytes::Bytes, Error> {
+        (**self).read_shared_bytes(len)
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl Writer for Vec<u8> {
+    #[inline]
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        self.extend_from_slice(bytes);
+        Ok(())
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl<W: Writer + ?Sized> Writer for &mut W {
+    #[inline]
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        (**self).write_bytes(bytes)
+    }
+
+    #[inline]
+    fn position(&self) -> Option<usize> {
+        (**self).position()
+    }
+
+    #[inline]
+    fn patch_bytes(&mut self, position: usize, bytes: &[u8]) -> Result<(), Error> {
+        (**self).patch_bytes(position, bytes)
+    }
+}
+
+/// A sized [`Writer`] that forwards to a `dyn Writer`, such that generic
+/// serialization functions can write to it.
+pub(crate) struct DynWriter<'a>(pub(crate) &'a mut dyn Writer);
+
+impl<'a> Writer for DynWriter<'a> {
+    #[inline]
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        self.0.write_bytes(bytes)
+    }
+
+    #[inline]
+    fn position(&self) -> Option<usize> {
+        self.0.position()
+    }
+
+    #[inline]
+    fn patch_bytes(&mut self, position: usize, bytes: &[u8]) -> Result<(), Error> {
+        self.0.patch_bytes(position, bytes)
+    }
+}
+
+/// An adapter that implements [`Reader`] for [`bytes::Buf`] and [`Writer`]
+/// for [`bytes::BufMut`].
+///
+/// Reading a `TlsBytes*` vector from a `BytesIo<bytes::Bytes>` slices the
+/// buffer instead of copying it.
+///
+/// ```
+/// use bytes::{Bytes, BytesMut};
+/// use tls_codec::{BytesIo, Deserialize, Serialize, TlsBytesU16};
+///
+/// let mut buf = BytesIo(BytesMut::new());
+/// TlsBytesU16::from_slice(&[1, 2, 3]).tls_serialize(&mut buf).unwrap();
+///
+/// let mut reader = BytesIo(buf.0.freeze());
+/// let v = TlsBytesU16::tls_deserialize(&mut reader).unwrap();
+/// assert_eq!(&[1, 2, 3], v.as_slice());
+/// ```
+#[cfg(feature = "bytes")]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct BytesIo<T>(pub T);
+
+#[cfg(feature = "bytes")]
+impl<T: bytes::Buf> Reader for BytesIo<T> {
+    #[inline]
+    fn read_bytes(&mut self, buf: &mut [u8]) -> Result<(), Error> {
+        if buf.len() > self.0.remaining() {
+            return Err(Error::EndOfStream);
+        }
+        self.0.copy_to_slice(buf);
+        Ok(())
+    }
+
+    #[inline]
+    fn read_shared_bytes(&mut self, len: usize) -> Result<bytes::Bytes, Error> {
+        if len > self.0.remaining() {
+            return Err(Error::EndOfStream);
+        }
+        Ok(self.0.copy_to_bytes(len))
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl<T: bytes::BufMut> Writer for BytesIo<T> {
+    #[inline]
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        if bytes.len() > self.0.remaining_mut() {
+            return Err(Error::EncodingError(alloc::format!(
+                "buffer has room for {} bytes but {} were written",
+                self.0.remaining_mut(),
+                bytes.len()
+            )));
+        }
+        self.0.put_slice(bytes);
+        Ok(())
+    }
+}
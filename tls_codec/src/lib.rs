@@ -3,27 +3,171 @@
 //! This crate implements the TLS codec as defined in [RFC 8446](https://tools.ietf.org/html/rfc8446)
 //! as well as some extensions required by MLS.
 //!
-//! With the feature `derive` `TlsSerialize`, `TlsDeserialize`, and `TlsSize`
-//! can be derived.
-//! Note that `TlsSerialize` and `TlsDeserialize` both require `TlsSize`.
+//! With the feature `derive` `TlsSerialize`, `TlsDeserialize`,
+//! `TlsDeserializeBytes`, `TlsSerializeWithContext`,
+//! `TlsDeserializeWithContext`, and `TlsSize` can be derived.
+//! Note that all of them require `TlsSize`.
 //!
 //! This crate provides the following data structures that implement TLS
 //! serialization/deserialization
-//! * `u8`, `u16`, `u32`, `u64`
-//! * `TlsVecU8`, `TlsVecU16`, `TlsVecU32`
-//! * `SecretTlsVecU8`, `SecretTlsVecU16`, `SecretTlsVecU32`
+//! * `u8`, `u16`, `u32`, `u64`, `u128`, and the signed `i8` to `i128`
+//!   in big-endian two's complement
+//! * `bool` as a `uint8` that must be `0` or `1`
+//! * `()` and `PhantomData<T>` with an empty encoding, e.g. for marker fields
+//! * [`UintN`] for integers of 1 to 8 bytes, e.g. the `uint24` [`U24`] and
+//!   the `uint48` [`U48`]
+//! * `VarInt` for the variable-length integers of QUIC and MLS, and the
+//!   `VLBytes` and `TlsVarVec` vectors with a `VarInt` length prefix
+//! * [`TlsVec<T, L>`](TlsVec) for vectors of `T` with a length prefix `L`,
+//!   which is any type that implements [`LengthPrefix`], i.e. `u8`, `u16`,
+//!   `U24`, `u32`, and `VarInt`. The vectors below are aliases of `TlsVec`,
+//!   `SecretTlsVec`, `TlsSlice`, `TlsByteSlice`, and `TlsByteVec` with one of
+//!   these prefixes.
+//! * [`Bounded<L, MIN, MAX>`](Bounded) as length prefix for vectors with
+//!   bounds in the presentation language, e.g.
+//!   `TlsVec<u16, Bounded<u16, 2, 65534>>` for `uint16 data<2..2^16-2>`.
+//!   Encoding and decoding reject lengths outside of the bounds with
+//!   [`Error::VectorLengthOutOfBounds`].
+//! * `TlsVecU8`, `TlsVecU16`, `TlsVecU24`, `TlsVecU32`
+//! * `SecretTlsVecU8`, `SecretTlsVecU16`, `SecretTlsVecU24`, `SecretTlsVecU32`
 //!   The same as the `TlsVec*` versions but it implements zeroize, requiring
 //!   the elements to implement zeroize as well.
-//! * `TlsSliceU8`, `TlsSliceU16`, `TlsSliceU32` are lightweight wrapper for slices
-//!   that allow to serialize them without having to create a `TlsVec*`.
-//! * `TlsByteSliceU8`, `TlsByteSliceU16`, `TlsByteSliceU32`, and
-//!   `TlsByteVecU8`, `TlsByteVecU16`, `TlsByteVecU32`
+//! * `TlsSliceU8`, `TlsSliceU16`, `TlsSliceU24`, `TlsSliceU32` are lightweight
+//!   wrappers for slices that allow to serialize them without having to create
+//!   a `TlsVec*`.
+//! * `TlsByteSliceU8`, `TlsByteSliceU16`, `TlsByteSliceU24`, `TlsByteSliceU32`,
+//!   and `TlsByteVecU8`, `TlsByteVecU16`, `TlsByteVecU24`, `TlsByteVecU32`
 //!   are provided with optimized implementations for byte vectors.
-//! * `[u8; l]`, for `l ∈ [1..128]`
+//! * `[T; N]` for any length `N` where `T` implements the codec, e.g.
+//!   `[u8; 32]` or `[u16; 4]`
 //! * Serialize for `Option<T>` where `T: Serialize`
 //! * Deserialize for `Option<T>` where `T: Deserialize`
-//! * Serialize for `(T, U)` and `(T, U, V)` where `T, U, V` implement Serialize`
-//! * Deserialize for `(T, U)` and `(T, U, V)` where `T, U, V` implement Deserialize`
+//! * Tuples with up to 12 elements that implement the codec, encoded one
+//!   after another
+//! * `&T` and `&mut T` for serialization, and `Box<T>`, `Rc<T>`, `Arc<T>`, and
+//!   `Cow<T>` encoded like `T`, e.g. for recursive structures. A decoded
+//!   `Cow` is always owned.
+//! * `Vec<u8>`, `[u8]`, `String`, and `str` with a variable-length integer
+//!   prefix like `VLBytes`. Decoded strings must be valid UTF-8. `Box`, `Rc`, and
+//!   `Arc` of `[u8]` and `str` are supported as well.
+//!
+//! [`DynSerialize`] is an object-safe version of [`Serialize`].
+//! `Box<dyn DynSerialize>` can be put into vectors to serialize values of
+//! different types, e.g. a list of extensions.
+//!
+//! Types with the same serialized length for all values, i.e. the integers,
+//! arrays, and tuples of them, implement [`FixedSize`].
+//! Deriving `TlsSize` implements it for enums, and for structs of fixed-size
+//! fields that are marked with `#[tls_codec(fixed_size)]`.
+//!
+//! The types above that implement `Deserialize` also implement `DeserializeBytes`,
+//! which decodes from a byte slice and returns the remaining bytes.
+//! The byte slices `TlsByteSliceU8`, `TlsByteSliceU16`, `TlsByteSliceU24`, and
+//! `TlsByteSliceU32` only implement `DeserializeBytes` and borrow from the input.
+//!
+//! ## Errors
+//!
+//! Decoding errors in derived structs, vectors, tuples, and the values of
+//! options are wrapped in an [`Error::Context`] that holds the byte offset of
+//! the failing value in the input and the path to it, e.g.
+//! `Welcome.secrets[3].encrypted_group_secrets`.
+//! Errors of tags, i.e. of options and derived enums, are returned as is.
+//! [`Error::kind`] returns the underlying error.
+//! [`Error::alert_description`] maps an error to the TLS alert to send, e.g.
+//! `illegal_parameter` for unknown enum values and `decode_error` for
+//! malformed lengths.
+//!
+//! Protocols like MLS reject messages with trailing bytes.
+//! [`Deserialize::tls_deserialize_exact`] and
+//! [`DeserializeBytes::tls_deserialize_exact_bytes`] decode a value that
+//! takes up all of the input and fail with [`Error::TrailingData`] otherwise.
+//!
+//! ## Decode limits
+//!
+//! The length prefixes of vectors are read from the input.
+//! To decode untrusted input without allocating arbitrary amounts of memory,
+//! use [`Deserialize::tls_deserialize_with_limits`] or a [`LimitedReader`]
+//! with [`DecodeLimits`].
+//! The limits apply to all vectors, including those in derived types.
+//! The allocations of `DeserializeBytes` are bounded by the length of its
+//! input.
+//!
+//! ## Partial input
+//!
+//! [`Deserialize::tls_deserialize_partial`] decodes from input that may be
+//! incomplete, e.g. a message that arrives in fragments.
+//! It returns [`Decoded::NeedMore`] with a lower bound of the number of missing
+//! bytes instead of failing, such that decoding can be retried when more bytes
+//! arrive.
+//! Once the length prefix of a vector is read, the bound includes all of its
+//! missing content.
+//!
+//! ```
+//! use tls_codec::{Decoded, Deserialize, TlsVecU16};
+//!
+//! let mut received = vec![0u8, 4, 0, 1];
+//! assert_eq!(
+//!     Decoded::NeedMore { at_least: 2 },
+//!     TlsVecU16::<u16>::tls_deserialize_partial(&received).unwrap()
+//! );
+//! received.extend_from_slice(&[0, 2, 9]);
+//! let (v, consumed) = TlsVecU16::<u16>::tls_deserialize_partial(&received)
+//!     .unwrap()
+//!     .complete()
+//!     .unwrap();
+//! assert_eq!(&[1, 2], v.as_slice());
+//! assert_eq!(6, consumed);
+//! ```
+//!
+//! ## Context
+//!
+//! Some values are encoded differently depending on out-of-band state, e.g.
+//! the negotiated protocol version or the hash length of the cipher suite.
+//! [`SerializeWithContext`] and [`DeserializeWithContext`] take this state as
+//! a `context`.
+//! Vectors, `Option`, and tuples pass it on to their elements, while
+//! integers, arrays, and byte vectors ignore it.
+//! Vector elements still have to implement [`Serialize`].
+//!
+//! The `TlsSerializeWithContext` and `TlsDeserializeWithContext` derives pass
+//! the context on to all fields.
+//! They are generic over the context unless it's set with
+//! `#[tls_codec(context = Type)]`.
+//! A field with `#[tls_codec(with_context = expr)]` gets `&(expr)` as its
+//! context instead, where `expr` can use `context`, and a field with
+//! `#[tls_codec(without_context)]` uses [`Serialize`] and [`Deserialize`].
+//! Enums ignore the context and also need to derive `TlsDeserialize`.
+//!
+//! ## `bytes`
+//!
+//! With the `bytes` feature `BytesIo` adapts `bytes::Buf` and
+//! `bytes::BufMut` to [`Reader`] and [`Writer`].
+//! The `TlsBytesU8`, `TlsBytesU16`, `TlsBytesU24`, and `TlsBytesU32` byte
+//! vectors are backed by `bytes::Bytes`.
+//! Decoding them from a `BytesIo<Bytes>` slices the buffer without copying.
+//!
+//! ## Async
+//!
+//! With the `async` feature the `AsyncSerialize` and `AsyncDeserialize`
+//! traits write to an `AsyncWriter` and read from an `AsyncReader`.
+//! They are implemented for all types above and derived along with
+//! `TlsSerialize` and `TlsDeserialize`.
+//! The `futures-io` and `tokio` features enable the `async` feature and add
+//! the `FuturesIo` and `TokioIo` adapters for the IO traits of these
+//! crates.
+//!
+//! ## `no_std`
+//!
+//! The crate is `no_std` compatible but requires an allocator.
+//! The `std` feature is enabled by default.
+//! Disable the default features and enable `alloc` to use the crate without
+//! `std`.
+//! Building without either of them fails with a compile error.
+//!
+//! Serialization writes to a [`Writer`] and deserialization reads from a
+//! [`Reader`].
+//! With `std` they are implemented for all [`std::io::Read`] and
+//! [`std::io::Write`] types, and without it for `&[u8]` and `Vec<u8>`.
 //!
 //! ## Usage
 //!
@@ -37,62 +181,72 @@
 //! let v = TlsVecU8::<u8>::tls_deserialize(&mut b).expect("Unable to tls_deserialize");
 //! assert_eq!(&[77, 88, 1, 99], v.as_slice());
 //! ```
+//!
+//! Byte vectors can be decoded without copying with `DeserializeBytes`.
+//!
+//! ```
+//! use tls_codec::{DeserializeBytes, TlsByteSliceU8};
+//! let b = &[4u8, 77, 88, 1, 99, 5] as &[u8];
+//!
+//! let (v, remainder) = TlsByteSliceU8::tls_deserialize_bytes(b).expect("Unable to tls_deserialize");
+//! assert_eq!(&[77, 88, 1, 99], v.0);
+//! assert_eq!(&[5], remainder);
+//! ```
 
-use std::{
-    fmt::Display,
-    io::{Read, Write},
-};
+#![no_std]
+
+#[cfg(not(feature = "alloc"))]
+compile_error!("tls_codec requires the `alloc` feature.");
+
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+use alloc::{boxed::Box, format, vec::Vec};
+#[cfg(feature = "async")]
+use core::future::Future;
 
 mod arrays;
+#[cfg(feature = "async")]
+mod async_io;
+mod error;
+mod io;
+mod limits;
+mod partial;
+mod pointers;
 mod primitives;
 mod tls_vec;
+mod uint;
+mod varint;
+#[cfg(feature = "futures-io")]
+pub use async_io::FuturesIo;
+#[cfg(feature = "tokio")]
+pub use async_io::TokioIo;
+#[cfg(feature = "async")]
+pub use async_io::{AsyncReader, AsyncWriter};
+pub use error::{AlertDescription, Error, Path, PathSegment};
+#[cfg(feature = "bytes")]
+pub use io::BytesIo;
+pub use io::{Reader, VecWriter, VectoredWriter, Writer};
+pub use limits::{DecodeBudget, DecodeLimit, DecodeLimits, LimitedReader};
+pub use partial::Decoded;
 pub use tls_vec::{
-    SecretTlsVecU16, SecretTlsVecU32, SecretTlsVecU8, TlsByteSliceU16, TlsByteSliceU32,
-    TlsByteSliceU8, TlsByteVecU16, TlsByteVecU32, TlsByteVecU8, TlsSliceU16, TlsSliceU32,
-    TlsSliceU8, TlsVecU16, TlsVecU32, TlsVecU8,
+    Bounded, LengthPrefix, SecretTlsVec, SecretTlsVecU16, SecretTlsVecU24, SecretTlsVecU32,
+    SecretTlsVecU8, TlsByteSlice, TlsByteSliceU16, TlsByteSliceU24, TlsByteSliceU32,
+    TlsByteSliceU8, TlsByteVec, TlsByteVecU16, TlsByteVecU24, TlsByteVecU32, TlsByteVecU8,
+    TlsSlice, TlsSliceU16, TlsSliceU24, TlsSliceU32, TlsSliceU8, TlsVec, TlsVecU16, TlsVecU24,
+    TlsVecU32, TlsVecU8, VectorNames,
 };
+#[cfg(feature = "bytes")]
+pub use tls_vec::{TlsBytes, TlsBytesU16, TlsBytesU24, TlsBytesU32, TlsBytesU8};
+pub use uint::{UintN, U24, U48};
+pub use varint::{TlsVarVec, VLBytes, VarInt};
 
 #[cfg(feature = "derive")]
-pub use tls_codec_derive::{TlsDeserialize, TlsSerialize, TlsSize};
-
-/// Errors that are thrown by this crate.
-#[derive(Debug, PartialEq, Clone)]
-pub enum Error {
-    /// An error occurred during encoding.
-    EncodingError(String),
-
-    /// The length of a vector is invalid.
-    InvalidVectorLength,
-
-    /// Error writing everything out.
-    InvalidWriteLength(String),
-
-    /// Invalid input when trying to decode a primitive integer.
-    InvalidInput,
-
-    /// An error occurred during decoding.
-    DecodingError(String),
-
-    /// Reached the end of a byte stream.
-    EndOfStream,
-}
-
-impl std::error::Error for Error {}
-
-impl Display for Error {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_fmt(format_args!("{:?}", self))
-    }
-}
-
-impl From<std::io::Error> for Error {
-    fn from(e: std::io::Error) -> Self {
-        match e.kind() {
-            std::io::ErrorKind::UnexpectedEof => Self::EndOfStream,
-            _ => Self::DecodingError(format!("io error: {:?}", e)),
-        }
-    }
-}
+pub use tls_codec_derive::{
+    TlsDeserialize, TlsDeserializeBytes, TlsDeserializeWithContext, TlsSerialize,
+    TlsSerializeWithContext, TlsSize,
+};
 
 /// The `Size` trait needs to be implemented by any struct that should be
 /// efficiently serialized.
@@ -100,6 +254,50 @@ impl From<std::io::Error> for Error {
 /// memory.
 pub trait Size {
     fn tls_serialized_len(&self) -> usize;
+
+    /// The serialized length like [`Size::tls_serialized_len`], but fails if
+    /// computing it overflows `usize`.
+    ///
+    /// Serialization uses this length.
+    /// The default implementation returns [`Size::tls_serialized_len`], which
+    /// is correct for types that don't add up the lengths of other values.
+    #[inline]
+    fn tls_serialized_len_checked(&self) -> Result<usize, Error> {
+        Ok(self.tls_serialized_len())
+    }
+
+    /// The serialized length of every value of this type, if it is the same
+    /// for all of them.
+    ///
+    /// This is `Some(TLS_LEN)` for types that implement [`FixedSize`].
+    /// Vectors use it to check their length prefix before decoding the
+    /// elements.
+    /// The default implementation returns `None`.
+    #[inline]
+    fn tls_fixed_len() -> Option<usize>
+    where
+        Self: Sized,
+    {
+        None
+    }
+}
+
+/// The `FixedSize` trait is implemented by types whose serialized length is
+/// the same for all values, e.g. `u16`, `[u8; 32]`, or a struct of such
+/// fields that derives `TlsSize`.
+///
+/// The length is known at compile time, e.g. to size a buffer on the stack.
+///
+/// ```
+/// use tls_codec::{FixedSize, Serialize};
+///
+/// let mut buf = [0u8; <(u16, [u8; 4])>::TLS_LEN];
+/// (1u16, [2u8; 4]).tls_serialize_to_slice(&mut buf).unwrap();
+/// assert_eq!([0, 1, 2, 2, 2, 2], buf);
+/// ```
+pub trait FixedSize: Size {
+    /// The serialized length in bytes.
+    const TLS_LEN: usize;
 }
 
 /// The `Serialize` trait provides functions to serialize a struct or enum.
@@ -110,12 +308,101 @@ pub trait Size {
 pub trait Serialize: Size {
     /// Serialize `self` and write it to the `writer`.
     /// The function returns the number of bytes written to `writer`.
-    fn tls_serialize<W: Write>(&self, writer: &mut W) -> Result<usize, Error>;
+    fn tls_serialize<W: Writer>(&self, writer: &mut W) -> Result<usize, Error>;
+
+    /// Serialize `self` into the beginning of `out`.
+    /// The function returns the number of bytes written to `out`.
+    ///
+    /// The length of `out` is checked against
+    /// [`Size::tls_serialized_len_checked`] before anything is written.
+    /// Returns [`Error::InvalidWriteLength`] if `out` is too short.
+    fn tls_serialize_to_slice(&self, out: &mut [u8]) -> Result<usize, Error> {
+        let out = io::slice_prefix(out, self.tls_serialized_len_checked()?)?;
+        self.tls_serialize_to_checked_slice(out)
+    }
+
+    /// Serialize `self` into the beginning of `out`, which the caller checked
+    /// to be at least as long as the serialization.
+    ///
+    /// [`Serialize::tls_serialize_to_slice`] checks the length once and then
+    /// calls this, such that nested values don't compute their length again.
+    /// Types that write into slices directly implement this.
+    /// The default implementation serializes with [`Serialize::tls_serialize`].
+    #[doc(hidden)]
+    #[inline]
+    fn tls_serialize_to_checked_slice(&self, out: &mut [u8]) -> Result<usize, Error> {
+        self.tls_serialize(&mut io::SliceWriter::new(out))
+    }
+
+    /// Serialize `self` into a [`VectoredWriter`] for vectored IO.
+    /// The function returns the number of bytes written to `writer`.
+    ///
+    /// Byte vectors and byte slices reference their content instead of
+    /// copying it, see [`VectoredWriter::write_borrowed`].
+    /// The default implementation copies the serialization of `self` with
+    /// [`Serialize::tls_serialize`].
+    fn tls_serialize_vectored<'a>(
+        &'a self,
+        writer: &mut VectoredWriter<'a>,
+    ) -> Result<usize, Error> {
+        self.tls_serialize(writer)
+    }
+
+    /// Serialize the `elements` of an array one after another.
+    ///
+    /// This is used by the implementation for arrays and allows `u8` to
+    /// write all of them at once.
+    #[doc(hidden)]
+    #[inline]
+    fn tls_serialize_elements<W: Writer>(elements: &[Self], writer: &mut W) -> Result<usize, Error>
+    where
+        Self: Sized,
+    {
+        elements
+            .iter()
+            .try_fold(0, |written, e| Ok(written + e.tls_serialize(writer)?))
+    }
+
+    /// Serialize the `elements` of an array one after another into the
+    /// beginning of `out`, which is at least as long as their serialization.
+    ///
+    /// This is used by the implementation for arrays and allows `u8` to copy
+    /// all of them at once.
+    #[doc(hidden)]
+    #[inline]
+    fn tls_serialize_elements_to_slice(elements: &[Self], out: &mut [u8]) -> Result<usize, Error>
+    where
+        Self: Sized,
+    {
+        elements.iter().try_fold(0, |written, e| {
+            Ok(written + e.tls_serialize_to_checked_slice(&mut out[written..])?)
+        })
+    }
+
+    /// Serialize the `elements` of an array one after another into a
+    /// [`VectoredWriter`].
+    ///
+    /// This is used by the implementation for arrays and allows `u8` to
+    /// reference all of them at once.
+    #[doc(hidden)]
+    #[inline]
+    fn tls_serialize_elements_vectored<'a>(
+        elements: &'a [Self],
+        writer: &mut VectoredWriter<'a>,
+    ) -> Result<usize, Error>
+    where
+        Self: Sized,
+    {
+        elements.iter().try_fold(0, |written, e| {
+            Ok(written + e.tls_serialize_vectored(writer)?)
+        })
+    }
 
     /// Serialize `self` and return it as a byte vector.
     fn tls_serialize_detached(&self) -> Result<Vec<u8>, Error> {
-        let mut buffer = Vec::with_capacity(self.tls_serialized_len());
-        let written = self.tls_serialize(&mut buffer)?;
+        let mut writer = VecWriter(Vec::with_capacity(self.tls_serialized_len_checked()?));
+        let written = self.tls_serialize(&mut writer)?;
+        let buffer = writer.0;
         debug_assert_eq!(
             written,
             buffer.len(),
@@ -138,11 +425,373 @@ pub trait Serialize: Size {
 /// The `Deserialize` trait defines functions to deserialize a byte slice to a
 /// struct or enum.
 pub trait Deserialize: Size {
-    /// This function deserializes the `bytes` from the provided a [`std::io::Read`]
+    /// This function deserializes the `bytes` from the provided a [`Reader`]
     /// and returns the populated struct.
     ///
     /// In order to get the amount of bytes read, use [`TlsSize::serialized_len`].
-    fn tls_deserialize<R: Read>(bytes: &mut R) -> Result<Self, Error>
+    fn tls_deserialize<R: Reader>(bytes: &mut R) -> Result<Self, Error>
     where
         Self: Sized;
+
+    /// This function deserializes a value that takes up all of `bytes`.
+    ///
+    /// Returns [`Error::TrailingData`] if bytes are left after the value.
+    fn tls_deserialize_exact(bytes: &[u8]) -> Result<Self, Error>
+    where
+        Self: Sized,
+    {
+        let mut reader = bytes;
+        let value = Self::tls_deserialize(&mut reader)?;
+        if !reader.is_empty() {
+            return Err(Error::TrailingData {
+                remaining: reader.len(),
+            });
+        }
+        Ok(value)
+    }
+
+    /// This function deserializes the `bytes` from the provided [`Reader`]
+    /// like [`Deserialize::tls_deserialize`] but fails if decoding exceeds
+    /// the `limits`.
+    ///
+    /// Use this to decode untrusted input.
+    fn tls_deserialize_with_limits<R: Reader>(
+        bytes: &mut R,
+        limits: DecodeLimits,
+    ) -> Result<Self, Error>
+    where
+        Self: Sized,
+    {
+        Self::tls_deserialize(&mut LimitedReader::new(bytes, limits))
+    }
+
+    /// This function deserializes a value from the beginning of `bytes`,
+    /// which may hold only part of it.
+    ///
+    /// It returns [`Decoded::NeedMore`] if `bytes` ends before the value is
+    /// complete, with a lower bound of the number of missing bytes.
+    /// Other errors are returned as they are.
+    /// Nothing is consumed in this case and decoding can be retried once more
+    /// bytes are available.
+    fn tls_deserialize_partial(bytes: &[u8]) -> Result<Decoded<Self>, Error>
+    where
+        Self: Sized,
+    {
+        partial::deserialize_partial(bytes, None)
+    }
+
+    /// This function deserializes a value from the beginning of `bytes` like
+    /// [`Deserialize::tls_deserialize_partial`] but fails if decoding exceeds
+    /// the `limits`.
+    fn tls_deserialize_partial_with_limits(
+        bytes: &[u8],
+        limits: DecodeLimits,
+    ) -> Result<Decoded<Self>, Error>
+    where
+        Self: Sized,
+    {
+        partial::deserialize_partial(bytes, Some(limits))
+    }
+
+    /// Deserialize the `N` elements of an array.
+    ///
+    /// This is used by the implementation for arrays and allows `u8` to
+    /// read all of them at once.
+    #[doc(hidden)]
+    #[inline]
+    fn tls_deserialize_array<R: Reader, const N: usize>(bytes: &mut R) -> Result<[Self; N], Error>
+    where
+        Self: Sized,
+    {
+        arrays::deserialize_array(|| Self::tls_deserialize(bytes))
+    }
+}
+
+/// The `DeserializeBytes` trait defines functions to deserialize a struct or
+/// enum directly from a byte slice.
+///
+/// In contrast to [`Deserialize`] the deserialized value may borrow from the
+/// input.
+/// This allows, for example, to decode a [`TlsByteSliceU16`] without copying
+/// the bytes.
+pub trait DeserializeBytes<'a>: Size + Sized {
+    /// This function deserializes a value from the beginning of `bytes` and
+    /// returns it together with the remaining bytes.
+    fn tls_deserialize_bytes(bytes: &'a [u8]) -> Result<(Self, &'a [u8]), Error>;
+
+    /// This function deserializes a value that takes up all of `bytes`.
+    ///
+    /// Returns [`Error::TrailingData`] if bytes are left after the value.
+    fn tls_deserialize_exact_bytes(bytes: &'a [u8]) -> Result<Self, Error> {
+        let (value, remainder) = Self::tls_deserialize_bytes(bytes)?;
+        if !remainder.is_empty() {
+            return Err(Error::TrailingData {
+                remaining: remainder.len(),
+            });
+        }
+        Ok(value)
+    }
+
+    /// Deserialize the `N` elements of an array from the beginning of
+    /// `bytes`.
+    ///
+    /// This is used by the implementation for arrays and allows `u8` to
+    /// split off all of them at once.
+    #[doc(hidden)]
+    #[inline]
+    fn tls_deserialize_bytes_array<const N: usize>(
+        bytes: &'a [u8],
+    ) -> Result<([Self; N], &'a [u8]), Error> {
+        let mut remainder = bytes;
+        let elements = arrays::deserialize_array(|| {
+            let (element, rest) = Self::tls_deserialize_bytes(remainder)?;
+            remainder = rest;
+            Ok(element)
+        })?;
+        Ok((elements, remainder))
+    }
+}
+
+/// The `SerializeWithContext` trait provides functions to serialize a struct
+/// or enum whose encoding depends on out-of-band state, the `context`, e.g.
+/// the negotiated protocol version.
+///
+/// Vectors, `Option`, tuples, and types that derive `TlsSerializeWithContext`
+/// pass the context on to their elements and fields.
+/// Integers, arrays, and byte vectors implement it for every context and
+/// ignore it.
+pub trait SerializeWithContext<C: ?Sized>: Size {
+    /// Serialize `self` in the `context` and write it to the `writer`.
+    /// The function returns the number of bytes written to `writer`.
+    ///
+    /// The context may change how a value is encoded but not its length,
+    /// which must be [`Size::tls_serialized_len`] such that vectors can
+    /// compute their length prefix.
+    fn tls_serialize_with_context<W: Writer>(
+        &self,
+        writer: &mut W,
+        context: &C,
+    ) -> Result<usize, Error>;
+
+    /// Serialize `self` in the `context` and return it as a byte vector.
+    fn tls_serialize_detached_with_context(&self, context: &C) -> Result<Vec<u8>, Error> {
+        let mut writer = VecWriter(Vec::with_capacity(self.tls_serialized_len_checked()?));
+        let written = self.tls_serialize_with_context(&mut writer, context)?;
+        let buffer = writer.0;
+        debug_assert_eq!(
+            written,
+            buffer.len(),
+            "Expected that {} bytes were written but the output holds {} bytes",
+            written,
+            buffer.len()
+        );
+        if written != buffer.len() {
+            Err(Error::EncodingError(format!(
+                "Expected that {} bytes were written but the output holds {} bytes",
+                written,
+                buffer.len()
+            )))
+        } else {
+            Ok(buffer)
+        }
+    }
+}
+
+/// The `DeserializeWithContext` trait defines functions to deserialize a
+/// struct or enum whose encoding depends on out-of-band state, the `context`,
+/// e.g. the negotiated protocol version.
+///
+/// Vectors, `Option`, tuples, and types that derive
+/// `TlsDeserializeWithContext` pass the context on to their elements and
+/// fields.
+/// Integers, arrays, and byte vectors implement it for every context and
+/// ignore it.
+///
+/// ```
+/// use tls_codec::{DeserializeWithContext, Error, Reader, Size};
+///
+/// /// A hash whose length depends on the cipher suite.
+/// struct HashValue(Vec<u8>);
+///
+/// impl Size for HashValue {
+///     fn tls_serialized_len(&self) -> usize {
+///         self.0.len()
+///     }
+/// }
+///
+/// impl DeserializeWithContext<usize> for HashValue {
+///     fn tls_deserialize_with_context<R: Reader>(
+///         bytes: &mut R,
+///         hash_len: &usize,
+///     ) -> Result<Self, Error> {
+///         let mut hash = vec![0; *hash_len];
+///         bytes.read_bytes(&mut hash)?;
+///         Ok(Self(hash))
+///     }
+/// }
+///
+/// let mut b = &[1u8, 2, 3, 4] as &[u8];
+/// let hash = HashValue::tls_deserialize_with_context(&mut b, &3).unwrap();
+/// assert_eq!(vec![1, 2, 3], hash.0);
+/// // Integers ignore the context.
+/// assert_eq!(4, u8::tls_deserialize_with_context(&mut b, &3).unwrap());
+/// ```
+pub trait DeserializeWithContext<C: ?Sized>: Size {
+    /// This function deserializes the `bytes` in the `context` from the
+    /// provided [`Reader`] and returns the populated struct.
+    fn tls_deserialize_with_context<R: Reader>(bytes: &mut R, context: &C) -> Result<Self, Error>
+    where
+        Self: Sized;
+}
+
+/// The `DynSerialize` trait is an object-safe version of [`Serialize`].
+///
+/// It's implemented for all types that implement [`Serialize`], such that
+/// values of different types can be kept as `Box<dyn DynSerialize>`.
+/// `Box<dyn DynSerialize>` implements [`Serialize`] and [`Size`] and can be
+/// put into vectors.
+///
+/// ```
+/// use tls_codec::{DynSerialize, Serialize, TlsByteVecU8, TlsVecU16};
+///
+/// let extensions: Vec<Box<dyn DynSerialize>> = vec![
+///     Box::new(1u8),
+///     Box::new(TlsByteVecU8::from_slice(&[2, 3])),
+///     Box::new(Some(4u16)),
+/// ];
+/// let extensions = TlsVecU16::from(extensions);
+/// assert_eq!(
+///     vec![0u8, 7, 1, 2, 2, 3, 1, 0, 4],
+///     extensions.tls_serialize_detached().unwrap()
+/// );
+/// ```
+pub trait DynSerialize: Size {
+    /// Serialize `self` and write it to the `writer`.
+    /// The function returns the number of bytes written to `writer`.
+    fn tls_serialize_dyn(&self, writer: &mut dyn Writer) -> Result<usize, Error>;
+}
+
+impl<T: Serialize + ?Sized> DynSerialize for T {
+    #[inline]
+    fn tls_serialize_dyn(&self, writer: &mut dyn Writer) -> Result<usize, Error> {
+        self.tls_serialize(&mut io::DynWriter(writer))
+    }
+}
+
+impl Size for Box<dyn DynSerialize> {
+    #[inline]
+    fn tls_serialized_len(&self) -> usize {
+        (**self).tls_serialized_len()
+    }
+
+    #[inline]
+    fn tls_serialized_len_checked(&self) -> Result<usize, Error> {
+        (**self).tls_serialized_len_checked()
+    }
+}
+
+impl Serialize for Box<dyn DynSerialize> {
+    #[inline]
+    fn tls_serialize<W: Writer>(&self, writer: &mut W) -> Result<usize, Error> {
+        (**self).tls_serialize_dyn(writer)
+    }
+}
+
+/// The `AsyncSerialize` trait is the async counterpart of [`Serialize`].
+///
+/// It writes to an [`AsyncWriter`].
+/// The returned futures are `Send`, such that generic code can serialize in
+/// a spawned task, e.g. with `tokio::spawn`.
+/// Implementations can use `async fn`.
+#[cfg(feature = "async")]
+pub trait AsyncSerialize: Size + Sync {
+    /// Serialize `self` and write it to the `writer`.
+    /// The function returns the number of bytes written to `writer`.
+    fn tls_serialize_async<W: AsyncWriter>(
+        &self,
+        writer: &mut W,
+    ) -> impl Future<Output = Result<usize, Error>> + Send;
+
+    /// Serialize the `elements` of an array one after another.
+    ///
+    /// This is used by the implementation for arrays and allows `u8` to
+    /// write all of them at once.
+    #[doc(hidden)]
+    fn tls_serialize_elements_async<W: AsyncWriter>(
+        elements: &[Self],
+        writer: &mut W,
+    ) -> impl Future<Output = Result<usize, Error>> + Send
+    where
+        Self: Sized,
+    {
+        async move {
+            let mut written = 0;
+            for e in elements {
+                written += e.tls_serialize_async(writer).await?;
+            }
+            Ok(written)
+        }
+    }
+}
+
+/// The `AsyncDeserialize` trait is the async counterpart of [`Deserialize`].
+///
+/// It reads from an [`AsyncReader`] and only reads as many bytes as the
+/// value takes up, i.e. vectors read exactly the number of bytes given by
+/// their length prefix.
+/// The returned futures are `Send` like those of [`AsyncSerialize`].
+#[cfg(feature = "async")]
+pub trait AsyncDeserialize: Size + Send {
+    /// This function deserializes the `bytes` from the provided
+    /// [`AsyncReader`] and returns the populated struct.
+    fn tls_deserialize_async<R: AsyncReader>(
+        bytes: &mut R,
+    ) -> impl Future<Output = Result<Self, Error>> + Send
+    where
+        Self: Sized;
+
+    /// This function deserializes the `bytes` from the provided
+    /// [`AsyncReader`] like [`AsyncDeserialize::tls_deserialize_async`] but
+    /// fails if decoding exceeds the `limits`.
+    fn tls_deserialize_async_with_limits<R: AsyncReader>(
+        bytes: &mut R,
+        limits: DecodeLimits,
+    ) -> impl Future<Output = Result<Self, Error>> + Send
+    where
+        Self: Sized,
+    {
+        async move { Self::tls_deserialize_async(&mut LimitedReader::new(bytes, limits)).await }
+    }
+
+    /// Deserialize the `N` elements of an array.
+    ///
+    /// This is used by the implementation for arrays and allows `u8` to
+    /// read all of them at once.
+    #[doc(hidden)]
+    fn tls_deserialize_array_async<R: AsyncReader, const N: usize>(
+        bytes: &mut R,
+    ) -> impl Future<Output = Result<[Self; N], Error>> + Send
+    where
+        Self: Sized,
+    {
+        async move {
+            let mut elements: [Option<Self>; N] = core::array::from_fn(|_| None);
+            let mut offset = 0;
+            for (index, element) in elements.iter_mut().enumerate() {
+                let value = Self::tls_deserialize_async(bytes)
+                    .await
+                    .map_err(|e| e.with_index(offset, index))?;
+                offset += value.tls_serialized_len();
+                *element = Some(value);
+            }
+            Ok(arrays::unwrap_elements(elements))
+        }
+    }
+}
+
+#[doc(hidden)]
+pub mod __private {
+    //! Re-exports used by the code generated in `tls_codec_derive`.
+    //! This is not part of the public API.
+    pub use crate::error::add_len;
 }
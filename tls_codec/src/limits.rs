@@ -0,0 +1,273 @@
+//! Limits for decoding untrusted input.
+//!
+//! A [`LimitedReader`] wraps a [`Reader`] and enforces [`DecodeLimits`] while
+//! decoding vectors, including vectors nested in derived structs and enums.
+
+use core::fmt::{self, Display};
+
+use crate::{Error, Reader};
+
+/// Limits for decoding untrusted input.
+///
+/// By default nothing is limited.
+/// Use the `with_*` functions to set limits.
+///
+/// ```
+/// use tls_codec::{DecodeLimits, Deserialize, TlsByteVecU32};
+///
+/// let limits = DecodeLimits::default().with_max_vector_len(1 << 16);
+/// let mut b = &[0xffu8, 0xff, 0xff, 0xff, 1, 2, 3] as &[u8];
+/// assert!(TlsByteVecU32::tls_deserialize_with_limits(&mut b, limits).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecodeLimits {
+    max_total_allocation: usize,
+    max_vector_len: usize,
+    max_element_count: usize,
+}
+
+impl Default for DecodeLimits {
+    fn default() -> Self {
+        Self {
+            max_total_allocation: usize::MAX,
+            max_vector_len: usize::MAX,
+            max_element_count: usize::MAX,
+        }
+    }
+}
+
+impl DecodeLimits {
+    /// Limit the number of bytes allocated for the content of all vectors.
+    ///
+    /// Elements of generic vectors count with their in-memory size.
+    pub fn with_max_total_allocation(mut self, max: usize) -> Self {
+        self.max_total_allocation = max;
+        self
+    }
+
+    /// Limit the length in bytes of a single vector as given by its length
+    /// prefix.
+    pub fn with_max_vector_len(mut self, max: usize) -> Self {
+        self.max_vector_len = max;
+        self
+    }
+
+    /// Limit the number of elements in all generic vectors.
+    pub fn with_max_element_count(mut self, max: usize) -> Self {
+        self.max_element_count = max;
+        self
+    }
+
+    /// Get the maximum number of bytes allocated for the content of all
+    /// vectors.
+    pub fn max_total_allocation(&self) -> usize {
+        self.max_total_allocation
+    }
+
+    /// Get the maximum length in bytes of a single vector.
+    pub fn max_vector_len(&self) -> usize {
+        self.max_vector_len
+    }
+
+    /// Get the maximum number of elements in all generic vectors.
+    pub fn max_element_count(&self) -> usize {
+        self.max_element_count
+    }
+}
+
+/// A limit in [`DecodeLimits`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DecodeLimit {
+    /// [`DecodeLimits::max_total_allocation`]
+    TotalAllocation,
+
+    /// [`DecodeLimits::max_vector_len`]
+    VectorLength,
+
+    /// [`DecodeLimits::max_element_count`]
+    ElementCount,
+}
+
+impl Display for DecodeLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            DecodeLimit::TotalAllocation => "total allocation",
+            DecodeLimit::VectorLength => "vector length",
+            DecodeLimit::ElementCount => "element count",
+        })
+    }
+}
+
+/// The [`DecodeLimits`] and how much of them was used while decoding.
+///
+/// Vectors get the budget from [`Reader::budget`] and check it before
+/// allocating.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodeBudget {
+    limits: DecodeLimits,
+    allocated: usize,
+    elements: usize,
+}
+
+impl DecodeBudget {
+    /// Create a new budget with nothing used yet.
+    pub fn new(limits: DecodeLimits) -> Self {
+        Self {
+            limits,
+            allocated: 0,
+            elements: 0,
+        }
+    }
+
+    /// Get the limits of this budget.
+    pub fn limits(&self) -> &DecodeLimits {
+        &self.limits
+    }
+
+    /// Get the number of bytes allocated so far.
+    pub fn allocated(&self) -> usize {
+        self.allocated
+    }
+
+    /// Get the number of vector elements decoded so far.
+    pub fn elements(&self) -> usize {
+        self.elements
+    }
+
+    /// Check the length in bytes of a vector before decoding it.
+    pub fn check_vector_len(&self, len: usize) -> Result<(), Error> {
+        check(DecodeLimit::VectorLength, len, self.limits.max_vector_len)
+    }
+
+    /// Use `bytes` of the total allocation.
+    pub fn allocate(&mut self, bytes: usize) -> Result<(), Error> {
+        let allocated = self.allocated.saturating_add(bytes);
+        check(
+            DecodeLimit::TotalAllocation,
+            allocated,
+            self.limits.max_total_allocation,
+        )?;
+        self.allocated = allocated;
+        Ok(())
+    }
+
+    /// Use `count` of the element count.
+    pub fn add_elements(&mut self, count: usize) -> Result<(), Error> {
+        let elements = self.elements.saturating_add(count);
+        check(
+            DecodeLimit::ElementCount,
+            elements,
+            self.limits.max_element_count,
+        )?;
+        self.elements = elements;
+        Ok(())
+    }
+}
+
+fn check(limit: DecodeLimit, actual: usize, max: usize) -> Result<(), Error> {
+    if actual > max {
+        return Err(Error::LimitExceeded { limit, max, actual });
+    }
+    Ok(())
+}
+
+/// A [`Reader`] that enforces [`DecodeLimits`].
+///
+/// ```
+/// use tls_codec::{DecodeLimits, Deserialize, LimitedReader, TlsVecU16};
+///
+/// let mut b = &[0u8, 4, 0, 1, 0, 2] as &[u8];
+/// let mut reader = LimitedReader::new(&mut b, DecodeLimits::default().with_max_element_count(1));
+/// assert!(TlsVecU16::<u16>::tls_deserialize(&mut reader).is_err());
+/// ```
+#[derive(Debug)]
+pub struct LimitedReader<'a, R: ?Sized> {
+    pub(crate) reader: &'a mut R,
+    pub(crate) budget: DecodeBudget,
+}
+
+impl<'a, R: ?Sized> LimitedReader<'a, R> {
+    /// Create a new reader that reads from `reader` within `limits`.
+    pub fn new(reader: &'a mut R, limits: DecodeLimits) -> Self {
+        Self {
+            reader,
+            budget: DecodeBudget::new(limits),
+        }
+    }
+}
+
+impl<'a, R: Reader + ?Sized> Reader for LimitedReader<'a, R> {
+    #[inline]
+    fn read_bytes(&mut self, buf: &mut [u8]) -> Result<(), Error> {
+        self.reader.read_bytes(buf)
+    }
+
+    #[inline]
+    fn budget(&mut self) -> Option<&mut DecodeBudget> {
+        Some(&mut self.budget)
+    }
+
+    #[inline]
+    fn expect_bytes(&mut self, len: usize) {
+        self.reader.expect_bytes(len)
+    }
+
+    #[cfg(feature = "bytes")]
+    #[inline]
+    fn read_shared_bytes(&mut self, len: usize) -> Result<bytes::Bytes, Error> {
+        self.reader.read_shared_bytes(len)
+    }
+}
+
+/// With the `std` feature `&mut R` is a [`Reader`] through
+/// [`std::io::Read`], which doesn't forward the budget.
+#[cfg(feature = "std")]
+impl<'a, 'b, R: Reader + ?Sized> Reader for &'b mut LimitedReader<'a, R> {
+    #[inline]
+    fn read_bytes(&mut self, buf: &mut [u8]) -> Result<(), Error> {
+        (**self).read_bytes(buf)
+    }
+
+    #[inline]
+    fn budget(&mut self) -> Option<&mut DecodeBudget> {
+        (**self).budget()
+    }
+
+    #[inline]
+    fn expect_bytes(&mut self, len: usize) {
+        (**self).expect_bytes(len)
+    }
+
+    #[cfg(feature = "bytes")]
+    #[inline]
+    fn read_shared_bytes(&mut self, len: usize) -> Result<bytes::Bytes, Error> {
+        (**self).read_shared_bytes(len)
+    }
+}
+
+/// Check the length in bytes of a vector against the `budget` of a reader,
+/// if any.
+#[inline]
+pub(crate) fn check_vector_len(budget: Option<&mut DecodeBudget>, len: usize) -> Result<(), Error> {
+    match budget {
+        Some(budget) => budget.check_vector_len(len),
+        None => Ok(()),
+    }
+}
+
+/// Use `bytes` of the total allocation and `elements` of the element count
+/// of the `budget` of a reader, if any.
+#[inline]
+pub(crate) fn allocate(
+    budget: Option<&mut DecodeBudget>,
+    bytes: usize,
+    elements: usize,
+) -> Result<(), Error> {
+    match budget {
+        Some(budget) => {
+            budget.allocate(bytes)?;
+            budget.add_elements(elements)
+        }
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,111 @@
+//! Decoding from partially received input.
+//!
+//! [`Deserialize::tls_deserialize_partial`](crate::Deserialize::tls_deserialize_partial)
+//! decodes a value from the beginning of a byte slice.
+//! If the slice ends before the value is complete, it reports a lower bound of
+//! the number of missing bytes instead of failing, such that decoding can be
+//! retried when more bytes arrive.
+
+use crate::{DecodeLimits, Deserialize, Error, LimitedReader, Reader};
+
+/// The result of decoding from partially received input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Decoded<T> {
+    /// The value was decoded from the first `consumed` bytes of the input.
+    Complete {
+        /// The decoded value.
+        value: T,
+        /// The number of bytes the value took up.
+        consumed: usize,
+    },
+
+    /// The input ended before the value was complete.
+    ///
+    /// Decoding needs `at_least` more bytes to make progress.
+    /// This is a lower bound: it accounts for the read that ran out of input
+    /// and the content of the vectors whose length prefix was read, but
+    /// values with variable length may need more than that.
+    NeedMore {
+        /// A lower bound of the number of bytes missing.
+        at_least: usize,
+    },
+}
+
+impl<T> Decoded<T> {
+    /// Get the value and the number of bytes it took up, or `None` if more
+    /// bytes are needed.
+    pub fn complete(self) -> Option<(T, usize)> {
+        match self {
+            Decoded::Complete { value, consumed } => Some((value, consumed)),
+            Decoded::NeedMore { .. } => None,
+        }
+    }
+}
+
+/// A [`Reader`] over a byte slice that records how many bytes were missing
+/// when it runs out of input.
+struct PartialReader<'a> {
+    input: &'a [u8],
+    read: usize,
+    missing: usize,
+    /// The end of the farthest vector content whose length prefix was read.
+    expected_end: usize,
+}
+
+impl<'a> Reader for PartialReader<'a> {
+    #[inline]
+    fn read_bytes(&mut self, buf: &mut [u8]) -> Result<(), Error> {
+        let available = self.input.len() - self.read;
+        if buf.len() > available {
+            self.missing = buf.len() - available;
+            return Err(Error::EndOfStream);
+        }
+        buf.copy_from_slice(&self.input[self.read..self.read + buf.len()]);
+        self.read += buf.len();
+        Ok(())
+    }
+
+    #[inline]
+    fn expect_bytes(&mut self, len: usize) {
+        let end = self.read.saturating_add(len);
+        self.expected_end = core::cmp::max(self.expected_end, end);
+    }
+}
+
+impl<'a> PartialReader<'a> {
+    /// Get the number of bytes missing after the input ran out, which
+    /// includes the rest of the vectors that are being read in pieces.
+    fn at_least(&self) -> usize {
+        let missing_content = self.expected_end.saturating_sub(self.input.len());
+        core::cmp::max(self.missing, missing_content)
+    }
+}
+
+/// Decode a `T` from the beginning of `input`, within `limits` if given.
+pub(crate) fn deserialize_partial<T: Deserialize>(
+    input: &[u8],
+    limits: Option<DecodeLimits>,
+) -> Result<Decoded<T>, Error> {
+    let mut reader = PartialReader {
+        input,
+        read: 0,
+        missing: 0,
+        expected_end: 0,
+    };
+    let result = match limits {
+        Some(limits) => T::tls_deserialize(&mut LimitedReader::new(&mut reader, limits)),
+        None => T::tls_deserialize(&mut reader),
+    };
+    match result {
+        Ok(value) => Ok(Decoded::Complete {
+            value,
+            consumed: reader.read,
+        }),
+        // Only the end of the input is caused by the missing bytes, other
+        // errors are returned as they are.
+        Err(e) if reader.missing > 0 && *e.kind() == Error::EndOfStream => Ok(Decoded::NeedMore {
+            at_least: reader.at_least(),
+        }),
+        Err(e) => Err(e),
+    }
+}
@@ -0,0 +1,421 @@
+//! Codec implementations for references, the smart pointers `Box`, `Rc`, and
+//! `Arc`, and `Cow`.
+//!
+//! They are encoded like the value they point to.
+//! The smart pointers are implemented for sized values, and for the unsized
+//! byte slices `[u8]` and strings `str`, e.g. `Box<[u8]>` or `Arc<str>`.
+//! Smart pointers allow to encode recursive and shared structures without
+//! wrapper types.
+//! Their async codecs box the future of the value, such that recursive types
+//! can be encoded and decoded asynchronously as well.
+//! `Rc` has no async codec because its futures can't be `Send`.
+
+#[cfg(target_has_atomic = "ptr")]
+use alloc::sync::Arc;
+use alloc::{borrow::Cow, borrow::ToOwned, boxed::Box, rc::Rc, string::String, vec::Vec};
+
+#[cfg(feature = "async")]
+use core::{future::Future, pin::Pin};
+
+#[cfg(feature = "async")]
+use super::{AsyncDeserialize, AsyncReader, AsyncSerialize, AsyncWriter};
+use super::{
+    Deserialize, DeserializeBytes, DeserializeWithContext, Error, FixedSize, Reader, Serialize,
+    SerializeWithContext, Size, VectoredWriter, Writer,
+};
+
+macro_rules! impl_reference {
+    ($($reference:tt)*) => {
+        impl<'r, T: Size + ?Sized> Size for $($reference)* T {
+            #[inline]
+            fn tls_serialized_len(&self) -> usize {
+                (**self).tls_serialized_len()
+            }
+
+            #[inline]
+            fn tls_serialized_len_checked(&self) -> Result<usize, Error> {
+                (**self).tls_serialized_len_checked()
+            }
+        }
+
+        impl<'r, T: Serialize + ?Sized> Serialize for $($reference)* T {
+            #[inline]
+            fn tls_serialize<W: Writer>(&self, writer: &mut W) -> Result<usize, Error> {
+                (**self).tls_serialize(writer)
+            }
+
+            #[inline]
+            fn tls_serialize_to_checked_slice(&self, out: &mut [u8]) -> Result<usize, Error> {
+                (**self).tls_serialize_to_checked_slice(out)
+            }
+
+            #[inline]
+            fn tls_serialize_vectored<'a>(
+                &'a self,
+                writer: &mut VectoredWriter<'a>,
+            ) -> Result<usize, Error> {
+                (**self).tls_serialize_vectored(writer)
+            }
+        }
+
+        impl<'r, C: ?Sized, T: SerializeWithContext<C> + ?Sized> SerializeWithContext<C>
+            for $($reference)* T
+        {
+            #[inline]
+            fn tls_serialize_with_context<W: Writer>(
+                &self,
+                writer: &mut W,
+                context: &C,
+            ) -> Result<usize, Error> {
+                (**self).tls_serialize_with_context(writer, context)
+            }
+        }
+
+        #[cfg(feature = "async")]
+        impl<'r, T: AsyncSerialize + ?Sized> AsyncSerialize for $($reference)* T {
+            async fn tls_serialize_async<W: AsyncWriter>(
+                &self,
+                writer: &mut W,
+            ) -> Result<usize, Error> {
+                (**self).tls_serialize_async(writer).await
+            }
+        }
+    };
+}
+
+impl_reference!(&'r);
+impl_reference!(&'r mut);
+
+macro_rules! impl_pointer {
+    ($pointer:ident) => {
+        impl<T: Size> Size for $pointer<T> {
+            #[inline]
+            fn tls_serialized_len(&self) -> usize {
+                (**self).tls_serialized_len()
+            }
+
+            #[inline]
+            fn tls_serialized_len_checked(&self) -> Result<usize, Error> {
+                (**self).tls_serialized_len_checked()
+            }
+
+            #[inline]
+            fn tls_fixed_len() -> Option<usize> {
+                T::tls_fixed_len()
+            }
+        }
+
+        impl<T: FixedSize> FixedSize for $pointer<T> {
+            const TLS_LEN: usize = T::TLS_LEN;
+        }
+
+        impl<T: Serialize> Serialize for $pointer<T> {
+            #[inline]
+            fn tls_serialize<W: Writer>(&self, writer: &mut W) -> Result<usize, Error> {
+                (**self).tls_serialize(writer)
+            }
+
+            #[inline]
+            fn tls_serialize_to_checked_slice(&self, out: &mut [u8]) -> Result<usize, Error> {
+                (**self).tls_serialize_to_checked_slice(out)
+            }
+
+            #[inline]
+            fn tls_serialize_vectored<'a>(
+                &'a self,
+                writer: &mut VectoredWriter<'a>,
+            ) -> Result<usize, Error> {
+                (**self).tls_serialize_vectored(writer)
+            }
+        }
+
+        impl<T: Deserialize> Deserialize for $pointer<T> {
+            #[inline]
+            fn tls_deserialize<R: Reader>(bytes: &mut R) -> Result<Self, Error> {
+                T::tls_deserialize(bytes).map($pointer::new)
+            }
+        }
+
+        impl<'a, T: DeserializeBytes<'a>> DeserializeBytes<'a> for $pointer<T> {
+            #[inline]
+            fn tls_deserialize_bytes(bytes: &'a [u8]) -> Result<(Self, &'a [u8]), Error> {
+                let (value, remainder) = T::tls_deserialize_bytes(bytes)?;
+                Ok(($pointer::new(value), remainder))
+            }
+        }
+
+        impl<C: ?Sized, T: SerializeWithContext<C>> SerializeWithContext<C> for $pointer<T> {
+            #[inline]
+            fn tls_serialize_with_context<W: Writer>(
+                &self,
+                writer: &mut W,
+                context: &C,
+            ) -> Result<usize, Error> {
+                (**self).tls_serialize_with_context(writer, context)
+            }
+        }
+
+        impl<C: ?Sized, T: DeserializeWithContext<C>> DeserializeWithContext<C> for $pointer<T> {
+            #[inline]
+            fn tls_deserialize_with_context<R: Reader>(
+                bytes: &mut R,
+                context: &C,
+            ) -> Result<Self, Error> {
+                T::tls_deserialize_with_context(bytes, context).map($pointer::new)
+            }
+        }
+    };
+}
+
+impl_pointer!(Box);
+impl_pointer!(Rc);
+#[cfg(target_has_atomic = "ptr")]
+impl_pointer!(Arc);
+
+/// Implement the codec for the smart pointer `$pointer` to the unsized
+/// `$unsized`, which is decoded as `$owned`.
+///
+/// The generic implementations above forward [`Size::tls_fixed_len`] and
+/// therefore need a sized value.
+macro_rules! impl_unsized_pointer {
+    ($pointer:ident, $unsized:ty, $owned:ty) => {
+        impl Size for $pointer<$unsized> {
+            #[inline]
+            fn tls_serialized_len(&self) -> usize {
+                (**self).tls_serialized_len()
+            }
+
+            #[inline]
+            fn tls_serialized_len_checked(&self) -> Result<usize, Error> {
+                (**self).tls_serialized_len_checked()
+            }
+        }
+
+        impl Serialize for $pointer<$unsized> {
+            #[inline]
+            fn tls_serialize<W: Writer>(&self, writer: &mut W) -> Result<usize, Error> {
+                (**self).tls_serialize(writer)
+            }
+
+            #[inline]
+            fn tls_serialize_to_checked_slice(&self, out: &mut [u8]) -> Result<usize, Error> {
+                (**self).tls_serialize_to_checked_slice(out)
+            }
+
+            #[inline]
+            fn tls_serialize_vectored<'a>(
+                &'a self,
+                writer: &mut VectoredWriter<'a>,
+            ) -> Result<usize, Error> {
+                (**self).tls_serialize_vectored(writer)
+            }
+        }
+
+        impl Deserialize for $pointer<$unsized> {
+            #[inline]
+            fn tls_deserialize<R: Reader>(bytes: &mut R) -> Result<Self, Error> {
+                <$owned>::tls_deserialize(bytes).map($pointer::from)
+            }
+        }
+
+        impl<'a> DeserializeBytes<'a> for $pointer<$unsized> {
+            #[inline]
+            fn tls_deserialize_bytes(bytes: &'a [u8]) -> Result<(Self, &'a [u8]), Error> {
+                let (value, remainder) = <$owned>::tls_deserialize_bytes(bytes)?;
+                Ok(($pointer::from(value), remainder))
+            }
+        }
+
+        impl<C: ?Sized> SerializeWithContext<C> for $pointer<$unsized> {
+            #[inline]
+            fn tls_serialize_with_context<W: Writer>(
+                &self,
+                writer: &mut W,
+                context: &C,
+            ) -> Result<usize, Error> {
+                (**self).tls_serialize_with_context(writer, context)
+            }
+        }
+
+        impl<C: ?Sized> DeserializeWithContext<C> for $pointer<$unsized> {
+            #[inline]
+            fn tls_deserialize_with_context<R: Reader>(
+                bytes: &mut R,
+                context: &C,
+            ) -> Result<Self, Error> {
+                <$owned>::tls_deserialize_with_context(bytes, context).map($pointer::from)
+            }
+        }
+    };
+}
+
+impl_unsized_pointer!(Box, [u8], Vec<u8>);
+impl_unsized_pointer!(Box, str, String);
+impl_unsized_pointer!(Rc, [u8], Vec<u8>);
+impl_unsized_pointer!(Rc, str, String);
+#[cfg(target_has_atomic = "ptr")]
+impl_unsized_pointer!(Arc, [u8], Vec<u8>);
+#[cfg(target_has_atomic = "ptr")]
+impl_unsized_pointer!(Arc, str, String);
+
+/// Implement the async codec for the smart pointer `$pointer`, which is
+/// [`Send`] and [`Sync`] if the value is `$($bound)*`.
+///
+/// There is none for [`Rc`] because its futures can't be [`Send`].
+#[cfg(feature = "async")]
+type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T, Error>> + Send + 'a>>;
+
+#[cfg(feature = "async")]
+macro_rules! impl_async_pointer {
+    ($pointer:ident $(, $bound:path)*) => {
+        // The futures of the value are boxed because recursive types go
+        // through a pointer, and a recursive future needs indirection.
+        // They are boxed as trait objects such that checking whether the
+        // future is `Send` doesn't need the type of the future, which would
+        // be a cycle for recursive types.
+        impl<T: AsyncSerialize $(+ $bound)*> AsyncSerialize for $pointer<T> {
+            async fn tls_serialize_async<W: AsyncWriter>(
+                &self,
+                writer: &mut W,
+            ) -> Result<usize, Error> {
+                let future: BoxFuture<'_, usize> = Box::pin((**self).tls_serialize_async(writer));
+                future.await
+            }
+        }
+
+        impl<T: AsyncDeserialize $(+ $bound)*> AsyncDeserialize for $pointer<T> {
+            async fn tls_deserialize_async<R: AsyncReader>(bytes: &mut R) -> Result<Self, Error> {
+                let future: BoxFuture<'_, T> = Box::pin(T::tls_deserialize_async(bytes));
+                future.await.map($pointer::new)
+            }
+        }
+
+        impl_async_pointer!(@unsized $pointer, [u8], Vec<u8>);
+        impl_async_pointer!(@unsized $pointer, str, String);
+    };
+    (@unsized $pointer:ident, $unsized:ty, $owned:ty) => {
+        impl AsyncSerialize for $pointer<$unsized> {
+            async fn tls_serialize_async<W: AsyncWriter>(
+                &self,
+                writer: &mut W,
+            ) -> Result<usize, Error> {
+                (**self).tls_serialize_async(writer).await
+            }
+        }
+
+        impl AsyncDeserialize for $pointer<$unsized> {
+            async fn tls_deserialize_async<R: AsyncReader>(bytes: &mut R) -> Result<Self, Error> {
+                <$owned>::tls_deserialize_async(bytes)
+                    .await
+                    .map($pointer::from)
+            }
+        }
+    };
+}
+
+#[cfg(feature = "async")]
+impl_async_pointer!(Box);
+#[cfg(all(feature = "async", target_has_atomic = "ptr"))]
+impl_async_pointer!(Arc, Send, Sync);
+
+// A `Cow` is always decoded into an owned value.
+impl<'c, T: ToOwned + Size + ?Sized> Size for Cow<'c, T> {
+    #[inline]
+    fn tls_serialized_len(&self) -> usize {
+        (**self).tls_serialized_len()
+    }
+
+    #[inline]
+    fn tls_serialized_len_checked(&self) -> Result<usize, Error> {
+        (**self).tls_serialized_len_checked()
+    }
+}
+
+impl<'c, T: ToOwned + Serialize + ?Sized> Serialize for Cow<'c, T> {
+    #[inline]
+    fn tls_serialize<W: Writer>(&self, writer: &mut W) -> Result<usize, Error> {
+        (**self).tls_serialize(writer)
+    }
+
+    #[inline]
+    fn tls_serialize_to_checked_slice(&self, out: &mut [u8]) -> Result<usize, Error> {
+        (**self).tls_serialize_to_checked_slice(out)
+    }
+
+    #[inline]
+    fn tls_serialize_vectored<'a>(
+        &'a self,
+        writer: &mut VectoredWriter<'a>,
+    ) -> Result<usize, Error> {
+        (**self).tls_serialize_vectored(writer)
+    }
+}
+
+impl<'c, T> Deserialize for Cow<'c, T>
+where
+    T: ToOwned + Size + ?Sized,
+    T::Owned: Deserialize,
+{
+    #[inline]
+    fn tls_deserialize<R: Reader>(bytes: &mut R) -> Result<Self, Error> {
+        T::Owned::tls_deserialize(bytes).map(Cow::Owned)
+    }
+}
+
+impl<'a, 'c, T> DeserializeBytes<'a> for Cow<'c, T>
+where
+    T: ToOwned + Size + ?Sized,
+    T::Owned: DeserializeBytes<'a>,
+{
+    #[inline]
+    fn tls_deserialize_bytes(bytes: &'a [u8]) -> Result<(Self, &'a [u8]), Error> {
+        let (value, remainder) = T::Owned::tls_deserialize_bytes(bytes)?;
+        Ok((Cow::Owned(value), remainder))
+    }
+}
+
+impl<'c, C: ?Sized, T: ToOwned + SerializeWithContext<C> + ?Sized> SerializeWithContext<C>
+    for Cow<'c, T>
+{
+    #[inline]
+    fn tls_serialize_with_context<W: Writer>(
+        &self,
+        writer: &mut W,
+        context: &C,
+    ) -> Result<usize, Error> {
+        (**self).tls_serialize_with_context(writer, context)
+    }
+}
+
+impl<'c, C: ?Sized, T> DeserializeWithContext<C> for Cow<'c, T>
+where
+    T: ToOwned + Size + ?Sized,
+    T::Owned: DeserializeWithContext<C>,
+{
+    #[inline]
+    fn tls_deserialize_with_context<R: Reader>(bytes: &mut R, context: &C) -> Result<Self, Error> {
+        T::Owned::tls_deserialize_with_context(bytes, context).map(Cow::Owned)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<'c, T> AsyncSerialize for Cow<'c, T>
+where
+    T: ToOwned + AsyncSerialize + ?Sized,
+    T::Owned: Sync,
+{
+    async fn tls_serialize_async<W: AsyncWriter>(&self, writer: &mut W) -> Result<usize, Error> {
+        (**self).tls_serialize_async(writer).await
+    }
+}
+
+#[cfg(feature = "async")]
+impl<'c, T> AsyncDeserialize for Cow<'c, T>
+where
+    T: ToOwned + Size + Sync + ?Sized,
+    T::Owned: AsyncDeserialize,
+{
+    async fn tls_deserialize_async<R: AsyncReader>(bytes: &mut R) -> Result<Self, Error> {
+        T::Owned::tls_deserialize_async(bytes).await.map(Cow::Owned)
+    }
+}
@@ -1,8 +1,15 @@
-//! Codec implementations for unsigned integer primitives.
+//! Codec implementations for primitives, i.e. integers, `bool`, `()`,
+//! `PhantomData`, `Option`, and tuples with up to 12 elements.
 
-use super::{Deserialize, Error, Serialize, Size};
+#[cfg(feature = "async")]
+use super::{AsyncDeserialize, AsyncReader, AsyncSerialize, AsyncWriter};
+use super::{
+    Deserialize, DeserializeBytes, DeserializeWithContext, Error, FixedSize, Reader, Serialize,
+    SerializeWithContext, Size, VectoredWriter, Writer,
+};
+use crate::{error::add_len, io::slice_prefix};
 
-use std::io::{Read, Write};
+use core::{convert::TryInto, marker::PhantomData};
 
 impl<T: Size> Size for Option<T> {
     #[inline]
@@ -12,18 +19,41 @@ impl<T: Size> Size for Option<T> {
             None => 0,
         }
     }
+
+    #[inline]
+    fn tls_serialized_len_checked(&self) -> Result<usize, Error> {
+        match self {
+            Some(v) => add_len(1, v.tls_serialized_len_checked()?),
+            None => Ok(1),
+        }
+    }
 }
 
 impl<T: Serialize> Serialize for Option<T> {
-    fn tls_serialize<W: Write>(&self, writer: &mut W) -> Result<usize, Error> {
+    fn tls_serialize<W: Writer>(&self, writer: &mut W) -> Result<usize, Error> {
         match self {
             Some(e) => {
-                let written = writer.write(&[1])?;
-                debug_assert_eq!(written, 1);
+                writer.write_bytes(&[1])?;
                 e.tls_serialize(writer).map(|l| l + 1)
             }
             None => {
-                writer.write_all(&[0])?;
+                writer.write_bytes(&[0])?;
+                Ok(1)
+            }
+        }
+    }
+
+    fn tls_serialize_vectored<'a>(
+        &'a self,
+        writer: &mut VectoredWriter<'a>,
+    ) -> Result<usize, Error> {
+        match self {
+            Some(e) => {
+                writer.write_bytes(&[1])?;
+                e.tls_serialize_vectored(writer).map(|l| l + 1)
+            }
+            None => {
+                writer.write_bytes(&[0])?;
                 Ok(1)
             }
         }
@@ -31,37 +61,214 @@ impl<T: Serialize> Serialize for Option<T> {
 }
 
 impl<T: Deserialize> Deserialize for Option<T> {
-    fn tls_deserialize<R: Read>(bytes: &mut R) -> Result<Self, Error> {
+    fn tls_deserialize<R: Reader>(bytes: &mut R) -> Result<Self, Error> {
+        let mut some_or_none = [0u8; 1];
+        bytes.read_bytes(&mut some_or_none)?;
+        match some_or_none[0] {
+            0 => Ok(None),
+            1 => {
+                let element = T::tls_deserialize(bytes).map_err(|e| e.with_offset(1))?;
+                Ok(Some(element))
+            }
+            tag => Err(Error::InvalidOptionTag { tag }),
+        }
+    }
+}
+
+impl<C: ?Sized, T: SerializeWithContext<C>> SerializeWithContext<C> for Option<T> {
+    fn tls_serialize_with_context<W: Writer>(
+        &self,
+        writer: &mut W,
+        context: &C,
+    ) -> Result<usize, Error> {
+        match self {
+            Some(e) => {
+                writer.write_bytes(&[1])?;
+                e.tls_serialize_with_context(writer, context).map(|l| l + 1)
+            }
+            None => {
+                writer.write_bytes(&[0])?;
+                Ok(1)
+            }
+        }
+    }
+}
+
+impl<C: ?Sized, T: DeserializeWithContext<C>> DeserializeWithContext<C> for Option<T> {
+    fn tls_deserialize_with_context<R: Reader>(bytes: &mut R, context: &C) -> Result<Self, Error> {
+        let mut some_or_none = [0u8; 1];
+        bytes.read_bytes(&mut some_or_none)?;
+        match some_or_none[0] {
+            0 => Ok(None),
+            1 => {
+                let element = T::tls_deserialize_with_context(bytes, context)
+                    .map_err(|e| e.with_offset(1))?;
+                Ok(Some(element))
+            }
+            tag => Err(Error::InvalidOptionTag { tag }),
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T: AsyncSerialize> AsyncSerialize for Option<T> {
+    async fn tls_serialize_async<W: AsyncWriter>(&self, writer: &mut W) -> Result<usize, Error> {
+        match self {
+            Some(e) => {
+                writer.write_bytes(&[1]).await?;
+                e.tls_serialize_async(writer).await.map(|l| l + 1)
+            }
+            None => {
+                writer.write_bytes(&[0]).await?;
+                Ok(1)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T: AsyncDeserialize> AsyncDeserialize for Option<T> {
+    async fn tls_deserialize_async<R: AsyncReader>(bytes: &mut R) -> Result<Self, Error> {
         let mut some_or_none = [0u8; 1];
-        bytes.read_exact(&mut some_or_none)?;
+        bytes.read_bytes(&mut some_or_none).await?;
         match some_or_none[0] {
-            0 => {
-                Ok(None)
-            },
+            0 => Ok(None),
             1 => {
-                let element = T::tls_deserialize(bytes)?;
+                let element = T::tls_deserialize_async(bytes)
+                    .await
+                    .map_err(|e| e.with_offset(1))?;
                 Ok(Some(element))
-            },
-            _ => Err(Error::DecodingError(format!("Trying to decode Option<T> with {} for option. It must be 0 for None and 1 for Some.", some_or_none[0])))
+            }
+            tag => Err(Error::InvalidOptionTag { tag }),
+        }
+    }
+}
+
+impl<'a, T: DeserializeBytes<'a>> DeserializeBytes<'a> for Option<T> {
+    fn tls_deserialize_bytes(bytes: &'a [u8]) -> Result<(Self, &'a [u8]), Error> {
+        let (some_or_none, remainder) = u8::tls_deserialize_bytes(bytes)?;
+        match some_or_none {
+            0 => Ok((None, remainder)),
+            1 => {
+                let (element, remainder) =
+                    T::tls_deserialize_bytes(remainder).map_err(|e| e.with_offset(1))?;
+                Ok((Some(element), remainder))
+            }
+            tag => Err(Error::InvalidOptionTag { tag }),
         }
     }
 }
 
-macro_rules! impl_unsigned {
-    ($t:ty, $bytes:literal) => {
+/// The implementations for arrays of bytes that read and write all of them
+/// at once.
+macro_rules! impl_byte_array {
+    (serialize) => {
+        #[inline]
+        fn tls_serialize_elements<W: Writer>(
+            elements: &[Self],
+            writer: &mut W,
+        ) -> Result<usize, Error> {
+            writer.write_bytes(elements)?;
+            Ok(elements.len())
+        }
+
+        #[inline]
+        fn tls_serialize_elements_to_slice(
+            elements: &[Self],
+            out: &mut [u8],
+        ) -> Result<usize, Error> {
+            slice_prefix(out, elements.len())?.copy_from_slice(elements);
+            Ok(elements.len())
+        }
+
+        #[inline]
+        fn tls_serialize_elements_vectored<'v>(
+            elements: &'v [Self],
+            writer: &mut VectoredWriter<'v>,
+        ) -> Result<usize, Error> {
+            writer.write_borrowed(elements);
+            Ok(elements.len())
+        }
+    };
+    (deserialize) => {
+        #[inline]
+        fn tls_deserialize_array<R: Reader, const N: usize>(
+            bytes: &mut R,
+        ) -> Result<[Self; N], Error> {
+            let mut out = [0u8; N];
+            bytes.read_bytes(&mut out)?;
+            Ok(out)
+        }
+    };
+    (deserialize_bytes) => {
+        #[inline]
+        fn tls_deserialize_bytes_array<const N: usize>(
+            bytes: &'a [u8],
+        ) -> Result<([Self; N], &'a [u8]), Error> {
+            if bytes.len() < N {
+                return Err(Error::EndOfStream);
+            }
+            let (out, remainder) = bytes.split_at(N);
+            Ok((out.try_into()?, remainder))
+        }
+    };
+    (serialize_async) => {
+        async fn tls_serialize_elements_async<W: AsyncWriter>(
+            elements: &[Self],
+            writer: &mut W,
+        ) -> Result<usize, Error> {
+            writer.write_bytes(elements).await?;
+            Ok(elements.len())
+        }
+    };
+    (deserialize_async) => {
+        async fn tls_deserialize_array_async<R: AsyncReader, const N: usize>(
+            bytes: &mut R,
+        ) -> Result<[Self; N], Error> {
+            let mut out = [0u8; N];
+            bytes.read_bytes(&mut out).await?;
+            Ok(out)
+        }
+    };
+}
+
+macro_rules! impl_int {
+    ($t:ty, $bytes:literal $(, $byte_array:ident)?) => {
         impl Deserialize for $t {
-            fn tls_deserialize<R: Read>(bytes: &mut R) -> Result<Self, Error> {
+            fn tls_deserialize<R: Reader>(bytes: &mut R) -> Result<Self, Error> {
                 let mut x = (0 as $t).to_be_bytes();
-                bytes.read_exact(&mut x)?;
+                bytes.read_bytes(&mut x)?;
                 Ok(<$t>::from_be_bytes(x))
             }
+
+            $($byte_array!(deserialize);)?
+        }
+
+        impl<'a> DeserializeBytes<'a> for $t {
+            fn tls_deserialize_bytes(bytes: &'a [u8]) -> Result<(Self, &'a [u8]), Error> {
+                if bytes.len() < $bytes {
+                    return Err(Error::EndOfStream);
+                }
+                let (x, remainder) = bytes.split_at($bytes);
+                Ok((<$t>::from_be_bytes(x.try_into()?), remainder))
+            }
+
+            $($byte_array!(deserialize_bytes);)?
         }
 
         impl Serialize for $t {
-            fn tls_serialize<W: Write>(&self, writer: &mut W) -> Result<usize, Error> {
-                let written = writer.write(&self.to_be_bytes())?;
-                Ok(written)
+            fn tls_serialize<W: Writer>(&self, writer: &mut W) -> Result<usize, Error> {
+                writer.write_bytes(&self.to_be_bytes())?;
+                Ok($bytes)
+            }
+
+            #[inline]
+            fn tls_serialize_to_checked_slice(&self, out: &mut [u8]) -> Result<usize, Error> {
+                slice_prefix(out, $bytes)?.copy_from_slice(&self.to_be_bytes());
+                Ok($bytes)
             }
+
+            $($byte_array!(serialize);)?
         }
 
         impl Size for $t {
@@ -69,94 +276,482 @@ macro_rules! impl_unsigned {
             fn tls_serialized_len(&self) -> usize {
                 $bytes
             }
+
+            #[inline]
+            fn tls_fixed_len() -> Option<usize> {
+                Some($bytes)
+            }
+        }
+
+        impl FixedSize for $t {
+            const TLS_LEN: usize = $bytes;
+        }
+
+        impl<C: ?Sized> SerializeWithContext<C> for $t {
+            #[inline]
+            fn tls_serialize_with_context<W: Writer>(
+                &self,
+                writer: &mut W,
+                _context: &C,
+            ) -> Result<usize, Error> {
+                self.tls_serialize(writer)
+            }
+        }
+
+        impl<C: ?Sized> DeserializeWithContext<C> for $t {
+            #[inline]
+            fn tls_deserialize_with_context<R: Reader>(
+                bytes: &mut R,
+                _context: &C,
+            ) -> Result<Self, Error> {
+                Self::tls_deserialize(bytes)
+            }
+        }
+
+        #[cfg(feature = "async")]
+        impl AsyncDeserialize for $t {
+            async fn tls_deserialize_async<R: AsyncReader>(bytes: &mut R) -> Result<Self, Error> {
+                let mut x = (0 as $t).to_be_bytes();
+                bytes.read_bytes(&mut x).await?;
+                Ok(<$t>::from_be_bytes(x))
+            }
+
+            $($byte_array!(deserialize_async);)?
+        }
+
+        #[cfg(feature = "async")]
+        impl AsyncSerialize for $t {
+            async fn tls_serialize_async<W: AsyncWriter>(
+                &self,
+                writer: &mut W,
+            ) -> Result<usize, Error> {
+                writer.write_bytes(&self.to_be_bytes()).await?;
+                Ok($bytes)
+            }
+
+            $($byte_array!(serialize_async);)?
         }
     };
 }
 
-impl_unsigned!(u8, 1);
-impl_unsigned!(u16, 2);
-impl_unsigned!(u32, 4);
-impl_unsigned!(u64, 8);
+impl_int!(u8, 1, impl_byte_array);
+impl_int!(u16, 2);
+impl_int!(u32, 4);
+impl_int!(u64, 8);
+impl_int!(u128, 16);
+// Signed integers are encoded in big-endian two's complement.
+impl_int!(i8, 1);
+impl_int!(i16, 2);
+impl_int!(i32, 4);
+impl_int!(i64, 8);
+impl_int!(i128, 16);
 
-impl From<std::array::TryFromSliceError> for Error {
-    fn from(_: std::array::TryFromSliceError) -> Self {
-        Self::InvalidInput
+// A `bool` is encoded as a `uint8` that must be `0` or `1`.
+impl Size for bool {
+    #[inline]
+    fn tls_serialized_len(&self) -> usize {
+        1
+    }
+
+    #[inline]
+    fn tls_fixed_len() -> Option<usize> {
+        Some(1)
+    }
+}
+
+impl FixedSize for bool {
+    const TLS_LEN: usize = 1;
+}
+
+#[inline]
+fn bool_from_u8(value: u8) -> Result<bool, Error> {
+    match value {
+        0 => Ok(false),
+        1 => Ok(true),
+        value => Err(Error::InvalidBool { value }),
     }
 }
 
-// Implement (de)serialization for tuple.
-impl<T, U> Deserialize for (T, U)
-where
-    T: Deserialize,
-    U: Deserialize,
-{
-    #[inline(always)]
-    fn tls_deserialize<R: Read>(bytes: &mut R) -> Result<Self, Error> {
-        Ok((T::tls_deserialize(bytes)?, U::tls_deserialize(bytes)?))
+impl Serialize for bool {
+    #[inline]
+    fn tls_serialize<W: Writer>(&self, writer: &mut W) -> Result<usize, Error> {
+        (*self as u8).tls_serialize(writer)
+    }
+
+    #[inline]
+    fn tls_serialize_to_checked_slice(&self, out: &mut [u8]) -> Result<usize, Error> {
+        (*self as u8).tls_serialize_to_checked_slice(out)
     }
 }
 
-impl<T, U> Serialize for (T, U)
-where
-    T: Serialize,
-    U: Serialize,
-{
-    #[inline(always)]
-    fn tls_serialize<W: Write>(&self, writer: &mut W) -> Result<usize, Error> {
-        let written = self.0.tls_serialize(writer)?;
-        self.1.tls_serialize(writer).map(|l| l + written)
+impl Deserialize for bool {
+    #[inline]
+    fn tls_deserialize<R: Reader>(bytes: &mut R) -> Result<Self, Error> {
+        bool_from_u8(u8::tls_deserialize(bytes)?)
     }
 }
 
-impl<T, U> Size for (T, U)
-where
-    T: Size,
-    U: Size,
-{
-    #[inline(always)]
-    fn tls_serialized_len(&self) -> usize {
-        self.0.tls_serialized_len() + self.1.tls_serialized_len()
-    }
-}
-
-impl<T, U, V> Deserialize for (T, U, V)
-where
-    T: Deserialize,
-    U: Deserialize,
-    V: Deserialize,
-{
-    #[inline(always)]
-    fn tls_deserialize<R: Read>(bytes: &mut R) -> Result<Self, Error> {
-        Ok((
-            T::tls_deserialize(bytes)?,
-            U::tls_deserialize(bytes)?,
-            V::tls_deserialize(bytes)?,
-        ))
-    }
-}
-
-impl<T, U, V> Serialize for (T, U, V)
-where
-    T: Serialize,
-    U: Serialize,
-    V: Serialize,
-{
-    #[inline(always)]
-    fn tls_serialize<W: Write>(&self, writer: &mut W) -> Result<usize, Error> {
-        let mut written = self.0.tls_serialize(writer)?;
-        written += self.1.tls_serialize(writer)?;
-        self.2.tls_serialize(writer).map(|l| l + written)
-    }
-}
-
-impl<T, U, V> Size for (T, U, V)
-where
-    T: Size,
-    U: Size,
-    V: Size,
-{
-    #[inline(always)]
-    fn tls_serialized_len(&self) -> usize {
-        self.0.tls_serialized_len() + self.1.tls_serialized_len() + self.2.tls_serialized_len()
+impl<'a> DeserializeBytes<'a> for bool {
+    #[inline]
+    fn tls_deserialize_bytes(bytes: &'a [u8]) -> Result<(Self, &'a [u8]), Error> {
+        let (value, remainder) = u8::tls_deserialize_bytes(bytes)?;
+        Ok((bool_from_u8(value)?, remainder))
+    }
+}
+
+impl<C: ?Sized> SerializeWithContext<C> for bool {
+    #[inline]
+    fn tls_serialize_with_context<W: Writer>(
+        &self,
+        writer: &mut W,
+        _context: &C,
+    ) -> Result<usize, Error> {
+        self.tls_serialize(writer)
+    }
+}
+
+impl<C: ?Sized> DeserializeWithContext<C> for bool {
+    #[inline]
+    fn tls_deserialize_with_context<R: Reader>(bytes: &mut R, _context: &C) -> Result<Self, Error> {
+        Self::tls_deserialize(bytes)
+    }
+}
+
+#[cfg(feature = "async")]
+impl AsyncSerialize for bool {
+    async fn tls_serialize_async<W: AsyncWriter>(&self, writer: &mut W) -> Result<usize, Error> {
+        (*self as u8).tls_serialize_async(writer).await
+    }
+}
+
+#[cfg(feature = "async")]
+impl AsyncDeserialize for bool {
+    async fn tls_deserialize_async<R: AsyncReader>(bytes: &mut R) -> Result<Self, Error> {
+        bool_from_u8(u8::tls_deserialize_async(bytes).await?)
     }
 }
+
+// `()` and `PhantomData` have no encoding, e.g. for marker fields of derived
+// structs.
+macro_rules! impl_zero_length {
+    ([$($generics:tt)*] $t:ty, $value:expr) => {
+        impl<$($generics)*> Size for $t {
+            #[inline]
+            fn tls_serialized_len(&self) -> usize {
+                0
+            }
+
+            #[inline]
+            fn tls_fixed_len() -> Option<usize> {
+                Some(0)
+            }
+        }
+
+        impl<$($generics)*> FixedSize for $t {
+            const TLS_LEN: usize = 0;
+        }
+
+        impl<$($generics)*> Serialize for $t {
+            #[inline]
+            fn tls_serialize<W: Writer>(&self, _writer: &mut W) -> Result<usize, Error> {
+                Ok(0)
+            }
+
+            #[inline]
+            fn tls_serialize_to_checked_slice(&self, _out: &mut [u8]) -> Result<usize, Error> {
+                Ok(0)
+            }
+        }
+
+        impl<$($generics)*> Deserialize for $t {
+            #[inline]
+            fn tls_deserialize<R: Reader>(_bytes: &mut R) -> Result<Self, Error> {
+                Ok($value)
+            }
+        }
+
+        impl<'a, $($generics)*> DeserializeBytes<'a> for $t {
+            #[inline]
+            fn tls_deserialize_bytes(bytes: &'a [u8]) -> Result<(Self, &'a [u8]), Error> {
+                Ok(($value, bytes))
+            }
+        }
+
+        impl<C: ?Sized, $($generics)*> SerializeWithContext<C> for $t {
+            #[inline]
+            fn tls_serialize_with_context<W: Writer>(
+                &self,
+                _writer: &mut W,
+                _context: &C,
+            ) -> Result<usize, Error> {
+                Ok(0)
+            }
+        }
+
+        impl<C: ?Sized, $($generics)*> DeserializeWithContext<C> for $t {
+            #[inline]
+            fn tls_deserialize_with_context<R: Reader>(
+                _bytes: &mut R,
+                _context: &C,
+            ) -> Result<Self, Error> {
+                Ok($value)
+            }
+        }
+
+        #[cfg(feature = "async")]
+        impl<$($generics)*> AsyncSerialize for $t
+        where
+            $t: Sync,
+        {
+            async fn tls_serialize_async<W: AsyncWriter>(
+                &self,
+                _writer: &mut W,
+            ) -> Result<usize, Error> {
+                Ok(0)
+            }
+        }
+
+        #[cfg(feature = "async")]
+        impl<$($generics)*> AsyncDeserialize for $t
+        where
+            $t: Send,
+        {
+            async fn tls_deserialize_async<R: AsyncReader>(_bytes: &mut R) -> Result<Self, Error> {
+                Ok($value)
+            }
+        }
+    };
+}
+
+impl_zero_length!([](), ());
+impl_zero_length!([T: ?Sized] PhantomData<T>, PhantomData);
+
+// Implement (de)serialization for tuples with up to 12 elements.
+//
+// Each element is given as `(type parameter, binding, index)`. The offset of
+// an element is only computed when decoding it fails.
+macro_rules! impl_tuple {
+    ($(($T:ident, $t:ident, $idx:tt)),+) => {
+        impl<$($T: Size),+> Size for ($($T,)+) {
+            #[inline(always)]
+            fn tls_serialized_len(&self) -> usize {
+                0 $(+ self.$idx.tls_serialized_len())+
+            }
+
+            #[inline(always)]
+            fn tls_serialized_len_checked(&self) -> Result<usize, Error> {
+                let mut len = 0;
+                $(len = add_len(len, self.$idx.tls_serialized_len_checked()?)?;)+
+                Ok(len)
+            }
+
+            #[inline(always)]
+            fn tls_fixed_len() -> Option<usize> {
+                let mut len = 0usize;
+                $(len = len.checked_add($T::tls_fixed_len()?)?;)+
+                Some(len)
+            }
+        }
+
+        impl<$($T: FixedSize),+> FixedSize for ($($T,)+) {
+            const TLS_LEN: usize = 0 $(+ $T::TLS_LEN)+;
+        }
+
+        impl<$($T: Serialize),+> Serialize for ($($T,)+) {
+            #[inline(always)]
+            fn tls_serialize<W: Writer>(&self, writer: &mut W) -> Result<usize, Error> {
+                let mut written = 0;
+                $(written += self.$idx.tls_serialize(writer)?;)+
+                Ok(written)
+            }
+
+            #[inline(always)]
+            fn tls_serialize_vectored<'a>(
+                &'a self,
+                writer: &mut VectoredWriter<'a>,
+            ) -> Result<usize, Error> {
+                let mut written = 0;
+                $(written += self.$idx.tls_serialize_vectored(writer)?;)+
+                Ok(written)
+            }
+        }
+
+        impl<$($T: Deserialize),+> Deserialize for ($($T,)+) {
+            #[inline(always)]
+            fn tls_deserialize<R: Reader>(bytes: &mut R) -> Result<Self, Error> {
+                let offset = || 0;
+                $(
+                    let $t = $T::tls_deserialize(bytes)
+                        .map_err(|e| e.with_field(offset(), stringify!($idx)))?;
+                    let offset = || offset() + $t.tls_serialized_len();
+                )+
+                let _ = offset;
+                Ok(($($t,)+))
+            }
+        }
+
+        impl<'a, $($T: DeserializeBytes<'a>),+> DeserializeBytes<'a> for ($($T,)+) {
+            #[inline(always)]
+            fn tls_deserialize_bytes(bytes: &'a [u8]) -> Result<(Self, &'a [u8]), Error> {
+                let remainder = bytes;
+                $(
+                    let ($t, remainder) = $T::tls_deserialize_bytes(remainder)
+                        .map_err(|e| e.with_field(bytes.len() - remainder.len(), stringify!($idx)))?;
+                )+
+                Ok((($($t,)+), remainder))
+            }
+        }
+
+        impl<Ctx: ?Sized, $($T: SerializeWithContext<Ctx>),+> SerializeWithContext<Ctx>
+            for ($($T,)+)
+        {
+            #[inline(always)]
+            fn tls_serialize_with_context<W: Writer>(
+                &self,
+                writer: &mut W,
+                context: &Ctx,
+            ) -> Result<usize, Error> {
+                let mut written = 0;
+                $(written += self.$idx.tls_serialize_with_context(writer, context)?;)+
+                Ok(written)
+            }
+        }
+
+        impl<Ctx: ?Sized, $($T: DeserializeWithContext<Ctx>),+> DeserializeWithContext<Ctx>
+            for ($($T,)+)
+        {
+            #[inline(always)]
+            fn tls_deserialize_with_context<R: Reader>(
+                bytes: &mut R,
+                context: &Ctx,
+            ) -> Result<Self, Error> {
+                let offset = || 0;
+                $(
+                    let $t = $T::tls_deserialize_with_context(bytes, context)
+                        .map_err(|e| e.with_field(offset(), stringify!($idx)))?;
+                    let offset = || offset() + $t.tls_serialized_len();
+                )+
+                let _ = offset;
+                Ok(($($t,)+))
+            }
+        }
+
+        #[cfg(feature = "async")]
+        impl<$($T: AsyncSerialize),+> AsyncSerialize for ($($T,)+) {
+            async fn tls_serialize_async<W: AsyncWriter>(
+                &self,
+                writer: &mut W,
+            ) -> Result<usize, Error> {
+                let mut written = 0;
+                $(written += self.$idx.tls_serialize_async(writer).await?;)+
+                Ok(written)
+            }
+        }
+
+        #[cfg(feature = "async")]
+        impl<$($T: AsyncDeserialize),+> AsyncDeserialize for ($($T,)+) {
+            async fn tls_deserialize_async<R: AsyncReader>(bytes: &mut R) -> Result<Self, Error> {
+                // Unlike the closures above, a running offset doesn't hold
+                // references to the elements across an `await`, which would
+                // need them to be `Sync`.
+                let mut offset = 0;
+                $(
+                    let $t = $T::tls_deserialize_async(bytes)
+                        .await
+                        .map_err(|e| e.with_field(offset, stringify!($idx)))?;
+                    offset += $t.tls_serialized_len();
+                )+
+                let _ = offset;
+                Ok(($($t,)+))
+            }
+        }
+    };
+}
+
+impl_tuple!((T0, t0, 0), (T1, t1, 1));
+impl_tuple!((T0, t0, 0), (T1, t1, 1), (T2, t2, 2));
+impl_tuple!((T0, t0, 0), (T1, t1, 1), (T2, t2, 2), (T3, t3, 3));
+impl_tuple!(
+    (T0, t0, 0),
+    (T1, t1, 1),
+    (T2, t2, 2),
+    (T3, t3, 3),
+    (T4, t4, 4)
+);
+impl_tuple!(
+    (T0, t0, 0),
+    (T1, t1, 1),
+    (T2, t2, 2),
+    (T3, t3, 3),
+    (T4, t4, 4),
+    (T5, t5, 5)
+);
+impl_tuple!(
+    (T0, t0, 0),
+    (T1, t1, 1),
+    (T2, t2, 2),
+    (T3, t3, 3),
+    (T4, t4, 4),
+    (T5, t5, 5),
+    (T6, t6, 6)
+);
+impl_tuple!(
+    (T0, t0, 0),
+    (T1, t1, 1),
+    (T2, t2, 2),
+    (T3, t3, 3),
+    (T4, t4, 4),
+    (T5, t5, 5),
+    (T6, t6, 6),
+    (T7, t7, 7)
+);
+impl_tuple!(
+    (T0, t0, 0),
+    (T1, t1, 1),
+    (T2, t2, 2),
+    (T3, t3, 3),
+    (T4, t4, 4),
+    (T5, t5, 5),
+    (T6, t6, 6),
+    (T7, t7, 7),
+    (T8, t8, 8)
+);
+impl_tuple!(
+    (T0, t0, 0),
+    (T1, t1, 1),
+    (T2, t2, 2),
+    (T3, t3, 3),
+    (T4, t4, 4),
+    (T5, t5, 5),
+    (T6, t6, 6),
+    (T7, t7, 7),
+    (T8, t8, 8),
+    (T9, t9, 9)
+);
+impl_tuple!(
+    (T0, t0, 0),
+    (T1, t1, 1),
+    (T2, t2, 2),
+    (T3, t3, 3),
+    (T4, t4, 4),
+    (T5, t5, 5),
+    (T6, t6, 6),
+    (T7, t7, 7),
+    (T8, t8, 8),
+    (T9, t9, 9),
+    (T10, t10, 10)
+);
+impl_tuple!(
+    (T0, t0, 0),
+    (T1, t1, 1),
+    (T2, t2, 2),
+    (T3, t3, 3),
+    (T4, t4, 4),
+    (T5, t5, 5),
+    (T6, t6, 6),
+    (T7, t7, 7),
+    (T8, t8, 8),
+    (T9, t9, 9),
+    (T10, t10, 10),
+    (T11, t11, 11)
+);
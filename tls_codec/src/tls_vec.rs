@@ -1,958 +1,2104 @@
-//! A vector with a length field for TLS serialisation.
-//! Use this for any vector that is serialised.
+//! Vectors with a length field for TLS serialisation.
+//! Use these for any vector that is serialised.
+//!
+//! The vectors are generic over their [`LengthPrefix`], the type that encodes
+//! the length in bytes of their content.
+//! [`TlsVec`] holds any elements, [`SecretTlsVec`] zeroizes its elements on
+//! drop, and [`TlsByteVec`] reads and writes its content at once.
+//! [`TlsSlice`] and [`TlsByteSlice`] serialize borrowed elements.
+//! Aliases such as [`TlsVecU16`] fix the prefix to one of the TLS integers.
+//! A [`Bounded`] prefix restricts the length of the content to a range.
+
+use alloc::{format, string::String, vec::Vec};
+use core::{marker::PhantomData, ops::Drop};
 
-// TODO: #2 share code between the different implementations. There's too much
-//       duplicate code in here.
+#[cfg(feature = "serde_serialize")]
+use serde::ser::SerializeStruct;
+use zeroize::Zeroize;
 
-use std::{
-    io::{Read, Write},
-    ops::Drop,
+use crate::{
+    error::add_len,
+    io::{slice_prefix, READ_CHUNK_LEN},
+    limits, Deserialize, DeserializeBytes, DeserializeWithContext, Error, FixedSize, Reader,
+    Serialize, SerializeWithContext, Size, VarInt, VectoredWriter, Writer, U24,
 };
+#[cfg(feature = "async")]
+use crate::{AsyncDeserialize, AsyncReader, AsyncSerialize, AsyncWriter};
+
+/// The length prefix of a vector, which encodes the length in bytes of the
+/// vector's content.
+///
+/// It's implemented for `u8`, `u16`, [`U24`], `u32`, and
+/// [`VarInt`](crate::VarInt).
+/// Implement it for another type to use that as prefix of the vectors in this
+/// crate.
+pub trait LengthPrefix: Serialize + Deserialize + for<'a> DeserializeBytes<'a> {
+    /// The maximum length in bytes of a vector's content.
+    const MAX_LEN: usize;
+
+    /// The names of the vectors with this prefix in their `Debug` output and
+    /// serde representation.
+    const NAMES: VectorNames = VectorNames::GENERIC;
+
+    /// Create the prefix of a vector with `len` bytes of content.
+    ///
+    /// Returns [`Error::InvalidVectorLength`] if `len` is larger than
+    /// [`LengthPrefix::MAX_LEN`].
+    fn from_len(len: usize) -> Result<Self, Error>;
+
+    /// Get the length in bytes of the vector's content from a decoded prefix.
+    ///
+    /// Returns [`Error::InvalidVectorLength`] if the length is larger than
+    /// [`LengthPrefix::MAX_LEN`].
+    fn to_len(&self) -> Result<usize, Error>;
+
+    /// Get the serialized length of the prefix of a vector with `len` bytes of
+    /// content.
+    #[inline]
+    fn prefix_len(len: usize) -> usize {
+        Self::tls_fixed_len()
+            .unwrap_or_else(|| Self::from_len(len).map_or(0, |prefix| prefix.tls_serialized_len()))
+    }
+}
+
+/// The names of the vectors with a certain [`LengthPrefix`], e.g.
+/// `TlsVecU16` for a [`TlsVec`] with a `u16` prefix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct VectorNames {
+    /// The name of a [`TlsVec`].
+    pub vec: &'static str,
+    /// The name of a [`SecretTlsVec`].
+    pub secret_vec: &'static str,
+    /// The name of a [`TlsByteVec`].
+    pub byte_vec: &'static str,
+    /// The name of a [`TlsBytes`].
+    pub bytes: &'static str,
+    /// The name of a [`TlsByteSlice`].
+    pub byte_slice: &'static str,
+}
+
+impl VectorNames {
+    /// The names of the generic vector types.
+    pub const GENERIC: Self = Self {
+        vec: "TlsVec",
+        secret_vec: "SecretTlsVec",
+        byte_vec: "TlsByteVec",
+        bytes: "TlsBytes",
+        byte_slice: "TlsByteSlice",
+    };
+}
+
+/// The [`VectorNames`] of the aliases with the suffix `$suffix`.
+macro_rules! vector_names {
+    ($suffix:literal) => {
+        VectorNames {
+            vec: concat!("TlsVec", $suffix),
+            secret_vec: concat!("SecretTlsVec", $suffix),
+            byte_vec: concat!("TlsByteVec", $suffix),
+            bytes: concat!("TlsBytes", $suffix),
+            byte_slice: concat!("TlsByteSlice", $suffix),
+        }
+    };
+}
+
+/// Check that the content length `len` of a vector is at most `max_len`.
+#[inline(always)]
+pub(crate) fn check_len(len: usize, max_len: usize) -> Result<usize, Error> {
+    if len > max_len {
+        return Err(Error::InvalidVectorLength {
+            expected: max_len,
+            actual: len,
+        });
+    }
+    Ok(len)
+}
+
+macro_rules! impl_length_prefix {
+    ($t:ty, $suffix:literal) => {
+        impl LengthPrefix for $t {
+            const MAX_LEN: usize = <$t>::MAX as usize;
+            const NAMES: VectorNames = vector_names!($suffix);
+
+            #[inline(always)]
+            fn from_len(len: usize) -> Result<Self, Error> {
+                Ok(check_len(len, Self::MAX_LEN)? as $t)
+            }
+
+            #[inline(always)]
+            fn to_len(&self) -> Result<usize, Error> {
+                Ok(*self as usize)
+            }
+        }
+    };
+}
+
+impl_length_prefix!(u8, "U8");
+impl_length_prefix!(u16, "U16");
+impl_length_prefix!(u32, "U32");
+
+impl LengthPrefix for U24 {
+    const MAX_LEN: usize = U24::MAX as usize;
+    const NAMES: VectorNames = vector_names!("U24");
+
+    #[inline(always)]
+    fn from_len(len: usize) -> Result<Self, Error> {
+        U24::new(check_len(len, Self::MAX_LEN)? as u64)
+    }
+
+    #[inline(always)]
+    fn to_len(&self) -> Result<usize, Error> {
+        Ok(self.value() as usize)
+    }
+}
+
+/// A [`LengthPrefix`] `L` that bounds the length in bytes of a vector's
+/// content to `MIN..=MAX`, i.e. `T data<MIN..MAX>` in the presentation
+/// language.
+///
+/// Vectors with a `Bounded` prefix return [`Error::VectorLengthOutOfBounds`]
+/// when they are encoded or decoded with a length outside of the bounds.
+/// Vectors of elements with a fixed length additionally reject content that
+/// isn't a multiple of the element length with
+/// [`Error::InvalidVectorLength`].
+///
+/// ```
+/// use tls_codec::{Bounded, Deserialize, Error, Serialize, TlsByteVec, TlsVec};
+///
+/// // opaque legacy_session_id<0..32>;
+/// type SessionId = TlsByteVec<Bounded<u8, 0, 32>>;
+/// // CipherSuite cipher_suites<2..2^16-2>;
+/// type CipherSuites = TlsVec<u16, Bounded<u16, 2, 65534>>;
+///
+/// let error = SessionId::from_slice(&[0; 33]).tls_serialize_detached().unwrap_err();
+/// assert_eq!(Error::VectorLengthOutOfBounds { min: 0, max: 32, actual: 33 }, error);
+///
+/// let error = CipherSuites::tls_deserialize(&mut &[0u8, 0][..]).unwrap_err();
+/// assert_eq!(Error::VectorLengthOutOfBounds { min: 2, max: 65534, actual: 0 }, error);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Bounded<L, const MIN: usize, const MAX: usize>(L);
+
+impl<L, const MIN: usize, const MAX: usize> Bounded<L, MIN, MAX> {
+    /// Check that the content length `len` of a vector is within the bounds.
+    #[inline(always)]
+    fn check_bounds(len: usize) -> Result<usize, Error> {
+        if len < MIN || len > MAX {
+            return Err(Error::VectorLengthOutOfBounds {
+                min: MIN,
+                max: MAX,
+                actual: len,
+            });
+        }
+        Ok(len)
+    }
+}
+
+impl<L: LengthPrefix, const MIN: usize, const MAX: usize> LengthPrefix for Bounded<L, MIN, MAX> {
+    const MAX_LEN: usize = if MAX < L::MAX_LEN { MAX } else { L::MAX_LEN };
+    const NAMES: VectorNames = L::NAMES;
+
+    #[inline]
+    fn from_len(len: usize) -> Result<Self, Error> {
+        L::from_len(Self::check_bounds(len)?).map(Self)
+    }
+
+    #[inline]
+    fn to_len(&self) -> Result<usize, Error> {
+        Self::check_bounds(self.0.to_len()?)
+    }
+
+    #[inline]
+    fn prefix_len(len: usize) -> usize {
+        L::prefix_len(len)
+    }
+}
+
+impl<L: Size, const MIN: usize, const MAX: usize> Size for Bounded<L, MIN, MAX> {
+    #[inline]
+    fn tls_serialized_len(&self) -> usize {
+        self.0.tls_serialized_len()
+    }
+
+    #[inline]
+    fn tls_fixed_len() -> Option<usize> {
+        L::tls_fixed_len()
+    }
+}
+
+impl<L: FixedSize, const MIN: usize, const MAX: usize> FixedSize for Bounded<L, MIN, MAX> {
+    const TLS_LEN: usize = L::TLS_LEN;
+}
+
+impl<L: Serialize, const MIN: usize, const MAX: usize> Serialize for Bounded<L, MIN, MAX> {
+    #[inline]
+    fn tls_serialize<W: Writer>(&self, writer: &mut W) -> Result<usize, Error> {
+        self.0.tls_serialize(writer)
+    }
+
+    #[inline]
+    fn tls_serialize_to_checked_slice(&self, out: &mut [u8]) -> Result<usize, Error> {
+        self.0.tls_serialize_to_checked_slice(out)
+    }
+}
+
+impl<L: Deserialize, const MIN: usize, const MAX: usize> Deserialize for Bounded<L, MIN, MAX> {
+    #[inline]
+    fn tls_deserialize<R: Reader>(bytes: &mut R) -> Result<Self, Error> {
+        L::tls_deserialize(bytes).map(Self)
+    }
+}
+
+impl<'a, L: DeserializeBytes<'a>, const MIN: usize, const MAX: usize> DeserializeBytes<'a>
+    for Bounded<L, MIN, MAX>
+{
+    #[inline]
+    fn tls_deserialize_bytes(bytes: &'a [u8]) -> Result<(Self, &'a [u8]), Error> {
+        let (prefix, remainder) = L::tls_deserialize_bytes(bytes)?;
+        Ok((Self(prefix), remainder))
+    }
+}
+
+#[cfg(feature = "async")]
+impl<L: AsyncSerialize, const MIN: usize, const MAX: usize> AsyncSerialize
+    for Bounded<L, MIN, MAX>
+{
+    async fn tls_serialize_async<W: AsyncWriter>(&self, writer: &mut W) -> Result<usize, Error> {
+        self.0.tls_serialize_async(writer).await
+    }
+}
+
+#[cfg(feature = "async")]
+impl<L: AsyncDeserialize, const MIN: usize, const MAX: usize> AsyncDeserialize
+    for Bounded<L, MIN, MAX>
+{
+    async fn tls_deserialize_async<R: AsyncReader>(bytes: &mut R) -> Result<Self, Error> {
+        L::tls_deserialize_async(bytes).await.map(Self)
+    }
+}
+
+/// Check that the content length `len` of a vector is a multiple of the
+/// serialized length of its elements, if that is fixed, and return the number
+/// of elements.
+///
+/// Returns `None` if the elements don't have a fixed length.
+/// Returns [`Error::InvalidVectorLength`] with the nearest multiple as
+/// `expected` length if `len` isn't a multiple.
+#[inline(always)]
+fn fixed_len_count<T: Size>(len: usize) -> Result<Option<usize>, Error> {
+    match T::tls_fixed_len() {
+        Some(element_len) if element_len > 0 => {
+            let rest = len % element_len;
+            if rest != 0 {
+                let expected = if rest < element_len - rest {
+                    len - rest
+                } else {
+                    (len - rest).saturating_add(element_len)
+                };
+                return Err(Error::InvalidVectorLength {
+                    expected,
+                    actual: len,
+                });
+            }
+            Ok(Some(len / element_len))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Get the serialized length of a decoded vector element.
+///
+/// Elements with a length of zero are rejected because decoding them doesn't
+/// consume any input, such that a length prefix would never be reached.
+#[inline(always)]
+fn element_len<T: Size>(element: &T) -> Result<usize, Error> {
+    match element.tls_serialized_len() {
+        0 => Err(Error::DecodingError(
+            "vector elements with a serialized length of zero can't be decoded".into(),
+        )),
+        len => Ok(len),
+    }
+}
+
+/// Preallocate a vector for `len` bytes of content read from a reader.
+///
+/// At most [`READ_CHUNK_LEN`] bytes are preallocated such that a bogus length
+/// prefix doesn't allocate more memory than the input provides.
+#[inline(always)]
+fn preallocate<T: Size>(vec: &mut Vec<T>, len: usize) -> Result<(), Error> {
+    if let Some(count) = fixed_len_count::<T>(len)? {
+        let max_count = READ_CHUNK_LEN / core::cmp::max(core::mem::size_of::<T>(), 1);
+        vec.reserve_exact(core::cmp::min(count, max_count));
+    }
+    Ok(())
+}
+
+/// Get the length in bytes of the content of a vector with `elements`.
+#[inline(always)]
+fn content_len<T: Size>(elements: &[T]) -> usize {
+    elements.iter().map(Size::tls_serialized_len).sum()
+}
+
+/// Get the length in bytes of the content of a vector with `elements`,
+/// failing on overflow.
+#[inline(always)]
+fn content_len_checked<T: Size>(elements: &[T]) -> Result<usize, Error> {
+    elements
+        .iter()
+        .try_fold(0, |acc, e| add_len(acc, e.tls_serialized_len_checked()?))
+}
+
+/// Get the serialized length of a vector with `len` bytes of content.
+#[inline(always)]
+fn vector_len<L: LengthPrefix>(len: usize) -> usize {
+    L::prefix_len(len) + len
+}
+
+/// Get the serialized length of a vector with `len` bytes of content, failing
+/// on overflow.
+#[inline(always)]
+fn vector_len_checked<L: LengthPrefix>(len: usize) -> Result<usize, Error> {
+    add_len(L::prefix_len(len), len)
+}
+
+/// Check that `written` bytes were serialized where `expected` bytes should
+/// have been.
+#[inline(always)]
+fn check_written(expected: usize, written: usize) -> Result<usize, Error> {
+    if written != expected {
+        return Err(Error::EncodingError(format!(
+            "{} bytes should have been serialized but {} were written",
+            expected, written
+        )));
+    }
+    Ok(written)
+}
+
+/// Check that the elements of a vector filled exactly the `len` bytes of
+/// content announced by its prefix, after `read` bytes were decoded.
+#[inline(always)]
+fn check_read(len: usize, read: usize) -> Result<(), Error> {
+    if read != len {
+        return Err(Error::InvalidVectorLength {
+            expected: len,
+            actual: read,
+        });
+    }
+    Ok(())
+}
+
+/// Get the length in bytes of the content of a vector with `elements` if they
+/// have a fixed length, failing on overflow.
+#[inline(always)]
+fn fixed_content_len<T: Size>(elements: &[T]) -> Result<Option<usize>, Error> {
+    T::tls_fixed_len()
+        .map(|len| {
+            len.checked_mul(elements.len()).ok_or_else(|| {
+                Error::EncodingError(format!(
+                    "the serialized length overflows: {} * {}",
+                    len,
+                    elements.len()
+                ))
+            })
+        })
+        .transpose()
+}
+
+/// The longest length prefix that is patched after writing the elements.
+const MAX_PATCHED_LEN_LEN: usize = 8;
+
+/// Get the length of the prefix `L` of a vector of `T` if it should be
+/// patched after writing the elements, i.e. if the prefix has a fixed length
+/// and the length of the elements isn't known up front.
+#[inline(always)]
+fn patched_len_len<T: Size, L: LengthPrefix>() -> Option<usize> {
+    match T::tls_fixed_len() {
+        Some(_) => None,
+        None => L::tls_fixed_len().filter(|len_len| *len_len <= MAX_PATCHED_LEN_LEN),
+    }
+}
+
+/// Get the length in bytes of the content of a vector with `elements` before
+/// streaming them, failing on overflow.
+#[inline(always)]
+fn streamed_content_len<T: Size>(elements: &[T]) -> Result<usize, Error> {
+    match fixed_content_len(elements)? {
+        Some(len) => Ok(len),
+        None => content_len_checked(elements),
+    }
+}
+
+/// Serializes the elements of a vector with or without a context.
+trait ElementSerializer<T> {
+    fn serialize<W: Writer>(&self, element: &T, writer: &mut W) -> Result<usize, Error>;
+}
+
+/// Serializes the elements with [`Serialize`].
+struct Plain;
+
+impl<T: Serialize> ElementSerializer<T> for Plain {
+    #[inline(always)]
+    fn serialize<W: Writer>(&self, element: &T, writer: &mut W) -> Result<usize, Error> {
+        element.tls_serialize(writer)
+    }
+}
+
+/// Serializes the elements with [`SerializeWithContext`] and the context.
+struct WithContext<'c, C: ?Sized>(&'c C);
+
+impl<'c, C: ?Sized, T: SerializeWithContext<C>> ElementSerializer<T> for WithContext<'c, C> {
+    #[inline(always)]
+    fn serialize<W: Writer>(&self, element: &T, writer: &mut W) -> Result<usize, Error> {
+        element.tls_serialize_with_context(writer, self.0)
+    }
+}
+
+/// Serialize a vector of `elements` with a length prefix `L` to `writer`,
+/// using `serializer` for each element.
+///
+/// If the `writer` supports patching, the prefix has a fixed length, and the
+/// elements don't, this writes a placeholder for the prefix and the elements,
+/// and then patches the prefix.
+/// This way nested vectors don't compute their length at every nesting level.
+/// Otherwise the length of the elements is computed once and the elements are
+/// streamed after the prefix.
+#[inline(always)]
+fn serialize_elements<T, L, W, S>(
+    elements: &[T],
+    writer: &mut W,
+    serializer: S,
+) -> Result<usize, Error>
+where
+    T: Size,
+    L: LengthPrefix,
+    W: Writer,
+    S: ElementSerializer<T>,
+{
+    if let (Some(position), Some(len_len)) = (writer.position(), patched_len_len::<T, L>()) {
+        let mut prefix = [0u8; MAX_PATCHED_LEN_LEN];
+        writer.write_bytes(&prefix[..len_len])?;
+        let mut len = 0usize;
+        for e in elements {
+            len = add_len(len, serializer.serialize(e, writer)?)?;
+        }
+        let len_len = L::from_len(len)?.tls_serialize_to_slice(&mut prefix[..len_len])?;
+        writer.patch_bytes(position, &prefix[..len_len])?;
+        return add_len(len, len_len);
+    }
+
+    let len = streamed_content_len(elements)?;
+    let mut written = L::from_len(len)?.tls_serialize(writer)?;
+    let expected = add_len(written, len)?;
+    for e in elements {
+        written += serializer.serialize(e, writer)?;
+    }
+    check_written(expected, written)
+}
+
+/// Serialize a vector of `elements` with a length prefix `L` to a `writer` for
+/// vectored IO, which references the content of byte vectors in the
+/// `elements`.
+///
+/// Like [`serialize_elements`], the prefix is patched if it has a fixed
+/// length, and the length of the elements is computed up front otherwise.
+#[inline(always)]
+fn serialize_elements_vectored<'v, T, L>(
+    elements: &'v [T],
+    writer: &mut VectoredWriter<'v>,
+) -> Result<usize, Error>
+where
+    T: Serialize,
+    L: LengthPrefix,
+{
+    if let (Some(position), Some(len_len)) = (writer.position(), patched_len_len::<T, L>()) {
+        let mut prefix = [0u8; MAX_PATCHED_LEN_LEN];
+        writer.write_bytes(&prefix[..len_len])?;
+        let mut len = 0usize;
+        for e in elements {
+            len = add_len(len, e.tls_serialize_vectored(writer)?)?;
+        }
+        let len_len = L::from_len(len)?.tls_serialize_to_slice(&mut prefix[..len_len])?;
+        writer.patch_bytes(position, &prefix[..len_len])?;
+        return add_len(len, len_len);
+    }
+
+    let len = streamed_content_len(elements)?;
+    let mut written = L::from_len(len)?.tls_serialize(writer)?;
+    let expected = add_len(written, len)?;
+    for e in elements {
+        written += e.tls_serialize_vectored(writer)?;
+    }
+    check_written(expected, written)
+}
+
+/// Decode the elements of a vector with a length prefix `L` from `bytes`,
+/// using `deserialize_element` for each element.
+#[inline(always)]
+fn deserialize_elements<T, L, R>(
+    bytes: &mut R,
+    mut deserialize_element: impl FnMut(&mut R) -> Result<T, Error>,
+) -> Result<Vec<T>, Error>
+where
+    T: Size,
+    L: LengthPrefix,
+    R: Reader,
+{
+    let prefix = L::tls_deserialize(bytes)?;
+    let len_len = prefix.tls_serialized_len();
+    let len = prefix.to_len()?;
+    limits::check_vector_len(bytes.budget(), len)?;
+    bytes.expect_bytes(len);
+    let mut vec = Vec::new();
+    preallocate(&mut vec, len)?;
+    let mut read = len_len;
+    while (read - len_len) < len {
+        limits::allocate(bytes.budget(), core::mem::size_of::<T>(), 1)
+            .map_err(|e| e.with_index(read, vec.len()))?;
+        let element = deserialize_element(bytes).map_err(|e| e.with_index(read, vec.len()))?;
+        read += element_len(&element).map_err(|e| e.with_index(read, vec.len()))?;
+        vec.push(element);
+    }
+    check_read(len, read - len_len)?;
+    Ok(vec)
+}
+
+/// Read the length prefix `L` of a vector from `bytes` and split the vector's
+/// content from the remaining bytes.
+///
+/// Returns the content, the remaining bytes, and the length of the prefix.
+#[inline(always)]
+fn split_content<L: LengthPrefix>(bytes: &[u8]) -> Result<(&[u8], &[u8], usize), Error> {
+    let (prefix, remainder) = L::tls_deserialize_bytes(bytes)?;
+    let len_len = prefix.tls_serialized_len();
+    let len = prefix.to_len()?;
+    if remainder.len() < len {
+        return Err(Error::EndOfStream.with_offset(len_len));
+    }
+    let (content, remainder) = remainder.split_at(len);
+    Ok((content, remainder, len_len))
+}
+
+/// Decode the elements of a vector with a length prefix `L` from `bytes` and
+/// return them with the remaining bytes.
+#[inline(always)]
+fn deserialize_bytes_elements<'a, T, L>(bytes: &'a [u8]) -> Result<(Vec<T>, &'a [u8]), Error>
+where
+    T: DeserializeBytes<'a>,
+    L: LengthPrefix,
+{
+    let (mut content, remainder, len_len) = split_content::<L>(bytes)?;
+    let content_len = content.len();
+    let mut vec = Vec::new();
+    if let Some(count) = fixed_len_count::<T>(content_len)? {
+        vec.reserve_exact(count);
+    }
+    while !content.is_empty() {
+        let offset = len_len + content_len - content.len();
+        let (element, rest) =
+            T::tls_deserialize_bytes(content).map_err(|e| e.with_index(offset, vec.len()))?;
+        element_len(&element).map_err(|e| e.with_index(offset, vec.len()))?;
+        content = rest;
+        vec.push(element);
+    }
+    Ok((vec, remainder))
+}
+
+/// Serialize a byte vector with a length prefix `L` and `content` to `writer`.
+#[inline(always)]
+fn serialize_bytes<L: LengthPrefix, W: Writer>(
+    content: &[u8],
+    writer: &mut W,
+) -> Result<usize, Error> {
+    let written = L::from_len(content.len())?.tls_serialize(writer)?;
+    writer.write_bytes(content)?;
+    Ok(written + content.len())
+}
+
+/// Serialize a byte vector with a length prefix `L` and `content` to a
+/// `writer` for vectored IO, which references the `content`.
+#[inline(always)]
+fn serialize_bytes_vectored<'v, L: LengthPrefix>(
+    content: &'v [u8],
+    writer: &mut VectoredWriter<'v>,
+) -> Result<usize, Error> {
+    let written = L::from_len(content.len())?.tls_serialize(writer)?;
+    writer.write_borrowed(content);
+    Ok(written + content.len())
+}
+
+/// Serialize a byte vector with a length prefix `L` and `content` into `out`.
+#[inline(always)]
+fn serialize_bytes_to_slice<L: LengthPrefix>(
+    content: &[u8],
+    out: &mut [u8],
+) -> Result<usize, Error> {
+    let prefix = L::from_len(content.len())?;
+    let len_len = prefix.tls_serialized_len();
+    let out = slice_prefix(out, add_len(len_len, content.len())?)?;
+    let (len, out) = out.split_at_mut(len_len);
+    prefix.tls_serialize_to_slice(len)?;
+    out.copy_from_slice(content);
+    Ok(len_len + content.len())
+}
+
+/// Read the length prefix `L` of a byte vector from `bytes` and account for
+/// its content in the decode budget.
+///
+/// Returns the length of the content and of the prefix.
+#[inline(always)]
+fn read_byte_prefix<L: LengthPrefix, R: Reader>(bytes: &mut R) -> Result<(usize, usize), Error> {
+    let prefix = L::tls_deserialize(bytes)?;
+    let len = prefix.to_len()?;
+    limits::check_vector_len(bytes.budget(), len)?;
+    limits::allocate(bytes.budget(), len, 0)?;
+    bytes.expect_bytes(len);
+    Ok((len, prefix.tls_serialized_len()))
+}
+
+/// Read a byte vector with a length prefix `L` from `bytes`.
+#[inline(always)]
+fn deserialize_bytes<L: LengthPrefix, R: Reader>(bytes: &mut R) -> Result<Vec<u8>, Error> {
+    let (len, len_len) = read_byte_prefix::<L, R>(bytes)?;
+    // Read the content in chunks such that a bogus length prefix doesn't
+    // allocate more memory than the input provides.
+    let mut content = Vec::new();
+    while content.len() < len {
+        let start = content.len();
+        content.resize(core::cmp::min(len, start + READ_CHUNK_LEN), 0);
+        bytes
+            .read_bytes(&mut content[start..])
+            .map_err(|e| e.with_offset(len_len))?;
+    }
+    Ok(content)
+}
 
+/// Serialize a vector of `elements` with a length prefix `L` to `writer`, like
+/// [`serialize_elements`].
+#[cfg(feature = "async")]
+async fn serialize_elements_async<T, L, W>(elements: &[T], writer: &mut W) -> Result<usize, Error>
+where
+    T: AsyncSerialize,
+    L: LengthPrefix + AsyncSerialize + Send,
+    W: AsyncWriter,
+{
+    if let (Some(position), Some(len_len)) = (writer.position(), patched_len_len::<T, L>()) {
+        let mut prefix = [0u8; MAX_PATCHED_LEN_LEN];
+        writer.write_bytes(&prefix[..len_len]).await?;
+        let mut len = 0usize;
+        for e in elements {
+            len = add_len(len, e.tls_serialize_async(writer).await?)?;
+        }
+        let len_len = L::from_len(len)?.tls_serialize_to_slice(&mut prefix[..len_len])?;
+        writer.patch_bytes(position, &prefix[..len_len])?;
+        return add_len(len, len_len);
+    }
+
+    let len = streamed_content_len(elements)?;
+    let mut written = L::from_len(len)?.tls_serialize_async(writer).await?;
+    let expected = add_len(written, len)?;
+    for e in elements {
+        written += e.tls_serialize_async(writer).await?;
+    }
+    check_written(expected, written)
+}
+
+#[cfg(feature = "async")]
+async fn deserialize_elements_async<T, L, R>(bytes: &mut R) -> Result<Vec<T>, Error>
+where
+    T: AsyncDeserialize,
+    L: LengthPrefix + AsyncDeserialize,
+    R: AsyncReader,
+{
+    let prefix = L::tls_deserialize_async(bytes).await?;
+    let len_len = prefix.tls_serialized_len();
+    let len = prefix.to_len()?;
+    limits::check_vector_len(bytes.budget(), len)?;
+    let mut vec = Vec::new();
+    preallocate(&mut vec, len)?;
+    let mut read = len_len;
+    while (read - len_len) < len {
+        limits::allocate(bytes.budget(), core::mem::size_of::<T>(), 1)
+            .map_err(|e| e.with_index(read, vec.len()))?;
+        let element = T::tls_deserialize_async(bytes)
+            .await
+            .map_err(|e| e.with_index(read, vec.len()))?;
+        read += element_len(&element).map_err(|e| e.with_index(read, vec.len()))?;
+        vec.push(element);
+    }
+    check_read(len, read - len_len)?;
+    Ok(vec)
+}
+
+#[cfg(feature = "async")]
+async fn serialize_bytes_async<L, W>(content: &[u8], writer: &mut W) -> Result<usize, Error>
+where
+    L: LengthPrefix + AsyncSerialize + Send,
+    W: AsyncWriter,
+{
+    let written = L::from_len(content.len())?
+        .tls_serialize_async(writer)
+        .await?;
+    writer.write_bytes(content).await?;
+    Ok(written + content.len())
+}
+
+#[cfg(feature = "async")]
+async fn deserialize_bytes_async<L, R>(bytes: &mut R) -> Result<Vec<u8>, Error>
+where
+    L: LengthPrefix + AsyncDeserialize,
+    R: AsyncReader,
+{
+    let prefix = L::tls_deserialize_async(bytes).await?;
+    let len_len = prefix.tls_serialized_len();
+    let len = prefix.to_len()?;
+    limits::check_vector_len(bytes.budget(), len)?;
+    limits::allocate(bytes.budget(), len, 0)?;
+    let mut content = Vec::new();
+    while content.len() < len {
+        let start = content.len();
+        content.resize(core::cmp::min(len, start + READ_CHUNK_LEN), 0);
+        bytes
+            .read_bytes(&mut content[start..])
+            .await
+            .map_err(|e| e.with_offset(len_len))?;
+    }
+    Ok(content)
+}
+
+/// Serialize the content of a vector as struct `name` with the field `vec`.
 #[cfg(feature = "serde_serialize")]
-use serde::ser::SerializeStruct;
-use zeroize::Zeroize;
+fn serialize_vec<S, T>(serializer: S, name: &'static str, vec: &[T]) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+    T: serde::Serialize,
+{
+    let mut state = serializer.serialize_struct(name, 1)?;
+    state.serialize_field("vec", vec)?;
+    state.end()
+}
+
+/// Deserialize the content of a vector from struct `name` with the field
+/// `vec`, which is expected as `struct {name}{params}`.
+#[cfg(feature = "serde_serialize")]
+fn deserialize_vec<'de, D, T>(
+    deserializer: D,
+    name: &'static str,
+    params: &'static str,
+) -> Result<Vec<T>, D::Error>
+where
+    D: serde::de::Deserializer<'de>,
+    T: serde::de::Deserialize<'de>,
+{
+    enum Field {
+        Vec,
+    }
+
+    impl<'de> serde::de::Deserialize<'de> for Field {
+        fn deserialize<D>(deserializer: D) -> Result<Field, D::Error>
+        where
+            D: serde::de::Deserializer<'de>,
+        {
+            struct FieldVisitor;
+
+            impl<'de> serde::de::Visitor<'de> for FieldVisitor {
+                type Value = Field;
+
+                fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+                    formatter.write_str("`vec`")
+                }
+
+                fn visit_str<E>(self, value: &str) -> Result<Field, E>
+                where
+                    E: serde::de::Error,
+                {
+                    match value {
+                        "vec" => Ok(Field::Vec),
+                        _ => Err(serde::de::Error::unknown_field(value, &["vec"])),
+                    }
+                }
+            }
+
+            deserializer.deserialize_identifier(FieldVisitor)
+        }
+    }
+
+    struct TlsVecVisitor<T> {
+        name: &'static str,
+        params: &'static str,
+        data: PhantomData<T>,
+    }
+
+    impl<'de, T> serde::de::Visitor<'de> for TlsVecVisitor<T>
+    where
+        T: serde::de::Deserialize<'de>,
+    {
+        type Value = Vec<T>;
+        fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+            formatter.write_fmt(format_args!("struct {}{}", self.name, self.params))
+        }
+        fn visit_seq<V>(self, mut seq: V) -> Result<Vec<T>, V::Error>
+        where
+            V: serde::de::SeqAccess<'de>,
+        {
+            seq.next_element()?
+                .ok_or_else(|| serde::de::Error::invalid_length(0, &self))
+        }
+        fn visit_map<V>(self, mut map: V) -> Result<Vec<T>, V::Error>
+        where
+            V: serde::de::MapAccess<'de>,
+        {
+            let mut vec = None;
+            while let Some(key) = map.next_key()? {
+                match key {
+                    Field::Vec => {
+                        if vec.is_some() {
+                            return Err(serde::de::Error::duplicate_field("vec"));
+                        }
+                        vec = Some(map.next_value()?);
+                    }
+                }
+            }
+            vec.ok_or_else(|| serde::de::Error::missing_field("vec"))
+        }
+    }
+
+    deserializer.deserialize_struct(
+        name,
+        &["vec"],
+        TlsVecVisitor {
+            name,
+            params,
+            data: PhantomData,
+        },
+    )
+}
+
+/// Implement the members and standard traits shared by the owned vectors
+/// `$name` with the generic parameters `$params`, declared as `$generics`, and
+/// elements `$element`.
+/// They are named [`VectorNames`]`::$kind` of their prefix, and expected as
+/// `struct {name}$type_params` by serde.
+macro_rules! impl_vec_common {
+    ($name:ident, $kind:ident, $type_params:literal, [$($generics:tt)*], [$($params:ident),*], $element:ty) => {
+        impl<$($generics)*> $name<$($params),*> {
+            /// Create a new vector from a Rust Vec.
+            #[inline]
+            pub fn new(vec: Vec<$element>) -> Self {
+                Self {
+                    vec,
+                    prefix: PhantomData,
+                }
+            }
+
+            /// Create a new vector from a slice.
+            #[inline]
+            pub fn from_slice(slice: &[$element]) -> Self
+            where
+                $element: Clone,
+            {
+                Self::new(slice.to_vec())
+            }
+
+            /// Get the length of the vector.
+            #[inline]
+            pub fn len(&self) -> usize {
+                self.vec.len()
+            }
+
+            /// Get a slice to the raw vector.
+            #[inline]
+            pub fn as_slice(&self) -> &[$element] {
+                &self.vec
+            }
+
+            /// Check if the vector is empty.
+            #[inline]
+            pub fn is_empty(&self) -> bool {
+                self.vec.is_empty()
+            }
+
+            /// Get the underlying vector and consume this.
+            #[inline]
+            pub fn into_vec(mut self) -> Vec<$element> {
+                core::mem::take(&mut self.vec)
+            }
+
+            /// Add an element to this.
+            #[inline]
+            pub fn push(&mut self, value: $element) {
+                self.vec.push(value);
+            }
+
+            /// Remove the last element.
+            #[inline]
+            pub fn pop(&mut self) -> Option<$element> {
+                self.vec.pop()
+            }
+
+            /// Remove the element at `index`.
+            #[inline]
+            pub fn remove(&mut self, index: usize) -> $element {
+                self.vec.remove(index)
+            }
+
+            /// Returns a reference to an element or subslice depending on the type of index.
+            /// XXX: implement SliceIndex instead
+            #[inline]
+            pub fn get(&self, index: usize) -> Option<&$element> {
+                self.vec.get(index)
+            }
+
+            /// Returns an iterator over the slice.
+            #[inline]
+            pub fn iter(&self) -> core::slice::Iter<'_, $element> {
+                self.vec.iter()
+            }
+
+            /// Retains only the elements specified by the predicate.
+            #[inline]
+            pub fn retain<F>(&mut self, f: F)
+            where
+                F: FnMut(&$element) -> bool,
+            {
+                self.vec.retain(f)
+            }
+        }
+
+        impl<$($generics)*> $name<$($params),*>
+        where
+            L: FixedSize,
+        {
+            /// Get the number of bytes used for the length encoding.
+            #[inline(always)]
+            pub fn len_len() -> usize {
+                L::TLS_LEN
+            }
+        }
+
+        impl<$($generics)*> Clone for $name<$($params),*>
+        where
+            $element: Clone,
+        {
+            fn clone(&self) -> Self {
+                Self::new(self.vec.clone())
+            }
+        }
+
+        impl<$($generics)*> core::fmt::Debug for $name<$($params),*>
+        where
+            $element: core::fmt::Debug,
+        {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                f.debug_struct(L::NAMES.$kind)
+                    .field("vec", &self.vec)
+                    .finish()
+            }
+        }
+
+        impl<$($generics)*> core::hash::Hash for $name<$($params),*>
+        where
+            $element: core::hash::Hash,
+        {
+            #[inline]
+            fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+                self.vec.hash(state)
+            }
+        }
+
+        impl<$($generics)*> core::cmp::PartialEq for $name<$($params),*>
+        where
+            $element: core::cmp::PartialEq,
+        {
+            fn eq(&self, other: &Self) -> bool {
+                self.vec.eq(&other.vec)
+            }
+        }
+
+        impl<$($generics)*> core::cmp::Eq for $name<$($params),*> where $element: core::cmp::Eq {}
+
+        impl<$($generics)*> core::ops::Index<usize> for $name<$($params),*> {
+            type Output = $element;
+
+            #[inline]
+            fn index(&self, i: usize) -> &$element {
+                self.vec.index(i)
+            }
+        }
+
+        impl<$($generics)*> core::ops::IndexMut<usize> for $name<$($params),*> {
+            #[inline]
+            fn index_mut(&mut self, i: usize) -> &mut Self::Output {
+                self.vec.index_mut(i)
+            }
+        }
+
+        impl<$($generics)*> core::borrow::Borrow<[$element]> for $name<$($params),*> {
+            #[inline]
+            fn borrow(&self) -> &[$element] {
+                &self.vec
+            }
+        }
+
+        impl<$($generics)*> core::iter::FromIterator<$element> for $name<$($params),*> {
+            #[inline]
+            fn from_iter<I>(iter: I) -> Self
+            where
+                I: IntoIterator<Item = $element>,
+            {
+                Self::new(Vec::from_iter(iter))
+            }
+        }
+
+        impl<$($generics)*> From<Vec<$element>> for $name<$($params),*> {
+            #[inline]
+            fn from(v: Vec<$element>) -> Self {
+                Self::new(v)
+            }
+        }
+
+        impl<$($generics)*> From<&[$element]> for $name<$($params),*>
+        where
+            $element: Clone,
+        {
+            #[inline]
+            fn from(v: &[$element]) -> Self {
+                Self::from_slice(v)
+            }
+        }
+
+        impl<$($generics)*> From<$name<$($params),*>> for Vec<$element> {
+            #[inline]
+            fn from(v: $name<$($params),*>) -> Self {
+                v.into_vec()
+            }
+        }
+
+        impl<$($generics)*> Default for $name<$($params),*> {
+            #[inline]
+            fn default() -> Self {
+                Self::new(Vec::new())
+            }
+        }
+
+        #[cfg(feature = "serde_serialize")]
+        impl<$($generics)*> serde::Serialize for $name<$($params),*>
+        where
+            $element: serde::Serialize,
+        {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+            {
+                serialize_vec(serializer, L::NAMES.$kind, &self.vec)
+            }
+        }
+
+        #[cfg(feature = "serde_serialize")]
+        impl<'de, $($generics)*> serde::de::Deserialize<'de> for $name<$($params),*>
+        where
+            $element: serde::de::Deserialize<'de>,
+        {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: serde::de::Deserializer<'de>,
+            {
+                deserialize_vec(deserializer, L::NAMES.$kind, $type_params).map(Self::new)
+            }
+        }
+    };
+}
+
+/// Implement the codec for the vectors `$name` of elements `T` with the
+/// additional bounds `$bounds` on `T`.
+macro_rules! impl_tls_vec_codec {
+    ($name:ident $(, $bounds:path)*) => {
+        impl<T: Size $(+ $bounds)*, L: LengthPrefix> Size for $name<T, L> {
+            #[inline]
+            fn tls_serialized_len(&self) -> usize {
+                vector_len::<L>(content_len(&self.vec))
+            }
+
+            #[inline]
+            fn tls_serialized_len_checked(&self) -> Result<usize, Error> {
+                vector_len_checked::<L>(content_len_checked(&self.vec)?)
+            }
+        }
+
+        impl<T: Serialize $(+ $bounds)*, L: LengthPrefix> Serialize for $name<T, L> {
+            fn tls_serialize<W: Writer>(&self, writer: &mut W) -> Result<usize, Error> {
+                serialize_elements::<T, L, W, _>(&self.vec, writer, Plain)
+            }
+
+            fn tls_serialize_vectored<'v>(
+                &'v self,
+                writer: &mut VectoredWriter<'v>,
+            ) -> Result<usize, Error> {
+                serialize_elements_vectored::<T, L>(&self.vec, writer)
+            }
+        }
+
+        impl<T: Deserialize $(+ $bounds)*, L: LengthPrefix> Deserialize for $name<T, L> {
+            fn tls_deserialize<R: Reader>(bytes: &mut R) -> Result<Self, Error> {
+                deserialize_elements::<T, L, R>(bytes, |bytes| T::tls_deserialize(bytes)).map(Self::new)
+            }
+        }
+
+        impl<'a, T: DeserializeBytes<'a> $(+ $bounds)*, L: LengthPrefix> DeserializeBytes<'a>
+            for $name<T, L>
+        {
+            fn tls_deserialize_bytes(bytes: &'a [u8]) -> Result<(Self, &'a [u8]), Error> {
+                let (vec, remainder) = deserialize_bytes_elements::<T, L>(bytes)?;
+                Ok((Self::new(vec), remainder))
+            }
+        }
+
+        impl<C: ?Sized, T: SerializeWithContext<C> $(+ $bounds)*, L: LengthPrefix>
+            SerializeWithContext<C> for $name<T, L>
+        {
+            fn tls_serialize_with_context<W: Writer>(
+                &self,
+                writer: &mut W,
+                context: &C,
+            ) -> Result<usize, Error> {
+                serialize_elements::<T, L, W, _>(&self.vec, writer, WithContext(context))
+            }
+        }
+
+        impl<C: ?Sized, T: DeserializeWithContext<C> $(+ $bounds)*, L: LengthPrefix>
+            DeserializeWithContext<C> for $name<T, L>
+        {
+            fn tls_deserialize_with_context<R: Reader>(
+                bytes: &mut R,
+                context: &C,
+            ) -> Result<Self, Error> {
+                deserialize_elements::<T, L, R>(bytes, |bytes| {
+                    T::tls_deserialize_with_context(bytes, context)
+                })
+                .map(Self::new)
+            }
+        }
+
+        #[cfg(feature = "async")]
+        impl<T: AsyncSerialize $(+ $bounds)*, L: LengthPrefix + AsyncSerialize + Send> AsyncSerialize
+            for $name<T, L>
+        {
+            async fn tls_serialize_async<W: AsyncWriter>(&self, writer: &mut W) -> Result<usize, Error> {
+                serialize_elements_async::<T, L, W>(&self.vec, writer).await
+            }
+        }
+
+        #[cfg(feature = "async")]
+        impl<T: AsyncDeserialize $(+ $bounds)*, L: LengthPrefix + AsyncDeserialize> AsyncDeserialize
+            for $name<T, L>
+        {
+            async fn tls_deserialize_async<R: AsyncReader>(bytes: &mut R) -> Result<Self, Error> {
+                deserialize_elements_async::<T, L, R>(bytes).await.map(Self::new)
+            }
+        }
+    };
+}
+
+/// A vector of elements `T` with a length prefix `L`, i.e. `T data<0..2^N-1>`
+/// for a prefix of `N` bits.
+///
+/// Use the aliases such as [`TlsVecU16`] for the prefixes of TLS, or any other
+/// [`LengthPrefix`].
+///
+/// ```
+/// use tls_codec::{Serialize, TlsVec, TlsVecU16};
+///
+/// let v = TlsVec::<u8, u16>::from_slice(&[1, 2]);
+/// assert_eq!(vec![0u8, 2, 1, 2], v.tls_serialize_detached().unwrap());
+/// assert_eq!(TlsVecU16::from_slice(&[1u8, 2]), v);
+/// ```
+pub struct TlsVec<T, L: LengthPrefix> {
+    vec: Vec<T>,
+    prefix: PhantomData<L>,
+}
+
+impl_vec_common!(TlsVec, vec, "<T>", [T, L: LengthPrefix], [T, L], T);
+impl_tls_vec_codec!(TlsVec);
+
+impl<T: Zeroize, L: LengthPrefix> Zeroize for TlsVec<T, L> {
+    fn zeroize(&mut self) {
+        self.vec.zeroize()
+    }
+}
+
+/// A [`TlsVec`] for secrets, which zeroizes its elements on drop.
+pub struct SecretTlsVec<T: Zeroize, L: LengthPrefix> {
+    vec: Vec<T>,
+    prefix: PhantomData<L>,
+}
+
+impl_vec_common!(SecretTlsVec, secret_vec, "<T>", [T: Zeroize, L: LengthPrefix], [T, L], T);
+impl_tls_vec_codec!(SecretTlsVec, Zeroize);
+
+impl<T: Zeroize, L: LengthPrefix> Zeroize for SecretTlsVec<T, L> {
+    fn zeroize(&mut self) {
+        self.vec.zeroize()
+    }
+}
+
+impl<T: Zeroize, L: LengthPrefix> Drop for SecretTlsVec<T, L> {
+    fn drop(&mut self) {
+        self.zeroize()
+    }
+}
+
+/// A byte vector with a length prefix `L`, i.e. `opaque data<0..2^N-1>` for a
+/// prefix of `N` bits.
+///
+/// Unlike a [`TlsVec`] of `u8`, it reads and writes its content at once.
+/// It implements [`Zeroize`] but doesn't zeroize itself on drop.
+pub struct TlsByteVec<L: LengthPrefix> {
+    vec: Vec<u8>,
+    prefix: PhantomData<L>,
+}
+
+impl_vec_common!(TlsByteVec, byte_vec, "", [L: LengthPrefix], [L], u8);
+
+impl<L: LengthPrefix> Size for TlsByteVec<L> {
+    #[inline]
+    fn tls_serialized_len(&self) -> usize {
+        vector_len::<L>(self.vec.len())
+    }
+
+    #[inline]
+    fn tls_serialized_len_checked(&self) -> Result<usize, Error> {
+        vector_len_checked::<L>(self.vec.len())
+    }
+}
+
+impl<L: LengthPrefix> Serialize for TlsByteVec<L> {
+    fn tls_serialize<W: Writer>(&self, writer: &mut W) -> Result<usize, Error> {
+        serialize_bytes::<L, W>(&self.vec, writer)
+    }
+
+    #[inline]
+    fn tls_serialize_to_checked_slice(&self, out: &mut [u8]) -> Result<usize, Error> {
+        serialize_bytes_to_slice::<L>(&self.vec, out)
+    }
+
+    #[inline]
+    fn tls_serialize_vectored<'v>(
+        &'v self,
+        writer: &mut VectoredWriter<'v>,
+    ) -> Result<usize, Error> {
+        serialize_bytes_vectored::<L>(&self.vec, writer)
+    }
+}
+
+impl<L: LengthPrefix> Deserialize for TlsByteVec<L> {
+    fn tls_deserialize<R: Reader>(bytes: &mut R) -> Result<Self, Error> {
+        deserialize_bytes::<L, R>(bytes).map(Self::new)
+    }
+}
+
+impl<'a, L: LengthPrefix> DeserializeBytes<'a> for TlsByteVec<L> {
+    fn tls_deserialize_bytes(bytes: &'a [u8]) -> Result<(Self, &'a [u8]), Error> {
+        let (content, remainder, _) = split_content::<L>(bytes)?;
+        Ok((Self::from_slice(content), remainder))
+    }
+}
+
+impl<C: ?Sized, L: LengthPrefix> SerializeWithContext<C> for TlsByteVec<L> {
+    #[inline]
+    fn tls_serialize_with_context<W: Writer>(
+        &self,
+        writer: &mut W,
+        _context: &C,
+    ) -> Result<usize, Error> {
+        self.tls_serialize(writer)
+    }
+}
+
+impl<C: ?Sized, L: LengthPrefix> DeserializeWithContext<C> for TlsByteVec<L> {
+    #[inline]
+    fn tls_deserialize_with_context<R: Reader>(bytes: &mut R, _context: &C) -> Result<Self, Error> {
+        Self::tls_deserialize(bytes)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<L: LengthPrefix + AsyncSerialize + Send> AsyncSerialize for TlsByteVec<L> {
+    async fn tls_serialize_async<W: AsyncWriter>(&self, writer: &mut W) -> Result<usize, Error> {
+        serialize_bytes_async::<L, W>(&self.vec, writer).await
+    }
+}
+
+#[cfg(feature = "async")]
+impl<L: LengthPrefix + AsyncDeserialize> AsyncDeserialize for TlsByteVec<L> {
+    async fn tls_deserialize_async<R: AsyncReader>(bytes: &mut R) -> Result<Self, Error> {
+        deserialize_bytes_async::<L, R>(bytes).await.map(Self::new)
+    }
+}
+
+impl<L: LengthPrefix> Zeroize for TlsByteVec<L> {
+    fn zeroize(&mut self) {
+        self.vec.zeroize()
+    }
+}
+
+/// A byte vector with a length prefix `L` backed by [`bytes::Bytes`].
+///
+/// Decoding it from a [`BytesIo`](crate::BytesIo) over a [`bytes::Bytes`]
+/// slices the buffer instead of copying it.
+#[cfg(feature = "bytes")]
+#[derive(Clone, PartialEq, Eq, Hash, Default)]
+pub struct TlsBytes<L: LengthPrefix> {
+    bytes: bytes::Bytes,
+    prefix: PhantomData<L>,
+}
+
+#[cfg(feature = "bytes")]
+impl<L: LengthPrefix> core::fmt::Debug for TlsBytes<L> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct(L::NAMES.bytes)
+            .field("bytes", &self.bytes)
+            .finish()
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl<L: LengthPrefix> TlsBytes<L> {
+    /// Create a new `TlsBytes` from a [`bytes::Bytes`].
+    #[inline]
+    pub fn new(bytes: bytes::Bytes) -> Self {
+        Self {
+            bytes,
+            prefix: PhantomData,
+        }
+    }
+
+    /// Create a new `TlsBytes` from a slice.
+    #[inline]
+    pub fn from_slice(slice: &[u8]) -> Self {
+        Self::new(bytes::Bytes::copy_from_slice(slice))
+    }
+
+    /// Get the length of the vector.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// Check if the vector is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// Get a slice to the raw bytes.
+    #[inline]
+    pub fn as_slice(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Get the underlying [`bytes::Bytes`].
+    #[inline]
+    pub fn into_bytes(self) -> bytes::Bytes {
+        self.bytes
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl<L: LengthPrefix> From<bytes::Bytes> for TlsBytes<L> {
+    #[inline]
+    fn from(bytes: bytes::Bytes) -> Self {
+        Self::new(bytes)
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl<L: LengthPrefix> From<TlsBytes<L>> for bytes::Bytes {
+    #[inline]
+    fn from(v: TlsBytes<L>) -> Self {
+        v.bytes
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl<L: LengthPrefix> From<Vec<u8>> for TlsBytes<L> {
+    #[inline]
+    fn from(v: Vec<u8>) -> Self {
+        Self::new(v.into())
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl<L: LengthPrefix> From<&[u8]> for TlsBytes<L> {
+    #[inline]
+    fn from(v: &[u8]) -> Self {
+        Self::from_slice(v)
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl<L: LengthPrefix> Size for TlsBytes<L> {
+    #[inline]
+    fn tls_serialized_len(&self) -> usize {
+        vector_len::<L>(self.bytes.len())
+    }
+
+    #[inline]
+    fn tls_serialized_len_checked(&self) -> Result<usize, Error> {
+        vector_len_checked::<L>(self.bytes.len())
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl<L: LengthPrefix> Serialize for TlsBytes<L> {
+    fn tls_serialize<W: Writer>(&self, writer: &mut W) -> Result<usize, Error> {
+        serialize_bytes::<L, W>(&self.bytes, writer)
+    }
+
+    #[inline]
+    fn tls_serialize_to_checked_slice(&self, out: &mut [u8]) -> Result<usize, Error> {
+        serialize_bytes_to_slice::<L>(&self.bytes, out)
+    }
+
+    #[inline]
+    fn tls_serialize_vectored<'v>(
+        &'v self,
+        writer: &mut VectoredWriter<'v>,
+    ) -> Result<usize, Error> {
+        serialize_bytes_vectored::<L>(&self.bytes, writer)
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl<L: LengthPrefix> Deserialize for TlsBytes<L> {
+    fn tls_deserialize<R: Reader>(bytes: &mut R) -> Result<Self, Error> {
+        let (len, len_len) = read_byte_prefix::<L, R>(bytes)?;
+        let content = bytes
+            .read_shared_bytes(len)
+            .map_err(|e| e.with_offset(len_len))?;
+        Ok(Self::new(content))
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl<'a, L: LengthPrefix> DeserializeBytes<'a> for TlsBytes<L> {
+    fn tls_deserialize_bytes(bytes: &'a [u8]) -> Result<(Self, &'a [u8]), Error> {
+        let (content, remainder, _) = split_content::<L>(bytes)?;
+        Ok((Self::from_slice(content), remainder))
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl<C: ?Sized, L: LengthPrefix> SerializeWithContext<C> for TlsBytes<L> {
+    #[inline]
+    fn tls_serialize_with_context<W: Writer>(
+        &self,
+        writer: &mut W,
+        _context: &C,
+    ) -> Result<usize, Error> {
+        self.tls_serialize(writer)
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl<C: ?Sized, L: LengthPrefix> DeserializeWithContext<C> for TlsBytes<L> {
+    #[inline]
+    fn tls_deserialize_with_context<R: Reader>(bytes: &mut R, _context: &C) -> Result<Self, Error> {
+        Self::tls_deserialize(bytes)
+    }
+}
+
+#[cfg(all(feature = "bytes", feature = "async"))]
+impl<L: LengthPrefix + AsyncSerialize + Send> AsyncSerialize for TlsBytes<L> {
+    async fn tls_serialize_async<W: AsyncWriter>(&self, writer: &mut W) -> Result<usize, Error> {
+        serialize_bytes_async::<L, W>(&self.bytes, writer).await
+    }
+}
+
+#[cfg(all(feature = "bytes", feature = "async"))]
+impl<L: LengthPrefix + AsyncDeserialize> AsyncDeserialize for TlsBytes<L> {
+    async fn tls_deserialize_async<R: AsyncReader>(bytes: &mut R) -> Result<Self, Error> {
+        let content = deserialize_bytes_async::<L, R>(bytes).await?;
+        Ok(Self::new(content.into()))
+    }
+}
+
+// We also implement shallow serialization for slices
+
+/// A borrowed byte slice that is serialized like a [`TlsByteVec`] with a
+/// length prefix `L`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TlsByteSlice<'a, L: LengthPrefix>(pub &'a [u8], PhantomData<L>);
+
+impl<'a, L: LengthPrefix> core::fmt::Debug for TlsByteSlice<'a, L> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple(L::NAMES.byte_slice).field(&self.0).finish()
+    }
+}
+
+impl<'a, L: LengthPrefix> TlsByteSlice<'a, L> {
+    /// Create a new `TlsByteSlice` that serializes `slice`.
+    #[inline]
+    pub fn new(slice: &'a [u8]) -> Self {
+        Self(slice, PhantomData)
+    }
+
+    /// Get the underlying slice.
+    #[inline]
+    pub fn as_slice(&self) -> &'a [u8] {
+        self.0
+    }
+}
+
+impl<'a, L: LengthPrefix> From<&'a [u8]> for TlsByteSlice<'a, L> {
+    #[inline]
+    fn from(slice: &'a [u8]) -> Self {
+        Self::new(slice)
+    }
+}
+
+impl<'a, L: LengthPrefix> Size for TlsByteSlice<'a, L> {
+    #[inline]
+    fn tls_serialized_len(&self) -> usize {
+        vector_len::<L>(self.0.len())
+    }
+
+    #[inline]
+    fn tls_serialized_len_checked(&self) -> Result<usize, Error> {
+        vector_len_checked::<L>(self.0.len())
+    }
+}
+
+impl<'a, L: LengthPrefix> Serialize for TlsByteSlice<'a, L> {
+    fn tls_serialize<W: Writer>(&self, writer: &mut W) -> Result<usize, Error> {
+        serialize_bytes::<L, W>(self.0, writer)
+    }
+
+    #[inline]
+    fn tls_serialize_to_checked_slice(&self, out: &mut [u8]) -> Result<usize, Error> {
+        serialize_bytes_to_slice::<L>(self.0, out)
+    }
+
+    #[inline]
+    fn tls_serialize_vectored<'v>(
+        &'v self,
+        writer: &mut VectoredWriter<'v>,
+    ) -> Result<usize, Error> {
+        serialize_bytes_vectored::<L>(self.0, writer)
+    }
+}
+
+impl<'a, C: ?Sized, L: LengthPrefix> SerializeWithContext<C> for TlsByteSlice<'a, L> {
+    #[inline]
+    fn tls_serialize_with_context<W: Writer>(
+        &self,
+        writer: &mut W,
+        _context: &C,
+    ) -> Result<usize, Error> {
+        self.tls_serialize(writer)
+    }
+}
+
+impl<'a, L: LengthPrefix> DeserializeBytes<'a> for TlsByteSlice<'a, L> {
+    fn tls_deserialize_bytes(bytes: &'a [u8]) -> Result<(Self, &'a [u8]), Error> {
+        let (content, remainder, _) = split_content::<L>(bytes)?;
+        Ok((Self::new(content), remainder))
+    }
+}
+
+#[cfg(feature = "async")]
+impl<'a, L: LengthPrefix + AsyncSerialize + Send> AsyncSerialize for TlsByteSlice<'a, L> {
+    async fn tls_serialize_async<W: AsyncWriter>(&self, writer: &mut W) -> Result<usize, Error> {
+        serialize_bytes_async::<L, W>(self.0, writer).await
+    }
+}
+
+/// A borrowed slice that is serialized like a [`TlsVec`] with a length prefix
+/// `L`.
+pub struct TlsSlice<'a, T, L: LengthPrefix>(pub &'a [T], PhantomData<L>);
+
+impl<'a, T, L: LengthPrefix> TlsSlice<'a, T, L> {
+    /// Create a new `TlsSlice` that serializes `slice`.
+    #[inline]
+    pub fn new(slice: &'a [T]) -> Self {
+        Self(slice, PhantomData)
+    }
+
+    /// Get the underlying slice.
+    #[inline]
+    pub fn as_slice(&self) -> &'a [T] {
+        self.0
+    }
+}
+
+impl<'a, T, L: LengthPrefix> From<&'a [T]> for TlsSlice<'a, T, L> {
+    #[inline]
+    fn from(slice: &'a [T]) -> Self {
+        Self::new(slice)
+    }
+}
 
-use crate::{Deserialize, Error, Serialize, Size};
+impl<'a, T: Size, L: LengthPrefix> Size for TlsSlice<'a, T, L> {
+    #[inline]
+    fn tls_serialized_len(&self) -> usize {
+        vector_len::<L>(content_len(self.0))
+    }
 
-macro_rules! impl_size {
-    ($self:ident, $size:ty, $name:ident, $len_len:literal) => {
-        /// The serialized len
-        #[inline(always)]
-        fn tls_serialized_length(&$self) -> usize {
-            $self.as_slice()
-                .iter()
-                .fold($len_len, |acc, e| acc + e.tls_serialized_len())
-        }
+    #[inline]
+    fn tls_serialized_len_checked(&self) -> Result<usize, Error> {
+        vector_len_checked::<L>(content_len_checked(self.0)?)
     }
 }
 
-macro_rules! impl_byte_size {
-    ($self:ident, $size:ty, $name:ident, $len_len:literal) => {
-        /// The serialized len
-        #[inline(always)]
-        fn tls_serialized_byte_length(&$self) -> usize {
-            $self.as_slice().len() + $len_len
-        }
+impl<'a, T: Serialize, L: LengthPrefix> Serialize for TlsSlice<'a, T, L> {
+    fn tls_serialize<W: Writer>(&self, writer: &mut W) -> Result<usize, Error> {
+        serialize_elements::<T, L, W, _>(self.0, writer, Plain)
     }
-}
 
-macro_rules! impl_byte_deserialize {
-    ($self:ident, $size:ty, $name:ident, $len_len:literal) => {
-        #[inline(always)]
-        fn deserialize_bytes<R: Read>(bytes: &mut R) -> Result<Self, Error> {
-            let len = <$size>::tls_deserialize(bytes)? as usize;
-            // When fuzzing we limit the maximum size to allocate.
-            // XXX: We should think about a configurable limit for the allocation
-            //      here.
-            if cfg!(fuzzing) && len > u16::MAX as usize {
-                return Err(Error::DecodingError(format!(
-                    "Trying to allocate {} bytes. Only {} allowed.",
-                    len,
-                    u16::MAX
-                )));
-            }
-            let mut result = Self {
-                vec: vec![0u8; len],
-            };
-            let read = bytes.read(result.vec.as_mut_slice())?;
-            if read != len {
-                if !cfg!(fuzzing) {
-                    // When fuzzing we don't want to assert here because it would
-                    // crash all the time. Throwing an error is sufficient.
-                    debug_assert_eq!(
-                        read, len,
-                        "Expected to read {} bytes but {} were read.",
-                        len, read
-                    );
-                }
-                Err(Error::DecodingError(format!(
-                    "{} bytes were read but {} were expected",
-                    read, len
-                )))
-            } else {
-                Ok(result)
-            }
-        }
-    };
+    fn tls_serialize_vectored<'v>(
+        &'v self,
+        writer: &mut VectoredWriter<'v>,
+    ) -> Result<usize, Error> {
+        serialize_elements_vectored::<T, L>(self.0, writer)
+    }
 }
 
-macro_rules! impl_deserialize {
-    ($self:ident, $size:ty, $name:ident, $len_len:literal) => {
-        #[inline(always)]
-        fn deserialize<R: Read>(bytes: &mut R) -> Result<Self, Error> {
-            let mut result = Self { vec: Vec::new() };
-            let len = <$size>::tls_deserialize(bytes)?;
-            let mut read = len.tls_serialized_len();
-            let len_len = read;
-            while (read - len_len) < len as usize {
-                let element = T::tls_deserialize(bytes)?;
-                read += element.tls_serialized_len();
-                result.push(element);
-            }
-            Ok(result)
-        }
-    };
+impl<'a, C: ?Sized, T: SerializeWithContext<C>, L: LengthPrefix> SerializeWithContext<C>
+    for TlsSlice<'a, T, L>
+{
+    fn tls_serialize_with_context<W: Writer>(
+        &self,
+        writer: &mut W,
+        context: &C,
+    ) -> Result<usize, Error> {
+        serialize_elements::<T, L, W, _>(self.0, writer, WithContext(context))
+    }
 }
 
-macro_rules! impl_serialize {
-    ($self:ident, $size:ty, $name:ident, $len_len:literal) => {
-        #[inline(always)]
-        fn serialize<W: Write>(&$self, writer: &mut W) -> Result<usize, Error> {
-            // Get the byte length of the content, make sure it's not too
-            // large and write it out.
-            let tls_serialized_len = $self.tls_serialized_len();
-            let byte_length = tls_serialized_len - $len_len;
-
-            let max_len = <$size>::MAX as usize;
-            debug_assert!(
-                byte_length <= max_len,
-                "Vector length can't be encoded in the vector length a {} >= {}",
-                byte_length,
-                max_len
-            );
-            if byte_length > max_len {
-                return Err(Error::InvalidVectorLength);
-            }
-
-            let mut written = (byte_length as $size).tls_serialize(writer)?;
+#[cfg(feature = "async")]
+impl<'a, T: AsyncSerialize, L: LengthPrefix + AsyncSerialize + Send> AsyncSerialize
+    for TlsSlice<'a, T, L>
+{
+    async fn tls_serialize_async<W: AsyncWriter>(&self, writer: &mut W) -> Result<usize, Error> {
+        serialize_elements_async::<T, L, W>(self.0, writer).await
+    }
+}
 
-            // Now serialize the elements
-            for e in $self.as_slice().iter() {
-                written += e.tls_serialize(writer)?;
-            }
+// Byte slices, byte vectors, and strings are encoded like `VLBytes`, i.e.
+// with a variable-length integer prefix as `opaque<V>` in MLS.
 
-            debug_assert_eq!(
-                written, tls_serialized_len,
-                "{} bytes should have been serialized but {} were written",
-                tls_serialized_len, written
-            );
-            if written != tls_serialized_len {
-                return Err(Error::EncodingError(format!(
-                    "{} bytes should have been serialized but {} were written",
-                    tls_serialized_len, written
-                )));
-            }
-            Ok(written)
-        }
-    };
+/// Decode the content of a string.
+#[inline]
+fn string_from_utf8(content: Vec<u8>) -> Result<String, Error> {
+    String::from_utf8(content).map_err(|e| Error::InvalidUtf8 {
+        valid_up_to: e.utf8_error().valid_up_to(),
+    })
 }
 
-macro_rules! impl_byte_serialize {
-    ($self:ident, $size:ty, $name:ident, $len_len:literal) => {
-        #[inline(always)]
-        fn serialize_bytes<W: Write>(&$self, writer: &mut W) -> Result<usize, Error> {
-            // Get the byte length of the content, make sure it's not too
-            // large and write it out.
-            let tls_serialized_len = $self.tls_serialized_len();
-            let byte_length = tls_serialized_len - $len_len;
-
-            let max_len = <$size>::MAX as usize;
-            debug_assert!(
-                byte_length <= max_len,
-                "Vector length can't be encoded in the vector length a {} >= {}",
-                byte_length,
-                max_len
-            );
-            if byte_length > max_len {
-                return Err(Error::InvalidVectorLength);
-            }
-
-            let mut written = (byte_length as $size).tls_serialize(writer)?;
-
-            // Now serialize the elements
-            written += writer.write($self.as_slice())?;
-
-            debug_assert_eq!(
-                written, tls_serialized_len,
-                "{} bytes should have been serialized but {} were written",
-                tls_serialized_len, written
-            );
-            if written != tls_serialized_len {
-                return Err(Error::EncodingError(format!(
-                    "{} bytes should have been serialized but {} were written",
-                    tls_serialized_len, written
-                )));
-            }
-            Ok(written)
-        }
-    };
+/// Decode the content of a borrowed string.
+#[inline]
+fn str_from_utf8(content: &[u8]) -> Result<&str, Error> {
+    core::str::from_utf8(content).map_err(|e| Error::InvalidUtf8 {
+        valid_up_to: e.valid_up_to(),
+    })
 }
 
-macro_rules! impl_tls_vec_codec_generic {
-    ($size:ty, $name:ident, $len_len: literal, $($bounds:ident),*) => {
-        impl<T: $($bounds + )*> Serialize
-            for $name<T>
-        {
-            fn tls_serialize<W: Write>(&self, writer: &mut W) -> Result<usize, Error> {
-                self.serialize(writer)
-            }
-        }
-
-        impl<T: $($bounds + )*> Size
-            for $name<T>
-        {
+/// Implement the codec for the unsized `$unsized`, which is serialized as the
+/// bytes returned by `$bytes`, e.g. for `[u8]` and `str`.
+macro_rules! impl_unsized_bytes {
+    ($unsized:ty, $bytes:ident) => {
+        impl Size for $unsized {
             #[inline]
             fn tls_serialized_len(&self) -> usize {
-                self.tls_serialized_length()
-            }
-        }
-
-        impl<T: $($bounds + )*> Serialize
-            for &$name<T>
-        {
-            fn tls_serialize<W: Write>(&self, writer: &mut W) -> Result<usize, Error> {
-                self.serialize(writer)
+                vector_len::<VarInt>(self.len())
             }
-        }
 
-        impl<T: $($bounds + )*> Size
-            for &$name<T>
-        {
             #[inline]
-            fn tls_serialized_len(&self) -> usize {
-                self.tls_serialized_length()
-            }
-        }
-
-        impl<T: $($bounds + )*> Deserialize
-            for $name<T>
-        {
-            fn tls_deserialize<R: Read>(bytes: &mut R) -> Result<Self, Error> {
-                Self::deserialize(bytes)
+            fn tls_serialized_len_checked(&self) -> Result<usize, Error> {
+                vector_len_checked::<VarInt>(self.len())
             }
         }
-    }
-}
 
-macro_rules! impl_tls_vec_codec_bytes {
-    ($size:ty, $name:ident, $len_len: literal) => {
-        impl Serialize for $name {
-            fn tls_serialize<W: Write>(&self, writer: &mut W) -> Result<usize, Error> {
-                self.serialize_bytes(writer)
+        impl Serialize for $unsized {
+            fn tls_serialize<W: Writer>(&self, writer: &mut W) -> Result<usize, Error> {
+                serialize_bytes::<VarInt, W>(self.$bytes(), writer)
             }
-        }
 
-        impl Size for $name {
             #[inline]
-            fn tls_serialized_len(&self) -> usize {
-                self.tls_serialized_byte_length()
-            }
-        }
-
-        impl Serialize for &$name {
-            fn tls_serialize<W: Write>(&self, writer: &mut W) -> Result<usize, Error> {
-                self.serialize_bytes(writer)
+            fn tls_serialize_to_checked_slice(&self, out: &mut [u8]) -> Result<usize, Error> {
+                serialize_bytes_to_slice::<VarInt>(self.$bytes(), out)
             }
-        }
 
-        impl Size for &$name {
             #[inline]
-            fn tls_serialized_len(&self) -> usize {
-                self.tls_serialized_byte_length()
+            fn tls_serialize_vectored<'v>(
+                &'v self,
+                writer: &mut VectoredWriter<'v>,
+            ) -> Result<usize, Error> {
+                serialize_bytes_vectored::<VarInt>(self.$bytes(), writer)
             }
         }
 
-        impl Deserialize for $name {
-            fn tls_deserialize<R: Read>(bytes: &mut R) -> Result<Self, Error> {
-                Self::deserialize_bytes(bytes)
+        impl<C: ?Sized> SerializeWithContext<C> for $unsized {
+            #[inline]
+            fn tls_serialize_with_context<W: Writer>(
+                &self,
+                writer: &mut W,
+                _context: &C,
+            ) -> Result<usize, Error> {
+                self.tls_serialize(writer)
             }
         }
-    };
-}
-
-macro_rules! impl_vec_members {
-    ($element_type:ident, $len_len:literal) => {
-        /// Create a new `TlsVec` from a Rust Vec.
-        #[inline]
-        pub fn new(vec: Vec<$element_type>) -> Self {
-            Self { vec }
-        }
 
-        /// Create a new `TlsVec` from a slice.
-        #[inline]
-        pub fn from_slice(slice: &[$element_type]) -> Self
-        where
-            $element_type: Clone,
-        {
-            Self {
-                vec: slice.to_vec(),
+        #[cfg(feature = "async")]
+        impl AsyncSerialize for $unsized {
+            async fn tls_serialize_async<W: AsyncWriter>(
+                &self,
+                writer: &mut W,
+            ) -> Result<usize, Error> {
+                serialize_bytes_async::<VarInt, W>(self.$bytes(), writer).await
             }
         }
-
-        /// Get the length of the vector.
-        #[inline]
-        pub fn len(&self) -> usize {
-            self.vec.len()
-        }
-
-        /// Get a slice to the raw vector.
-        #[inline]
-        pub fn as_slice(&self) -> &[$element_type] {
-            &self.vec
-        }
-
-        /// Check if the vector is empty.
-        #[inline]
-        pub fn is_empty(&self) -> bool {
-            self.vec.is_empty()
-        }
-
-        /// Get the underlying vector and consume this.
-        #[inline]
-        pub fn into_vec(mut self) -> Vec<$element_type> {
-            std::mem::take(&mut self.vec)
-        }
-
-        /// Add an element to this.
-        #[inline]
-        pub fn push(&mut self, value: $element_type) {
-            self.vec.push(value);
-        }
-
-        /// Remove the last element.
-        #[inline]
-        pub fn pop(&mut self) -> Option<$element_type> {
-            self.vec.pop()
-        }
-
-        /// Remove the element at `index`.
-        #[inline]
-        pub fn remove(&mut self, index: usize) -> $element_type {
-            self.vec.remove(index)
-        }
-
-        /// Returns a reference to an element or subslice depending on the type of index.
-        /// XXX: implement SliceIndex instead
-        #[inline]
-        pub fn get(&self, index: usize) -> Option<&$element_type> {
-            self.vec.get(index)
-        }
-
-        /// Returns an iterator over the slice.
-        #[inline]
-        pub fn iter(&self) -> std::slice::Iter<'_, $element_type> {
-            self.vec.iter()
-        }
-
-        /// Retains only the elements specified by the predicate.
-        #[inline]
-        pub fn retain<F>(&mut self, f: F)
-        where
-            F: FnMut(&$element_type) -> bool,
-        {
-            self.vec.retain(f)
-        }
-
-        /// Get the number of bytes used for the length encoding.
-        #[inline(always)]
-        pub fn len_len() -> usize {
-            $len_len
-        }
     };
 }
 
-macro_rules! impl_tls_vec_generic {
-    ($size:ty, $name:ident, $len_len: literal, $($bounds:ident),*) => {
-        #[derive(Eq, Debug)]
-        pub struct $name<T: $($bounds + )*> {
-            vec: Vec<T>,
-        }
-
-        impl<T: Clone + $($bounds + )*> Clone for $name<T> {
-            fn clone(&self) -> Self {
-                Self::new(self.vec.clone())
-            }
-        }
-
-        impl<T: $($bounds + )*> $name<T> {
-            impl_vec_members!(T, $len_len);
-        }
+impl_unsized_bytes!([u8], as_ref);
+impl_unsized_bytes!(str, as_bytes);
 
-        impl<T: std::hash::Hash + $($bounds + )*> std::hash::Hash for $name<T> {
+/// Implement the codec for the owned `$owned` that dereferences to
+/// `$unsized`, which decodes its content with `$decode`, e.g. for `Vec<u8>`
+/// and `String`.
+macro_rules! impl_owned_bytes {
+    ($owned:ty, $unsized:ty, $decode:expr) => {
+        impl Size for $owned {
             #[inline]
-            fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-                self.vec.hash(state)
+            fn tls_serialized_len(&self) -> usize {
+                <$unsized>::tls_serialized_len(self)
             }
-        }
-
-        impl<T: $($bounds + )*> std::ops::Index<usize> for $name<T> {
-            type Output = T;
 
             #[inline]
-            fn index(&self, i: usize) -> &T {
-                self.vec.index(i)
-            }
-        }
-
-        impl<T: std::cmp::PartialEq + $($bounds + )*> std::cmp::PartialEq for $name<T> {
-            fn eq(&self, other: &Self) -> bool {
-                self.vec.eq(&other.vec)
+            fn tls_serialized_len_checked(&self) -> Result<usize, Error> {
+                <$unsized>::tls_serialized_len_checked(self)
             }
         }
 
-        impl<T: $($bounds + )*> std::ops::IndexMut<usize> for $name<T> {
+        impl Serialize for $owned {
             #[inline]
-            fn index_mut(&mut self, i: usize) -> &mut Self::Output {
-                self.vec.index_mut(i)
+            fn tls_serialize<W: Writer>(&self, writer: &mut W) -> Result<usize, Error> {
+                <$unsized>::tls_serialize(self, writer)
             }
-        }
 
-        impl<T: $($bounds + )*> std::borrow::Borrow<[T]> for $name<T> {
             #[inline]
-            fn borrow(&self) -> &[T] {
-                &self.vec
+            fn tls_serialize_to_checked_slice(&self, out: &mut [u8]) -> Result<usize, Error> {
+                <$unsized>::tls_serialize_to_checked_slice(self, out)
             }
-        }
 
-        impl<T: $($bounds + )*> std::iter::FromIterator<T> for  $name<T>  {
             #[inline]
-            fn from_iter<I>(iter: I) -> Self
-            where
-                I: IntoIterator<Item = T>, {
-                let vec = Vec::<T>::from_iter(iter);
-                Self{vec}
+            fn tls_serialize_vectored<'v>(
+                &'v self,
+                writer: &mut VectoredWriter<'v>,
+            ) -> Result<usize, Error> {
+                <$unsized>::tls_serialize_vectored(self, writer)
             }
         }
 
-        impl<T: $($bounds + )*> From<Vec<T>>
-            for $name<T>
-        {
-            #[inline]
-            fn from(v: Vec<T>) -> Self {
-                Self::new(v)
+        impl Deserialize for $owned {
+            fn tls_deserialize<R: Reader>(bytes: &mut R) -> Result<Self, Error> {
+                deserialize_bytes::<VarInt, R>(bytes).and_then($decode)
             }
         }
 
-        impl<T: Clone + $($bounds + )*> From<&[T]>
-            for $name<T>
-        {
-            #[inline]
-            fn from(v: &[T]) -> Self {
-                Self::from_slice(v)
+        impl<'a> DeserializeBytes<'a> for $owned {
+            fn tls_deserialize_bytes(bytes: &'a [u8]) -> Result<(Self, &'a [u8]), Error> {
+                let (content, remainder, _) = split_content::<VarInt>(bytes)?;
+                Ok(($decode(content.to_vec())?, remainder))
             }
         }
 
-        impl<T: $($bounds + )*> From<$name<T>>
-            for Vec<T>
-        {
+        impl<C: ?Sized> SerializeWithContext<C> for $owned {
             #[inline]
-            fn from(mut v: $name<T>) -> Self {
-                std::mem::take(&mut v.vec)
+            fn tls_serialize_with_context<W: Writer>(
+                &self,
+                writer: &mut W,
+                _context: &C,
+            ) -> Result<usize, Error> {
+                self.tls_serialize(writer)
             }
         }
 
-        impl<T: $($bounds + )*> Default
-            for $name<T>
-        {
+        impl<C: ?Sized> DeserializeWithContext<C> for $owned {
             #[inline]
-            fn default() -> Self {
-                Self { vec: Vec::new() }
+            fn tls_deserialize_with_context<R: Reader>(
+                bytes: &mut R,
+                _context: &C,
+            ) -> Result<Self, Error> {
+                Self::tls_deserialize(bytes)
             }
         }
 
-        #[cfg(feature = "serde_serialize")]
-        impl<T> serde::Serialize for $name<T>
-        where
-            T: $($bounds + )* serde::Serialize,
-        {
-            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-            where
-                S: serde::Serializer,
-            {
-                let mut state = serializer.serialize_struct(stringify!($name), 1)?;
-                state.serialize_field("vec", &self.vec)?;
-                state.end()
+        #[cfg(feature = "async")]
+        impl AsyncSerialize for $owned {
+            async fn tls_serialize_async<W: AsyncWriter>(
+                &self,
+                writer: &mut W,
+            ) -> Result<usize, Error> {
+                <$unsized>::tls_serialize_async(self, writer).await
             }
         }
 
-        #[cfg(feature = "serde_serialize")]
-        impl<'de, T> serde::de::Deserialize<'de> for $name<T>
-        where
-            T: Serialize
-                + Deserialize
-                + Size
-                + $($bounds + )*
-                serde::de::Deserialize<'de>,
-        {
-            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-            where
-                D: serde::de::Deserializer<'de>,
-            {
-                enum Field {
-                    Vec,
-                }
-
-                impl<'de> serde::de::Deserialize<'de> for Field {
-                    fn deserialize<D>(deserializer: D) -> Result<Field, D::Error>
-                    where
-                        D: serde::de::Deserializer<'de>,
-                    {
-                        struct FieldVisitor;
-
-                        impl<'de> serde::de::Visitor<'de> for FieldVisitor {
-                            type Value = Field;
-
-                            fn expecting(
-                                &self,
-                                formatter: &mut std::fmt::Formatter,
-                            ) -> std::fmt::Result {
-                                formatter.write_str("`vec`")
-                            }
-
-                            fn visit_str<E>(self, value: &str) -> Result<Field, E>
-                            where
-                                E: serde::de::Error,
-                            {
-                                match value {
-                                    "vec" => Ok(Field::Vec),
-                                    _ => Err(serde::de::Error::unknown_field(value, &["vec"])),
-                                }
-                            }
-                        }
-
-                        deserializer.deserialize_identifier(FieldVisitor)
-                    }
-                }
-
-                struct TlsVecVisitor<T> {
-                    data: std::marker::PhantomData<T>,
-                }
-
-                impl<'de, T> serde::de::Visitor<'de> for TlsVecVisitor<T>
-                where
-                    T: Serialize
-                        + Deserialize
-                        + Size
-                        + $($bounds + )*
-                        serde::de::Deserialize<'de>,
-                {
-                    type Value = $name<T>;
-                    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
-                        formatter.write_fmt(format_args!("struct {}<T>", stringify!($name)))
-                    }
-                    fn visit_seq<V>(self, mut seq: V) -> Result<$name<T>, V::Error>
-                    where
-                        V: serde::de::SeqAccess<'de>,
-                    {
-                        let vec = seq
-                            .next_element()?
-                            .ok_or_else(|| serde::de::Error::invalid_length(0, &self))?;
-                        Ok($name::<T>::new(vec))
-                    }
-                    fn visit_map<V>(self, mut map: V) -> Result<$name<T>, V::Error>
-                    where
-                        V: serde::de::MapAccess<'de>,
-                    {
-                        let mut vec = None;
-                        while let Some(key) = map.next_key()? {
-                            match key {
-                                Field::Vec => {
-                                    if vec.is_some() {
-                                        return Err(serde::de::Error::duplicate_field("vec"));
-                                    }
-                                    vec = Some(map.next_value()?);
-                                }
-                            }
-                        }
-                        let vec = vec.ok_or_else(|| serde::de::Error::missing_field("vec"))?;
-                        Ok($name::<T>::new(vec))
-                    }
-                }
-                deserializer.deserialize_struct(
-                    stringify!($name),
-                    &["vec"],
-                    TlsVecVisitor {
-                        data: std::marker::PhantomData,
-                    },
-                )
+        #[cfg(feature = "async")]
+        impl AsyncDeserialize for $owned {
+            async fn tls_deserialize_async<R: AsyncReader>(bytes: &mut R) -> Result<Self, Error> {
+                deserialize_bytes_async::<VarInt, R>(bytes)
+                    .await
+                    .and_then($decode)
             }
         }
     };
 }
 
-macro_rules! impl_tls_vec {
-    ($name:ident, $len_len:literal) => {
-        #[derive(Eq, Clone, Debug)]
-        pub struct $name {
-            vec: Vec<u8>,
-        }
+impl_owned_bytes!(Vec<u8>, [u8], Ok::<_, Error>);
+impl_owned_bytes!(String, str, string_from_utf8);
 
-        impl $name {
-            impl_vec_members!(u8, 1);
-        }
+impl<'a> DeserializeBytes<'a> for &'a [u8] {
+    fn tls_deserialize_bytes(bytes: &'a [u8]) -> Result<(Self, &'a [u8]), Error> {
+        let (content, remainder, _) = split_content::<VarInt>(bytes)?;
+        Ok((content, remainder))
+    }
+}
 
-        impl std::hash::Hash for $name {
-            #[inline]
-            fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-                self.vec.hash(state)
-            }
-        }
+impl<'a> DeserializeBytes<'a> for &'a str {
+    fn tls_deserialize_bytes(bytes: &'a [u8]) -> Result<(Self, &'a [u8]), Error> {
+        let (content, remainder, _) = split_content::<VarInt>(bytes)?;
+        Ok((str_from_utf8(content)?, remainder))
+    }
+}
 
-        impl std::ops::Index<usize> for $name {
-            type Output = u8;
+/// Declare the aliases of the vectors with the length prefix `$size`.
+///
+/// The slices are tuple structs instead of aliases, such that they can be
+/// constructed and matched like tuple structs, e.g. `TlsSliceU16(&values)`.
+/// They convert from and into [`TlsSlice`] and [`TlsByteSlice`].
+macro_rules! impl_tls_vec_aliases {
+    ($size:ty, $vec:ident, $secret_vec:ident, $byte_vec:ident, $slice:ident, $byte_slice:ident) => {
+        #[doc = concat!("A [`TlsVec`] with a `", stringify!($size), "` length prefix.")]
+        pub type $vec<T> = TlsVec<T, $size>;
 
-            #[inline]
-            fn index(&self, i: usize) -> &u8 {
-                self.vec.index(i)
-            }
-        }
+        #[doc = concat!("A [`SecretTlsVec`] with a `", stringify!($size), "` length prefix.")]
+        pub type $secret_vec<T> = SecretTlsVec<T, $size>;
 
-        impl std::cmp::PartialEq for $name {
-            fn eq(&self, other: &Self) -> bool {
-                self.vec.eq(&other.vec)
-            }
-        }
+        #[doc = concat!("A [`TlsByteVec`] with a `", stringify!($size), "` length prefix.")]
+        pub type $byte_vec = TlsByteVec<$size>;
 
-        impl std::ops::IndexMut<usize> for $name {
-            #[inline]
-            fn index_mut(&mut self, i: usize) -> &mut Self::Output {
-                self.vec.index_mut(i)
-            }
-        }
+        #[doc = concat!("A borrowed slice that is serialized like a [`TlsSlice`] with a `", stringify!($size), "` length prefix.")]
+        pub struct $slice<'a, T>(pub &'a [T]);
 
-        impl std::borrow::Borrow<[u8]> for $name {
+        impl<'a, T> $slice<'a, T> {
+            /// Get the underlying slice.
             #[inline]
-            fn borrow(&self) -> &[u8] {
-                &self.vec
+            pub fn as_slice(&self) -> &'a [T] {
+                self.0
             }
         }
 
-        impl std::iter::FromIterator<u8> for $name {
+        impl<'a, T> From<&'a [T]> for $slice<'a, T> {
             #[inline]
-            fn from_iter<I>(iter: I) -> Self
-            where
-                I: IntoIterator<Item = u8>,
-            {
-                let vec = Vec::<u8>::from_iter(iter);
-                Self { vec }
+            fn from(slice: &'a [T]) -> Self {
+                Self(slice)
             }
         }
 
-        impl From<Vec<u8>> for $name {
+        impl<'a, T> From<TlsSlice<'a, T, $size>> for $slice<'a, T> {
             #[inline]
-            fn from(v: Vec<u8>) -> Self {
-                Self::new(v)
+            fn from(slice: TlsSlice<'a, T, $size>) -> Self {
+                Self(slice.0)
             }
         }
 
-        impl From<&[u8]> for $name {
+        impl<'a, T> From<$slice<'a, T>> for TlsSlice<'a, T, $size> {
             #[inline]
-            fn from(v: &[u8]) -> Self {
-                Self::from_slice(v)
+            fn from(slice: $slice<'a, T>) -> Self {
+                Self::new(slice.0)
             }
         }
 
-        impl From<$name> for Vec<u8> {
+        impl<'a, T: Size> Size for $slice<'a, T> {
             #[inline]
-            fn from(mut v: $name) -> Self {
-                std::mem::take(&mut v.vec)
+            fn tls_serialized_len(&self) -> usize {
+                vector_len::<$size>(content_len(self.0))
             }
-        }
 
-        impl Default for $name {
             #[inline]
-            fn default() -> Self {
-                Self { vec: Vec::new() }
+            fn tls_serialized_len_checked(&self) -> Result<usize, Error> {
+                vector_len_checked::<$size>(content_len_checked(self.0)?)
             }
         }
 
-        #[cfg(feature = "serde_serialize")]
-        impl serde::Serialize for $name {
-            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-            where
-                S: serde::Serializer,
-            {
-                let mut state = serializer.serialize_struct(stringify!($name), 1)?;
-                state.serialize_field("vec", &self.vec)?;
-                state.end()
+        impl<'a, T: Serialize> Serialize for $slice<'a, T> {
+            fn tls_serialize<W: Writer>(&self, writer: &mut W) -> Result<usize, Error> {
+                serialize_elements::<T, $size, W, _>(self.0, writer, Plain)
             }
-        }
-
-        #[cfg(feature = "serde_serialize")]
-        impl<'de> serde::de::Deserialize<'de> for $name {
-            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-            where
-                D: serde::de::Deserializer<'de>,
-            {
-                enum Field {
-                    Vec,
-                }
-
-                impl<'de> serde::de::Deserialize<'de> for Field {
-                    fn deserialize<D>(deserializer: D) -> Result<Field, D::Error>
-                    where
-                        D: serde::de::Deserializer<'de>,
-                    {
-                        struct FieldVisitor;
-
-                        impl<'de> serde::de::Visitor<'de> for FieldVisitor {
-                            type Value = Field;
-
-                            fn expecting(
-                                &self,
-                                formatter: &mut std::fmt::Formatter,
-                            ) -> std::fmt::Result {
-                                formatter.write_str("`vec`")
-                            }
-
-                            fn visit_str<E>(self, value: &str) -> Result<Field, E>
-                            where
-                                E: serde::de::Error,
-                            {
-                                match value {
-                                    "vec" => Ok(Field::Vec),
-                                    _ => Err(serde::de::Error::unknown_field(value, &["vec"])),
-                                }
-                            }
-                        }
 
-                        deserializer.deserialize_identifier(FieldVisitor)
-                    }
-                }
-
-                struct TlsVecVisitor {
-                    data: std::marker::PhantomData<u8>,
-                }
-
-                impl<'de> serde::de::Visitor<'de> for TlsVecVisitor {
-                    type Value = $name;
-                    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
-                        formatter.write_fmt(format_args!("struct {}", stringify!($name)))
-                    }
-                    fn visit_seq<V>(self, mut seq: V) -> Result<$name, V::Error>
-                    where
-                        V: serde::de::SeqAccess<'de>,
-                    {
-                        let vec = seq
-                            .next_element()?
-                            .ok_or_else(|| serde::de::Error::invalid_length(0, &self))?;
-                        Ok($name::new(vec))
-                    }
-                    fn visit_map<V>(self, mut map: V) -> Result<$name, V::Error>
-                    where
-                        V: serde::de::MapAccess<'de>,
-                    {
-                        let mut vec = None;
-                        while let Some(key) = map.next_key()? {
-                            match key {
-                                Field::Vec => {
-                                    if vec.is_some() {
-                                        return Err(serde::de::Error::duplicate_field("vec"));
-                                    }
-                                    vec = Some(map.next_value()?);
-                                }
-                            }
-                        }
-                        let vec = vec.ok_or_else(|| serde::de::Error::missing_field("vec"))?;
-                        Ok($name::new(vec))
-                    }
-                }
-                deserializer.deserialize_struct(
-                    stringify!($name),
-                    &["vec"],
-                    TlsVecVisitor {
-                        data: std::marker::PhantomData,
-                    },
-                )
+            fn tls_serialize_vectored<'v>(
+                &'v self,
+                writer: &mut VectoredWriter<'v>,
+            ) -> Result<usize, Error> {
+                serialize_elements_vectored::<T, $size>(self.0, writer)
             }
         }
-    };
-}
-
-macro_rules! impl_secret_tls_vec {
-    ($size:ty, $name:ident, $len_len: literal) => {
-        impl_tls_vec_generic!(
-            $size,
-            $name,
-            $len_len,
-            Serialize,
-            Deserialize,
-            Size,
-            Zeroize
-        );
-        impl_tls_vec_codec_generic!(
-            $size,
-            $name,
-            $len_len,
-            Serialize,
-            Deserialize,
-            Size,
-            Zeroize
-        );
-
-        impl<T: Serialize + Deserialize + Size + Zeroize> $name<T> {
-            // This implements serialize and size for all versions
-            impl_serialize!(self, $size, $name, $len_len);
-            impl_size!(self, $size, $name, $len_len);
-            impl_deserialize!(self, $size, $name, $len_len);
-        }
 
-        impl<T: Serialize + Deserialize + Size + Zeroize> Zeroize for $name<T> {
-            fn zeroize(&mut self) {
-                self.vec.zeroize()
+        impl<'a, C: ?Sized, T: SerializeWithContext<C>> SerializeWithContext<C> for $slice<'a, T> {
+            fn tls_serialize_with_context<W: Writer>(
+                &self,
+                writer: &mut W,
+                context: &C,
+            ) -> Result<usize, Error> {
+                serialize_elements::<T, $size, W, _>(self.0, writer, WithContext(context))
             }
         }
 
-        impl<T: Serialize + Deserialize + Size + Zeroize> Drop for $name<T> {
-            fn drop(&mut self) {
-                self.zeroize()
+        #[cfg(feature = "async")]
+        impl<'a, T: AsyncSerialize> AsyncSerialize for $slice<'a, T> {
+            async fn tls_serialize_async<W: AsyncWriter>(
+                &self,
+                writer: &mut W,
+            ) -> Result<usize, Error> {
+                serialize_elements_async::<T, $size, W>(self.0, writer).await
             }
         }
-    };
-}
-
-macro_rules! impl_public_tls_vec {
-    ($size:ty, $name:ident, $len_len: literal) => {
-        impl_tls_vec_generic!($size, $name, $len_len, Serialize, Deserialize, Size);
-
-        impl_tls_vec_codec_generic!($size, $name, $len_len, Serialize, Deserialize, Size);
-
-        impl<T: Serialize + Deserialize + Size> $name<T> {
-            // This implements serialize and size for all versions
-            impl_serialize!(self, $size, $name, $len_len);
-            impl_size!(self, $size, $name, $len_len);
-            impl_deserialize!(self, $size, $name, $len_len);
-        }
-    };
-}
-
-macro_rules! impl_tls_byte_vec {
-    ($size:ty, $name:ident, $len_len: literal) => {
-        impl_tls_vec!($name, $len_len);
-
-        impl $name {
-            // This implements serialize and size for all versions
-            impl_byte_serialize!(self, $size, $name, $len_len);
-            impl_byte_size!(self, $size, $name, $len_len);
-            impl_byte_deserialize!(self, $size, $name, $len_len);
-        }
-
-        impl_tls_vec_codec_bytes!($size, $name, $len_len);
-    };
-}
-
-// TODO: #1 provide specialized TlsByteVec* versions holding u8 that are more efficient.
-
-impl_public_tls_vec!(u8, TlsVecU8, 1);
-impl_public_tls_vec!(u16, TlsVecU16, 2);
-impl_public_tls_vec!(u32, TlsVecU32, 4);
-
-impl_tls_byte_vec!(u8, TlsByteVecU8, 1);
-impl_tls_byte_vec!(u16, TlsByteVecU16, 2);
-impl_tls_byte_vec!(u32, TlsByteVecU32, 4);
 
-// Secrets should be put into these Secret tls vectors as they implement zeroize.
-impl_secret_tls_vec!(u8, SecretTlsVecU8, 1);
-impl_secret_tls_vec!(u16, SecretTlsVecU16, 2);
-impl_secret_tls_vec!(u32, SecretTlsVecU32, 4);
+        #[doc = concat!("A borrowed byte slice that is serialized like a [`TlsByteSlice`] with a `", stringify!($size), "` length prefix.")]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub struct $byte_slice<'a>(pub &'a [u8]);
 
-// We also implement shallow serialization for slices
-
-macro_rules! impl_tls_byte_slice {
-    ($size:ty, $name:ident, $len_len:literal) => {
-        pub struct $name<'a>(pub &'a [u8]);
-
-        impl<'a> $name<'a> {
-            #[inline(always)]
-            fn as_slice(&self) -> &[u8] {
-                &self.0
+        impl<'a> $byte_slice<'a> {
+            /// Get the underlying slice.
+            #[inline]
+            pub fn as_slice(&self) -> &'a [u8] {
+                self.0
             }
         }
 
-        impl<'a> $name<'a> {
-            impl_byte_serialize!(self, $size, $name, $len_len);
-            impl_byte_size!(self, $size, $name, $len_len);
+        impl<'a> From<&'a [u8]> for $byte_slice<'a> {
+            #[inline]
+            fn from(slice: &'a [u8]) -> Self {
+                Self(slice)
+            }
         }
 
-        impl<'a> Serialize for &$name<'a> {
-            fn tls_serialize<W: Write>(&self, writer: &mut W) -> Result<usize, Error> {
-                self.serialize_bytes(writer)
+        impl<'a> From<TlsByteSlice<'a, $size>> for $byte_slice<'a> {
+            #[inline]
+            fn from(slice: TlsByteSlice<'a, $size>) -> Self {
+                Self(slice.0)
             }
         }
 
-        impl<'a> Serialize for $name<'a> {
-            fn tls_serialize<W: Write>(&self, writer: &mut W) -> Result<usize, Error> {
-                self.serialize_bytes(writer)
+        impl<'a> From<$byte_slice<'a>> for TlsByteSlice<'a, $size> {
+            #[inline]
+            fn from(slice: $byte_slice<'a>) -> Self {
+                Self::new(slice.0)
             }
         }
 
-        impl<'a> Size for &$name<'a> {
+        impl<'a> Size for $byte_slice<'a> {
             #[inline]
             fn tls_serialized_len(&self) -> usize {
-                self.tls_serialized_byte_length()
+                vector_len::<$size>(self.0.len())
             }
-        }
 
-        impl<'a> Size for $name<'a> {
             #[inline]
-            fn tls_serialized_len(&self) -> usize {
-                self.tls_serialized_byte_length()
+            fn tls_serialized_len_checked(&self) -> Result<usize, Error> {
+                vector_len_checked::<$size>(self.0.len())
             }
         }
-    };
-}
-
-impl_tls_byte_slice!(u8, TlsByteSliceU8, 1);
-impl_tls_byte_slice!(u16, TlsByteSliceU16, 2);
-impl_tls_byte_slice!(u32, TlsByteSliceU32, 4);
 
-macro_rules! impl_tls_slice {
-    ($size:ty, $name:ident, $len_len: literal) => {
-        pub struct $name<'a, T: Size + Serialize>(pub &'a [T]);
-
-        impl<'a, T: Size + Serialize> $name<'a, T> {
-            #[inline(always)]
-            fn as_slice(&self) -> &[T] {
-                &self.0
+        impl<'a> Serialize for $byte_slice<'a> {
+            fn tls_serialize<W: Writer>(&self, writer: &mut W) -> Result<usize, Error> {
+                serialize_bytes::<$size, W>(self.0, writer)
             }
-        }
 
-        impl<'a, T: Size + Serialize> $name<'a, T> {
-            impl_serialize!(self, $size, $name, $len_len);
-            impl_size!(self, $size, $name, $len_len);
-        }
+            #[inline]
+            fn tls_serialize_to_checked_slice(&self, out: &mut [u8]) -> Result<usize, Error> {
+                serialize_bytes_to_slice::<$size>(self.0, out)
+            }
 
-        impl<'a, T: Size + Serialize> Serialize for &$name<'a, T> {
-            fn tls_serialize<W: Write>(&self, writer: &mut W) -> Result<usize, Error> {
-                self.serialize(writer)
+            #[inline]
+            fn tls_serialize_vectored<'v>(
+                &'v self,
+                writer: &mut VectoredWriter<'v>,
+            ) -> Result<usize, Error> {
+                serialize_bytes_vectored::<$size>(self.0, writer)
             }
         }
 
-        impl<'a, T: Size + Serialize> Serialize for $name<'a, T> {
-            fn tls_serialize<W: Write>(&self, writer: &mut W) -> Result<usize, Error> {
-                self.serialize(writer)
+        impl<'a, C: ?Sized> SerializeWithContext<C> for $byte_slice<'a> {
+            #[inline]
+            fn tls_serialize_with_context<W: Writer>(
+                &self,
+                writer: &mut W,
+                _context: &C,
+            ) -> Result<usize, Error> {
+                self.tls_serialize(writer)
             }
         }
 
-        impl<'a, T: Size + Serialize> Size for &$name<'a, T> {
-            #[inline]
-            fn tls_serialized_len(&self) -> usize {
-                self.tls_serialized_length()
+        impl<'a> DeserializeBytes<'a> for $byte_slice<'a> {
+            fn tls_deserialize_bytes(bytes: &'a [u8]) -> Result<(Self, &'a [u8]), Error> {
+                let (content, remainder, _) = split_content::<$size>(bytes)?;
+                Ok((Self(content), remainder))
             }
         }
 
-        impl<'a, T: Size + Serialize> Size for $name<'a, T> {
-            #[inline]
-            fn tls_serialized_len(&self) -> usize {
-                self.tls_serialized_length()
+        #[cfg(feature = "async")]
+        impl<'a> AsyncSerialize for $byte_slice<'a> {
+            async fn tls_serialize_async<W: AsyncWriter>(
+                &self,
+                writer: &mut W,
+            ) -> Result<usize, Error> {
+                serialize_bytes_async::<$size, W>(self.0, writer).await
             }
         }
     };
 }
 
-impl_tls_slice!(u8, TlsSliceU8, 1);
-impl_tls_slice!(u16, TlsSliceU16, 2);
-impl_tls_slice!(u32, TlsSliceU32, 4);
-
-impl From<std::num::TryFromIntError> for Error {
-    fn from(_e: std::num::TryFromIntError) -> Self {
-        Self::InvalidVectorLength
-    }
-}
-
-impl From<std::convert::Infallible> for Error {
-    fn from(_e: std::convert::Infallible) -> Self {
-        Self::InvalidVectorLength
-    }
-}
+impl_tls_vec_aliases!(
+    u8,
+    TlsVecU8,
+    SecretTlsVecU8,
+    TlsByteVecU8,
+    TlsSliceU8,
+    TlsByteSliceU8
+);
+impl_tls_vec_aliases!(
+    u16,
+    TlsVecU16,
+    SecretTlsVecU16,
+    TlsByteVecU16,
+    TlsSliceU16,
+    TlsByteSliceU16
+);
+impl_tls_vec_aliases!(
+    U24,
+    TlsVecU24,
+    SecretTlsVecU24,
+    TlsByteVecU24,
+    TlsSliceU24,
+    TlsByteSliceU24
+);
+impl_tls_vec_aliases!(
+    u32,
+    TlsVecU32,
+    SecretTlsVecU32,
+    TlsByteVecU32,
+    TlsSliceU32,
+    TlsByteSliceU32
+);
+
+/// A [`TlsBytes`] with a `u8` length prefix.
+#[cfg(feature = "bytes")]
+pub type TlsBytesU8 = TlsBytes<u8>;
+/// A [`TlsBytes`] with a `u16` length prefix.
+#[cfg(feature = "bytes")]
+pub type TlsBytesU16 = TlsBytes<u16>;
+/// A [`TlsBytes`] with a `U24` length prefix.
+#[cfg(feature = "bytes")]
+pub type TlsBytesU24 = TlsBytes<U24>;
+/// A [`TlsBytes`] with a `u32` length prefix.
+#[cfg(feature = "bytes")]
+pub type TlsBytesU32 = TlsBytes<u32>;
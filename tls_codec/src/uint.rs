@@ -0,0 +1,212 @@
+//! Big-endian unsigned integers with a width that isn't a power of two, like
+//! the `uint24` lengths of TLS handshake messages.
+
+#[cfg(feature = "async")]
+use super::{AsyncDeserialize, AsyncReader, AsyncSerialize, AsyncWriter};
+use super::{
+    Deserialize, DeserializeBytes, DeserializeWithContext, Error, FixedSize, Reader, Serialize,
+    SerializeWithContext, Size, Writer,
+};
+use crate::io::slice_prefix;
+use core::convert::TryFrom;
+
+/// An unsigned integer that is encoded in `BYTES` bytes in big-endian order.
+///
+/// `BYTES` must be between 1 and 8.
+/// Conversions from and to the Rust integer types check that the value fits.
+///
+/// ```
+/// use core::convert::TryFrom;
+/// use tls_codec::{Serialize, U24};
+///
+/// let len = U24::try_from(0x01_02_03u32).unwrap();
+/// assert_eq!(vec![1u8, 2, 3], len.tls_serialize_detached().unwrap());
+/// assert_eq!(0x01_02_03, u64::from(len));
+/// assert_eq!(0x01_02_03, u32::try_from(len).unwrap());
+/// assert!(U24::try_from(1u32 << 24).is_err());
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct UintN<const BYTES: usize>(u64);
+
+/// A `uint24`, e.g. the length of TLS handshake messages.
+pub type U24 = UintN<3>;
+
+/// A `uint48`, e.g. the sequence number of DTLS records.
+pub type U48 = UintN<6>;
+
+impl<const BYTES: usize> UintN<BYTES> {
+    // Referencing this in the constructors fails to compile for an invalid
+    // `BYTES`.
+    const VALID: () = assert!(BYTES >= 1 && BYTES <= 8, "UintN must have 1 to 8 bytes");
+
+    /// The largest value.
+    pub const MAX: u64 = u64::MAX >> (64 - 8 * BYTES);
+
+    /// Create a new integer from `value`.
+    ///
+    /// Returns [`Error::InvalidInput`] if `value` is larger than
+    /// [`UintN::MAX`].
+    pub fn new(value: u64) -> Result<Self, Error> {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::VALID;
+        if value > Self::MAX {
+            return Err(Error::InvalidInput);
+        }
+        Ok(Self(value))
+    }
+
+    /// Get the value.
+    #[inline]
+    pub fn value(&self) -> u64 {
+        self.0
+    }
+
+    /// Get the big-endian encoding of the value.
+    #[inline]
+    pub fn to_be_bytes(&self) -> [u8; BYTES] {
+        let mut out = [0u8; BYTES];
+        out.copy_from_slice(&self.0.to_be_bytes()[8 - BYTES..]);
+        out
+    }
+
+    /// Create an integer from its big-endian encoding.
+    #[inline]
+    pub fn from_be_bytes(bytes: [u8; BYTES]) -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::VALID;
+        let mut value = [0u8; 8];
+        value[8 - BYTES..].copy_from_slice(&bytes);
+        Self(u64::from_be_bytes(value))
+    }
+}
+
+impl<const BYTES: usize> TryFrom<u64> for UintN<BYTES> {
+    type Error = Error;
+
+    fn try_from(value: u64) -> Result<Self, Error> {
+        Self::new(value)
+    }
+}
+
+impl<const BYTES: usize> TryFrom<u32> for UintN<BYTES> {
+    type Error = Error;
+
+    fn try_from(value: u32) -> Result<Self, Error> {
+        Self::new(value.into())
+    }
+}
+
+impl<const BYTES: usize> TryFrom<usize> for UintN<BYTES> {
+    type Error = Error;
+
+    fn try_from(value: usize) -> Result<Self, Error> {
+        Self::new(u64::try_from(value)?)
+    }
+}
+
+impl<const BYTES: usize> From<UintN<BYTES>> for u64 {
+    #[inline]
+    fn from(value: UintN<BYTES>) -> Self {
+        value.0
+    }
+}
+
+impl<const BYTES: usize> TryFrom<UintN<BYTES>> for u32 {
+    type Error = Error;
+
+    fn try_from(value: UintN<BYTES>) -> Result<Self, Error> {
+        Ok(u32::try_from(value.0)?)
+    }
+}
+
+impl<const BYTES: usize> TryFrom<UintN<BYTES>> for usize {
+    type Error = Error;
+
+    fn try_from(value: UintN<BYTES>) -> Result<Self, Error> {
+        Ok(usize::try_from(value.0)?)
+    }
+}
+
+impl<const BYTES: usize> Size for UintN<BYTES> {
+    #[inline]
+    fn tls_serialized_len(&self) -> usize {
+        BYTES
+    }
+
+    #[inline]
+    fn tls_fixed_len() -> Option<usize> {
+        Some(BYTES)
+    }
+}
+
+impl<const BYTES: usize> FixedSize for UintN<BYTES> {
+    const TLS_LEN: usize = BYTES;
+}
+
+impl<const BYTES: usize> Serialize for UintN<BYTES> {
+    fn tls_serialize<W: Writer>(&self, writer: &mut W) -> Result<usize, Error> {
+        writer.write_bytes(&self.to_be_bytes())?;
+        Ok(BYTES)
+    }
+
+    #[inline]
+    fn tls_serialize_to_checked_slice(&self, out: &mut [u8]) -> Result<usize, Error> {
+        slice_prefix(out, BYTES)?.copy_from_slice(&self.to_be_bytes());
+        Ok(BYTES)
+    }
+}
+
+impl<const BYTES: usize> Deserialize for UintN<BYTES> {
+    fn tls_deserialize<R: Reader>(bytes: &mut R) -> Result<Self, Error> {
+        let mut x = [0u8; BYTES];
+        bytes.read_bytes(&mut x)?;
+        Ok(Self::from_be_bytes(x))
+    }
+}
+
+impl<'a, const BYTES: usize> DeserializeBytes<'a> for UintN<BYTES> {
+    fn tls_deserialize_bytes(bytes: &'a [u8]) -> Result<(Self, &'a [u8]), Error> {
+        if bytes.len() < BYTES {
+            return Err(Error::EndOfStream);
+        }
+        let (x, remainder) = bytes.split_at(BYTES);
+        let mut out = [0u8; BYTES];
+        out.copy_from_slice(x);
+        Ok((Self::from_be_bytes(out), remainder))
+    }
+}
+
+impl<C: ?Sized, const BYTES: usize> SerializeWithContext<C> for UintN<BYTES> {
+    #[inline]
+    fn tls_serialize_with_context<W: Writer>(
+        &self,
+        writer: &mut W,
+        _context: &C,
+    ) -> Result<usize, Error> {
+        self.tls_serialize(writer)
+    }
+}
+
+impl<C: ?Sized, const BYTES: usize> DeserializeWithContext<C> for UintN<BYTES> {
+    #[inline]
+    fn tls_deserialize_with_context<R: Reader>(bytes: &mut R, _context: &C) -> Result<Self, Error> {
+        Self::tls_deserialize(bytes)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<const BYTES: usize> AsyncSerialize for UintN<BYTES> {
+    async fn tls_serialize_async<W: AsyncWriter>(&self, writer: &mut W) -> Result<usize, Error> {
+        writer.write_bytes(&self.to_be_bytes()).await?;
+        Ok(BYTES)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<const BYTES: usize> AsyncDeserialize for UintN<BYTES> {
+    async fn tls_deserialize_async<R: AsyncReader>(bytes: &mut R) -> Result<Self, Error> {
+        let mut x = [0u8; BYTES];
+        bytes.read_bytes(&mut x).await?;
+        Ok(Self::from_be_bytes(x))
+    }
+}
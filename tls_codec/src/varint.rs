@@ -0,0 +1,297 @@
+//! Variable-length integers as defined in
+//! [RFC 9000](https://www.rfc-editor.org/rfc/rfc9000#section-16) and vectors
+//! that use them as length prefix as defined in
+//! [RFC 9420](https://www.rfc-editor.org/rfc/rfc9420#section-2.1.2).
+//!
+//! The two most significant bits of the first byte encode the length of the
+//! integer, i.e. 1, 2, 4, or 8 bytes, and the remaining bits the value in
+//! big-endian order.
+//! Only the shortest encoding of a value is valid.
+
+use core::convert::TryFrom;
+
+use crate::{
+    io::slice_prefix,
+    tls_vec::{check_len, LengthPrefix, TlsByteVec, TlsVec, VectorNames},
+    Deserialize, DeserializeBytes, DeserializeWithContext, Error, Reader, Serialize,
+    SerializeWithContext, Size, Writer,
+};
+#[cfg(feature = "async")]
+use crate::{AsyncDeserialize, AsyncReader, AsyncSerialize, AsyncWriter};
+
+/// A variable-length integer of up to 62 bits.
+///
+/// ```
+/// use tls_codec::{Deserialize, Error, Serialize, VarInt};
+///
+/// let value = VarInt::new(494_878_333).unwrap();
+/// assert_eq!(vec![0x9d, 0x7f, 0x3e, 0x7d], value.tls_serialize_detached().unwrap());
+/// assert_eq!(value, VarInt::tls_deserialize(&mut &[0x9du8, 0x7f, 0x3e, 0x7d][..]).unwrap());
+///
+/// // The value 37 fits into a single byte.
+/// let error = VarInt::tls_deserialize(&mut &[0x40u8, 0x25][..]).unwrap_err();
+/// assert_eq!(Error::NonMinimalVarInt { value: 37 }, error);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct VarInt(u64);
+
+impl VarInt {
+    /// The largest value, `2^62 - 1`.
+    pub const MAX: u64 = (1 << 62) - 1;
+
+    /// Create a new integer from `value`.
+    ///
+    /// Returns [`Error::InvalidInput`] if `value` is larger than
+    /// [`VarInt::MAX`].
+    pub fn new(value: u64) -> Result<Self, Error> {
+        if value > Self::MAX {
+            return Err(Error::InvalidInput);
+        }
+        Ok(Self(value))
+    }
+
+    /// Get the value.
+    #[inline]
+    pub fn value(&self) -> u64 {
+        self.0
+    }
+
+    /// Get the length of the encoding of `value`.
+    #[inline]
+    fn encoded_len(value: u64) -> usize {
+        match value {
+            0..=0x3f => 1,
+            0x40..=0x3fff => 2,
+            0x4000..=0x3fff_ffff => 4,
+            _ => 8,
+        }
+    }
+
+    /// Get the length of the encoding that starts with `first`.
+    #[inline]
+    fn decoded_len(first: u8) -> usize {
+        1 << (first >> 6)
+    }
+
+    /// Encode the value into the first bytes of the returned buffer and get
+    /// their number.
+    #[inline]
+    fn encode(&self) -> ([u8; 8], usize) {
+        let len = Self::encoded_len(self.0);
+        let mut out = [0u8; 8];
+        out[..len].copy_from_slice(&self.0.to_be_bytes()[8 - len..]);
+        out[0] |= (len.trailing_zeros() as u8) << 6;
+        (out, len)
+    }
+
+    /// Decode an integer from its complete encoding.
+    #[inline]
+    fn decode(encoding: &[u8]) -> Result<Self, Error> {
+        let value = encoding[1..]
+            .iter()
+            .fold(u64::from(encoding[0] & 0x3f), |value, &b| {
+                value << 8 | u64::from(b)
+            });
+        if Self::encoded_len(value) != encoding.len() {
+            return Err(Error::NonMinimalVarInt { value });
+        }
+        Ok(Self(value))
+    }
+}
+
+impl From<u8> for VarInt {
+    #[inline]
+    fn from(value: u8) -> Self {
+        Self(value.into())
+    }
+}
+
+impl From<u16> for VarInt {
+    #[inline]
+    fn from(value: u16) -> Self {
+        Self(value.into())
+    }
+}
+
+impl From<u32> for VarInt {
+    #[inline]
+    fn from(value: u32) -> Self {
+        Self(value.into())
+    }
+}
+
+impl TryFrom<u64> for VarInt {
+    type Error = Error;
+
+    fn try_from(value: u64) -> Result<Self, Error> {
+        Self::new(value)
+    }
+}
+
+impl TryFrom<usize> for VarInt {
+    type Error = Error;
+
+    fn try_from(value: usize) -> Result<Self, Error> {
+        Self::new(u64::try_from(value)?)
+    }
+}
+
+impl From<VarInt> for u64 {
+    #[inline]
+    fn from(value: VarInt) -> Self {
+        value.0
+    }
+}
+
+impl TryFrom<VarInt> for usize {
+    type Error = Error;
+
+    fn try_from(value: VarInt) -> Result<Self, Error> {
+        Ok(usize::try_from(value.0)?)
+    }
+}
+
+impl Size for VarInt {
+    #[inline]
+    fn tls_serialized_len(&self) -> usize {
+        Self::encoded_len(self.0)
+    }
+}
+
+impl Serialize for VarInt {
+    fn tls_serialize<W: Writer>(&self, writer: &mut W) -> Result<usize, Error> {
+        let (encoding, len) = self.encode();
+        writer.write_bytes(&encoding[..len])?;
+        Ok(len)
+    }
+
+    #[inline]
+    fn tls_serialize_to_checked_slice(&self, out: &mut [u8]) -> Result<usize, Error> {
+        let (encoding, len) = self.encode();
+        slice_prefix(out, len)?.copy_from_slice(&encoding[..len]);
+        Ok(len)
+    }
+}
+
+impl Deserialize for VarInt {
+    fn tls_deserialize<R: Reader>(bytes: &mut R) -> Result<Self, Error> {
+        let mut encoding = [0u8; 8];
+        bytes.read_bytes(&mut encoding[..1])?;
+        let len = Self::decoded_len(encoding[0]);
+        bytes
+            .read_bytes(&mut encoding[1..len])
+            .map_err(|e| e.with_offset(1))?;
+        Self::decode(&encoding[..len])
+    }
+}
+
+impl<'a> DeserializeBytes<'a> for VarInt {
+    fn tls_deserialize_bytes(bytes: &'a [u8]) -> Result<(Self, &'a [u8]), Error> {
+        let first = bytes.first().ok_or(Error::EndOfStream)?;
+        let len = Self::decoded_len(*first);
+        if bytes.len() < len {
+            return Err(Error::EndOfStream.with_offset(1));
+        }
+        let (encoding, remainder) = bytes.split_at(len);
+        Ok((Self::decode(encoding)?, remainder))
+    }
+}
+
+impl<C: ?Sized> SerializeWithContext<C> for VarInt {
+    #[inline]
+    fn tls_serialize_with_context<W: Writer>(
+        &self,
+        writer: &mut W,
+        _context: &C,
+    ) -> Result<usize, Error> {
+        self.tls_serialize(writer)
+    }
+}
+
+impl<C: ?Sized> DeserializeWithContext<C> for VarInt {
+    #[inline]
+    fn tls_deserialize_with_context<R: Reader>(bytes: &mut R, _context: &C) -> Result<Self, Error> {
+        Self::tls_deserialize(bytes)
+    }
+}
+
+#[cfg(feature = "async")]
+impl AsyncSerialize for VarInt {
+    async fn tls_serialize_async<W: AsyncWriter>(&self, writer: &mut W) -> Result<usize, Error> {
+        let (encoding, len) = self.encode();
+        writer.write_bytes(&encoding[..len]).await?;
+        Ok(len)
+    }
+}
+
+#[cfg(feature = "async")]
+impl AsyncDeserialize for VarInt {
+    async fn tls_deserialize_async<R: AsyncReader>(bytes: &mut R) -> Result<Self, Error> {
+        let mut encoding = [0u8; 8];
+        bytes.read_bytes(&mut encoding[..1]).await?;
+        let len = VarInt::decoded_len(encoding[0]);
+        bytes
+            .read_bytes(&mut encoding[1..len])
+            .await
+            .map_err(|e| e.with_offset(1))?;
+        VarInt::decode(&encoding[..len])
+    }
+}
+
+impl LengthPrefix for VarInt {
+    /// MLS doesn't allow the 8 byte encoding for vector lengths, such that the
+    /// content of a vector is at most `2^30 - 1` bytes long.
+    const MAX_LEN: usize = (1 << 30) - 1;
+    const NAMES: VectorNames = VectorNames {
+        vec: "TlsVarVec",
+        byte_vec: "VLBytes",
+        ..VectorNames::GENERIC
+    };
+
+    #[inline]
+    fn from_len(len: usize) -> Result<Self, Error> {
+        Ok(Self(check_len(len, Self::MAX_LEN)? as u64))
+    }
+
+    #[inline]
+    fn to_len(&self) -> Result<usize, Error> {
+        check_len(usize::try_from(self.0).unwrap_or(usize::MAX), Self::MAX_LEN)
+    }
+
+    #[inline]
+    fn prefix_len(len: usize) -> usize {
+        Self::encoded_len(len as u64)
+    }
+}
+
+/// A byte vector with a variable-length integer as length prefix, i.e.
+/// `opaque data<V>` in MLS.
+///
+/// `VLBytes` implements [`Zeroize`](zeroize::Zeroize) but doesn't zeroize
+/// itself on drop.
+/// Wrap it into [`zeroize::Zeroizing`] for secrets.
+///
+/// ```
+/// use tls_codec::{Serialize, VLBytes};
+///
+/// let bytes = VLBytes::from_slice(&[0xab; 64]);
+/// let serialized = bytes.tls_serialize_detached().unwrap();
+/// assert_eq!(&[0x40, 64, 0xab], &serialized[..3]);
+/// ```
+pub type VLBytes = TlsByteVec<VarInt>;
+
+/// A vector with a variable-length integer as length prefix, i.e.
+/// `T data<V>` in MLS.
+///
+/// `TlsVarVec` implements [`Zeroize`](zeroize::Zeroize) if the elements do
+/// but doesn't zeroize itself on drop.
+///
+/// ```
+/// use tls_codec::{Deserialize, Serialize, TlsVarVec};
+///
+/// let v = TlsVarVec::<u16>::from_slice(&[1, 2]);
+/// let serialized = v.tls_serialize_detached().unwrap();
+/// assert_eq!(vec![4u8, 0, 1, 0, 2], serialized);
+/// assert_eq!(v, TlsVarVec::tls_deserialize(&mut serialized.as_slice()).unwrap());
+/// ```
+pub type TlsVarVec<T> = TlsVec<T, VarInt>;
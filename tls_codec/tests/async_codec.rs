@@ -0,0 +1,256 @@
+#![cfg(all(feature = "futures-io", feature = "tokio"))]
+
+use futures::executor::block_on;
+use tls_codec::{
+    AsyncDeserialize, AsyncReader, AsyncSerialize, AsyncWriter, DecodeLimit, DecodeLimits, Error,
+    FuturesIo, Serialize, Size, TlsByteSliceU16, TlsByteVecU16, TlsByteVecU32, TlsSliceU8,
+    TlsVecU16, TlsVecU8, TokioIo,
+};
+
+#[test]
+fn async_roundtrip() {
+    block_on(async {
+        let value = (
+            TlsVecU16::<(u8, Option<u32>)>::from(vec![(1, Some(2)), (3, None)]),
+            TlsByteVecU16::from_slice(&[4, 5, 6]),
+            [7u8; 3],
+        );
+        let mut serialized = Vec::new();
+        let written = value.tls_serialize_async(&mut serialized).await.unwrap();
+        assert_eq!(serialized.len(), written);
+        assert_eq!(value.tls_serialize_detached().unwrap(), serialized);
+
+        let mut reader = serialized.as_slice();
+        let deserialized =
+            <(TlsVecU16<(u8, Option<u32>)>, TlsByteVecU16, [u8; 3])>::tls_deserialize_async(
+                &mut reader,
+            )
+            .await
+            .unwrap();
+        assert_eq!(value, deserialized);
+        assert!(reader.is_empty());
+
+        let mut serialized_slices = Vec::new();
+        TlsSliceU8(&[1u16, 2])
+            .tls_serialize_async(&mut serialized_slices)
+            .await
+            .unwrap();
+        TlsByteSliceU16(&[3, 4])
+            .tls_serialize_async(&mut serialized_slices)
+            .await
+            .unwrap();
+        assert_eq!(vec![4u8, 0, 1, 0, 2, 0, 2, 3, 4], serialized_slices);
+    });
+}
+
+#[test]
+fn async_errors() {
+    block_on(async {
+        let b = &[8u8, 2, 4, 0, 1, 0] as &[u8];
+        let error = TlsVecU8::<(u8, TlsVecU8<u16>)>::tls_deserialize_async(&mut &b[..])
+            .await
+            .unwrap_err();
+        assert_eq!(&Error::EndOfStream, error.kind());
+        assert_eq!(5, error.offset());
+        assert_eq!("[0].1[1]", error.path().unwrap().to_string());
+
+        let b = &[0xffu8, 0xff, 0xff, 0xff, 1, 2, 3] as &[u8];
+        let error = TlsByteVecU32::tls_deserialize_async(&mut &b[..])
+            .await
+            .unwrap_err();
+        assert_eq!(&Error::EndOfStream, error.kind());
+        let limits = DecodeLimits::default().with_max_vector_len(3);
+        let error = TlsByteVecU32::tls_deserialize_async_with_limits(&mut &b[..], limits)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            Error::LimitExceeded {
+                limit: DecodeLimit::VectorLength,
+                ..
+            }
+        ));
+    });
+}
+
+#[test]
+fn futures_io() {
+    block_on(async {
+        let mut writer = FuturesIo(futures::io::Cursor::new(Vec::new()));
+        TlsVecU8::<u16>::from_slice(&[1, 2])
+            .tls_serialize_async(&mut writer)
+            .await
+            .unwrap();
+        7u32.tls_serialize_async(&mut writer).await.unwrap();
+
+        let mut reader = FuturesIo(futures::io::Cursor::new(writer.0.into_inner()));
+        let v = TlsVecU8::<u16>::tls_deserialize_async(&mut reader)
+            .await
+            .unwrap();
+        assert_eq!(&[1, 2], v.as_slice());
+        assert_eq!(7, u32::tls_deserialize_async(&mut reader).await.unwrap());
+        let error = u8::tls_deserialize_async(&mut reader).await.unwrap_err();
+        assert_eq!(Error::EndOfStream, error);
+    });
+}
+
+#[tokio::test]
+async fn tokio_stream() {
+    use tokio::io::AsyncWriteExt;
+
+    // Decode messages from a stream that delivers them in small pieces.
+    let (client, server) = tokio::io::duplex(3);
+    let sender = tokio::spawn(async move {
+        let mut client = TokioIo(client);
+        for message in [&[1u8, 2, 3][..], &[], &[4; 10]] {
+            TlsByteVecU16::from_slice(message)
+                .tls_serialize_async(&mut client)
+                .await
+                .unwrap();
+        }
+        client.0.shutdown().await.unwrap();
+    });
+
+    let mut server = TokioIo(server);
+    let mut messages = Vec::new();
+    for _ in 0..3 {
+        let message = TlsByteVecU16::tls_deserialize_async(&mut server)
+            .await
+            .unwrap();
+        messages.push(message.into_vec());
+    }
+    assert_eq!(vec![vec![1u8, 2, 3], vec![], vec![4; 10]], messages);
+    let error = TlsByteVecU16::tls_deserialize_async(&mut server)
+        .await
+        .unwrap_err();
+    assert_eq!(&Error::EndOfStream, error.kind());
+    sender.await.unwrap();
+}
+
+#[tokio::test]
+async fn tokio_spawn_generic() {
+    // Generic code can spawn the futures because they are `Send`.
+    fn spawn_serialize<T: AsyncSerialize + Send + 'static>(
+        value: T,
+    ) -> tokio::task::JoinHandle<Result<Vec<u8>, Error>> {
+        tokio::spawn(async move {
+            let mut serialized = Vec::new();
+            value.tls_serialize_async(&mut serialized).await?;
+            Ok(serialized)
+        })
+    }
+
+    fn spawn_deserialize<T: AsyncDeserialize + 'static>(
+        bytes: Vec<u8>,
+    ) -> tokio::task::JoinHandle<Result<T, Error>> {
+        tokio::spawn(async move {
+            let (client, server) = tokio::io::duplex(2);
+            let mut client = TokioIo(client);
+            let mut server = TokioIo(server);
+            let (_, value) = tokio::join!(client.write_bytes(&bytes), async {
+                T::tls_deserialize_async(&mut server).await
+            });
+            value
+        })
+    }
+
+    let value = (
+        TlsVecU16::<(u8, Option<u32>)>::from(vec![(1, Some(2)), (3, None)]),
+        Box::new(TlsByteVecU16::from_slice(&[4, 5])),
+        String::from("tls"),
+    );
+    let serialized = spawn_serialize(value.clone()).await.unwrap().unwrap();
+    assert_eq!(value.tls_serialize_detached().unwrap(), serialized);
+    let deserialized =
+        spawn_deserialize::<(TlsVecU16<(u8, Option<u32>)>, Box<TlsByteVecU16>, String)>(serialized)
+            .await
+            .unwrap()
+            .unwrap();
+    assert_eq!(value, deserialized);
+}
+
+#[test]
+fn async_single_pass() {
+    // A byte that panics if its length is computed.
+    struct Unmeasured(u8);
+
+    impl Size for Unmeasured {
+        fn tls_serialized_len(&self) -> usize {
+            panic!("the length of an element was computed")
+        }
+    }
+
+    impl AsyncSerialize for Unmeasured {
+        async fn tls_serialize_async<W: AsyncWriter>(
+            &self,
+            writer: &mut W,
+        ) -> Result<usize, Error> {
+            self.0.tls_serialize_async(writer).await
+        }
+    }
+
+    block_on(async {
+        let v = TlsVecU16::from(vec![
+            TlsVecU8::from(vec![Unmeasured(1), Unmeasured(2)]),
+            TlsVecU8::from(vec![]),
+        ]);
+        let expected = vec![0u8, 4, 2, 1, 2, 0];
+
+        let mut serialized = Vec::new();
+        assert_eq!(6, v.tls_serialize_async(&mut serialized).await.unwrap());
+        assert_eq!(expected, serialized);
+
+        // Writers that can't patch compute the length and stream the elements.
+        let v = TlsVecU16::from(vec![TlsVecU8::from(vec![1u8, 2]), TlsVecU8::from(vec![])]);
+        let mut writer = FuturesIo(futures::io::Cursor::new(Vec::new()));
+        assert_eq!(6, v.tls_serialize_async(&mut writer).await.unwrap());
+        assert_eq!(expected, writer.0.into_inner());
+    });
+}
+
+#[test]
+fn async_recursive() {
+    // A recursive list, which needs the boxed futures of `Box`.
+    #[derive(Debug, PartialEq)]
+    struct List(u8, Option<Box<List>>);
+
+    impl Size for List {
+        fn tls_serialized_len(&self) -> usize {
+            (&self.0, &self.1).tls_serialized_len()
+        }
+    }
+
+    impl AsyncSerialize for List {
+        async fn tls_serialize_async<W: AsyncWriter>(
+            &self,
+            writer: &mut W,
+        ) -> Result<usize, Error> {
+            (&self.0, &self.1).tls_serialize_async(writer).await
+        }
+    }
+
+    impl AsyncDeserialize for List {
+        async fn tls_deserialize_async<R: AsyncReader>(bytes: &mut R) -> Result<Self, Error> {
+            let (head, tail) = <(u8, Option<Box<List>>)>::tls_deserialize_async(bytes).await?;
+            Ok(Self(head, tail))
+        }
+    }
+
+    block_on(async {
+        let list = List(1, Some(Box::new(List(2, Some(Box::new(List(3, None)))))));
+        let mut serialized = Vec::new();
+        assert_eq!(6, list.tls_serialize_async(&mut serialized).await.unwrap());
+        assert_eq!(vec![1u8, 1, 2, 1, 3, 0], serialized);
+        assert_eq!(
+            list,
+            List::tls_deserialize_async(&mut serialized.as_slice())
+                .await
+                .unwrap()
+        );
+        let error = List::tls_deserialize_async(&mut &[1u8, 1, 2, 2][..])
+            .await
+            .unwrap_err();
+        assert_eq!(&Error::InvalidOptionTag { tag: 2 }, error.kind());
+        assert_eq!("1.1", error.path().unwrap().to_string());
+    });
+}
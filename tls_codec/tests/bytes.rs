@@ -0,0 +1,85 @@
+#![cfg(feature = "bytes")]
+
+use bytes::{Buf, Bytes, BytesMut};
+use tls_codec::{
+    BytesIo, DecodeLimits, Deserialize, DeserializeBytes, Error, Serialize, Size, TlsBytesU16,
+    TlsBytesU24, TlsBytesU32, TlsBytesU8, TlsVecU8,
+};
+
+#[test]
+fn buf_reader_writer() {
+    let value = (TlsVecU8::<u16>::from_slice(&[1, 2]), 3u32);
+    let mut writer = BytesIo(BytesMut::new());
+    let written = value.tls_serialize(&mut writer).unwrap();
+    assert_eq!(value.tls_serialized_len(), written);
+    assert_eq!(value.tls_serialize_detached().unwrap(), writer.0.as_ref());
+
+    // Any `Buf` can be read from, e.g. a chain of buffers.
+    let serialized = writer.0.freeze();
+    let mut reader = BytesIo(serialized.slice(..3).chain(serialized.slice(3..)));
+    let deserialized = <(TlsVecU8<u16>, u32)>::tls_deserialize(&mut reader).unwrap();
+    assert_eq!(value, deserialized);
+    assert!(!reader.0.has_remaining());
+    assert_eq!(
+        Error::EndOfStream,
+        u8::tls_deserialize(&mut reader).unwrap_err()
+    );
+
+    // Writing into a fixed size buffer fails if it's too small.
+    let mut buf = [0u8; 4];
+    let mut writer = BytesIo(&mut buf[..]);
+    assert!(matches!(
+        value.tls_serialize(&mut writer),
+        Err(Error::EncodingError(_))
+    ));
+}
+
+#[test]
+fn tls_bytes() {
+    let mut writer = BytesIo(BytesMut::new());
+    TlsBytesU8::from_slice(&[1, 2])
+        .tls_serialize(&mut writer)
+        .unwrap();
+    TlsBytesU16::from_slice(&[3; 300])
+        .tls_serialize(&mut writer)
+        .unwrap();
+    TlsBytesU32::from(vec![4, 5])
+        .tls_serialize(&mut writer)
+        .unwrap();
+    TlsBytesU24::from_slice(&[6])
+        .tls_serialize(&mut writer)
+        .unwrap();
+    let serialized = writer.0.freeze();
+
+    // Decoding from `Bytes` slices the buffer.
+    let mut reader = BytesIo(serialized.clone());
+    let u8_bytes = TlsBytesU8::tls_deserialize(&mut reader).unwrap();
+    let u16_bytes = TlsBytesU16::tls_deserialize(&mut reader).unwrap();
+    let u32_bytes = TlsBytesU32::tls_deserialize(&mut reader).unwrap();
+    let u24_bytes = TlsBytesU24::tls_deserialize(&mut reader).unwrap();
+    assert_eq!(&[1, 2], u8_bytes.as_slice());
+    assert_eq!(&[3; 300], u16_bytes.as_slice());
+    assert_eq!(&[4, 5], u32_bytes.as_slice());
+    assert_eq!(&[6], u24_bytes.as_slice());
+    assert_eq!(&[0, 0, 1, 6], &serialized[serialized.len() - 4..]);
+    assert_eq!(serialized[5..].as_ptr(), u16_bytes.as_slice().as_ptr());
+
+    // Decoding from other readers copies.
+    let mut reader = serialized.as_ref();
+    assert_eq!(u8_bytes, TlsBytesU8::tls_deserialize(&mut reader).unwrap());
+    let (deserialized, remainder) = TlsBytesU8::tls_deserialize_bytes(&serialized).unwrap();
+    assert_eq!(u8_bytes, deserialized);
+    assert_eq!(&serialized[3..], remainder);
+    assert_eq!(Bytes::from_static(&[1, 2]), Bytes::from(deserialized));
+
+    // A bogus length is reported without reading.
+    let mut reader = BytesIo(Bytes::from_static(&[0, 0, 0, 9, 1, 2]));
+    let error = TlsBytesU32::tls_deserialize(&mut reader).unwrap_err();
+    assert_eq!(&Error::EndOfStream, error.kind());
+    assert_eq!(4, error.offset());
+    assert_eq!(2, reader.0.len());
+
+    let mut reader = BytesIo(serialized);
+    let limits = DecodeLimits::default().with_max_vector_len(1);
+    assert!(TlsBytesU8::tls_deserialize_with_limits(&mut reader, limits).is_err());
+}
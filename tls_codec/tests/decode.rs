@@ -1,6 +1,10 @@
 use tls_codec::{
-    Deserialize, Serialize, Size, TlsByteSliceU16, TlsByteVecU16, TlsByteVecU8, TlsSliceU16,
-    TlsVecU16, TlsVecU8,
+    AlertDescription, Bounded, DecodeLimit, DecodeLimits, Decoded, Deserialize, DeserializeBytes,
+    DeserializeWithContext, Error, FixedSize, LengthPrefix, LimitedReader, Reader, SecretTlsVecU24,
+    Serialize, SerializeWithContext, Size, TlsByteSlice, TlsByteSliceU16, TlsByteSliceU24,
+    TlsByteVec, TlsByteVecU16, TlsByteVecU24, TlsByteVecU32, TlsByteVecU8, TlsSlice, TlsSliceU16,
+    TlsSliceU24, TlsSliceU8, TlsVarVec, TlsVec, TlsVecU16, TlsVecU24, TlsVecU32, TlsVecU8, UintN,
+    VLBytes, VarInt, Writer, U24, U48,
 };
 
 #[test]
@@ -76,3 +80,1148 @@ fn deserialize_tls_byte_vec() {
     assert_eq!(long_vector.len(), deserialized_long_vec.len());
     assert_eq!(long_vector.as_slice(), deserialized_long_vec.as_slice());
 }
+
+/// A reader that is not a `std::io::Read`.
+struct Source<'a>(&'a [u8]);
+
+impl Reader for Source<'_> {
+    fn read_bytes(&mut self, buf: &mut [u8]) -> Result<(), Error> {
+        if buf.len() > self.0.len() {
+            return Err(Error::EndOfStream);
+        }
+        let (head, tail) = self.0.split_at(buf.len());
+        buf.copy_from_slice(head);
+        self.0 = tail;
+        Ok(())
+    }
+}
+
+#[test]
+fn deserialize_custom_reader() {
+    let mut source = Source(&[77u8, 0, 4, 0, 1, 0, 2]);
+    let (a, v) =
+        <(u8, TlsVecU16<u16>)>::tls_deserialize(&mut source).expect("Unable to tls_deserialize");
+    assert_eq!(77, a);
+    assert_eq!(&[1, 2], v.as_slice());
+    assert_eq!(Err(Error::EndOfStream), u8::tls_deserialize(&mut source));
+}
+
+#[test]
+fn deserialize_bytes_primitives() {
+    let b = &[77u8, 88, 1, 99, 0, 0, 0, 1, 2] as &[u8];
+
+    let (a, b) = u8::tls_deserialize_bytes(b).expect("Unable to tls_deserialize");
+    assert_eq!(77, a);
+    let (a, b) = <(u8, u16)>::tls_deserialize_bytes(b).expect("Unable to tls_deserialize");
+    assert_eq!((88, 355), a);
+    let (a, b) = Option::<u32>::tls_deserialize_bytes(b).expect("Unable to tls_deserialize");
+    assert_eq!(None, a);
+    let (a, b) = <[u8; 2]>::tls_deserialize_bytes(b).expect("Unable to tls_deserialize");
+    assert_eq!([0, 0], a);
+    assert_eq!(&[1, 2], b);
+
+    assert_eq!(Err(Error::EndOfStream), u32::tls_deserialize_bytes(b));
+    assert_eq!(Err(Error::EndOfStream), <[u8; 3]>::tls_deserialize_bytes(b));
+}
+
+#[test]
+fn deserialize_bytes_tls_vec() {
+    let b = &[4u8, 0, 1, 0, 2, 0, 3, 1, 2, 3, 0xFF] as &[u8];
+
+    let (v, remainder) =
+        TlsVecU8::<u16>::tls_deserialize_bytes(b).expect("Unable to tls_deserialize");
+    assert_eq!(&[1, 2], v.as_slice());
+    let (v, remainder) =
+        TlsByteVecU16::tls_deserialize_bytes(remainder).expect("Unable to tls_deserialize");
+    assert_eq!(&[1, 2, 3], v.as_slice());
+    assert_eq!(&[0xFF], remainder);
+
+    let (v, remainder) =
+        TlsByteSliceU16::tls_deserialize_bytes(&b[5..]).expect("Unable to tls_deserialize");
+    assert_eq!(TlsByteSliceU16(&[1, 2, 3]), v);
+    assert!(core::ptr::eq(v.0.as_ptr(), &b[7]));
+    assert_eq!(&[0xFF], remainder);
+
+    // The content of a vector must not be longer than the input.
+    let error = TlsByteSliceU16::tls_deserialize_bytes(&b[5..9]).unwrap_err();
+    assert_eq!(&Error::EndOfStream, error.kind());
+    assert_eq!(2, error.offset());
+    // Elements must not overflow the vector.
+    let error = TlsVecU8::<Option<u16>>::tls_deserialize_bytes(&[4u8, 1, 0, 1, 1, 0]).unwrap_err();
+    assert_eq!(&Error::EndOfStream, error.kind());
+    assert_eq!(5, error.offset());
+    assert_eq!("[1]", error.path().unwrap().to_string());
+    // Vectors of fixed-size elements are checked before decoding them.
+    let error = TlsVecU8::<u16>::tls_deserialize_bytes(&[3u8, 0, 1, 0]).unwrap_err();
+    assert_eq!(
+        Error::InvalidVectorLength {
+            expected: 4,
+            actual: 3
+        },
+        error
+    );
+
+    let long_vector = vec![77u8; 65535];
+    let serialized_long_vec = TlsSliceU16(&long_vector).tls_serialize_detached().unwrap();
+    let (deserialized_long_vec, remainder) =
+        TlsVecU16::<u8>::tls_deserialize_bytes(&serialized_long_vec).unwrap();
+    assert!(remainder.is_empty());
+    assert_eq!(long_vector.as_slice(), deserialized_long_vec.as_slice());
+}
+
+#[test]
+fn deserialize_errors() {
+    // The length of the vector isn't a multiple of the element length.
+    let b = &[3u8, 0, 1, 0, 2] as &[u8];
+    let error = TlsVecU8::<u16>::tls_deserialize(&mut &b[..]).unwrap_err();
+    assert_eq!(
+        Error::InvalidVectorLength {
+            expected: 4,
+            actual: 3
+        },
+        error
+    );
+    assert_eq!(
+        "invalid vector length: expected 4 bytes but got 3",
+        error.to_string()
+    );
+
+    // Errors in nested vectors and tuples carry the offset and path.
+    let b = &[8u8, 2, 4, 0, 1, 0] as &[u8];
+    let error = TlsVecU8::<(u8, TlsVecU8<u16>)>::tls_deserialize(&mut &b[..]).unwrap_err();
+    assert_eq!(&Error::EndOfStream, error.kind());
+    assert_eq!(5, error.offset());
+    assert_eq!("[0].1[1]", error.path().unwrap().to_string());
+    assert_eq!(
+        "unexpected end of stream at byte 5 in `[0].1[1]`",
+        error.to_string()
+    );
+
+    // Errors in byte vectors point to the start of their content.
+    let error = TlsByteVecU16::tls_deserialize(&mut &[0u8, 2, 1][..]).unwrap_err();
+    assert_eq!(&Error::EndOfStream, error.kind());
+    assert_eq!(2, error.offset());
+    assert_eq!(None, error.path().unwrap().root());
+}
+
+#[test]
+fn deserialize_exact() {
+    let b = &[0u8, 2, 1, 2] as &[u8];
+    let v = TlsByteVecU16::tls_deserialize_exact(b).unwrap();
+    assert_eq!(&[1, 2], v.as_slice());
+    let v = TlsByteSliceU16::tls_deserialize_exact_bytes(b).unwrap();
+    assert_eq!(&[1, 2], v.0);
+
+    let b = &[0u8, 2, 1, 2, 3, 4] as &[u8];
+    let error = TlsByteVecU16::tls_deserialize_exact(b).unwrap_err();
+    assert_eq!(Error::TrailingData { remaining: 2 }, error);
+    assert_eq!(
+        "2 bytes of trailing data after the value",
+        error.to_string()
+    );
+    assert_eq!(
+        Error::TrailingData { remaining: 2 },
+        TlsByteSliceU16::tls_deserialize_exact_bytes(b).unwrap_err()
+    );
+
+    // Errors decoding the value are returned as they are.
+    let error = TlsVecU16::<u16>::tls_deserialize_exact(&[0, 4, 0, 1]).unwrap_err();
+    assert_eq!(&Error::EndOfStream, error.kind());
+}
+
+#[test]
+fn fixed_size() {
+    assert_eq!(2, u16::TLS_LEN);
+    assert_eq!(32, <[u8; 32]>::TLS_LEN);
+    assert_eq!(13, <(u8, u32, u64)>::TLS_LEN);
+    assert_eq!(Some(5), <(u8, u32)>::tls_fixed_len());
+    assert_eq!(None, <(u8, TlsVecU8<u8>)>::tls_fixed_len());
+    assert_eq!(None, Option::<u8>::tls_fixed_len());
+
+    // The length of a vector of fixed-size elements must be a multiple of
+    // their length.
+    let b = &[0u8, 6, 0, 0, 0, 1, 0, 0, 0, 2] as &[u8];
+    let expected = Error::InvalidVectorLength {
+        expected: 8,
+        actual: 6,
+    };
+    assert_eq!(
+        expected,
+        TlsVecU16::<u32>::tls_deserialize(&mut &b[..]).unwrap_err()
+    );
+    assert_eq!(
+        expected,
+        TlsVecU16::<u32>::tls_deserialize_bytes(b).unwrap_err()
+    );
+    // The expected length is the nearest multiple.
+    assert_eq!(
+        Error::InvalidVectorLength {
+            expected: 4,
+            actual: 5,
+        },
+        TlsVecU16::<u32>::tls_deserialize(&mut &[0u8, 5, 0, 0, 0, 1, 0][..]).unwrap_err()
+    );
+    let b = &[0u8, 8, 0, 0, 0, 1, 0, 0, 0, 2] as &[u8];
+    let v = TlsVecU16::<u32>::tls_deserialize(&mut &b[..]).unwrap();
+    assert_eq!(&[1, 2], v.as_slice());
+    let (v, remainder) = TlsVecU16::<u32>::tls_deserialize_bytes(b).unwrap();
+    assert_eq!(&[1, 2], v.as_slice());
+    assert!(remainder.is_empty());
+}
+
+#[test]
+fn deserialize_with_limits() {
+    // A bogus length doesn't allocate memory that isn't backed by the input.
+    let b = &[0xffu8, 0xff, 0xff, 0xff, 1, 2, 3] as &[u8];
+    let error = TlsByteVecU32::tls_deserialize(&mut &b[..]).unwrap_err();
+    assert_eq!(&Error::EndOfStream, error.kind());
+
+    let limits = DecodeLimits::default().with_max_vector_len(3);
+    let error = TlsByteVecU32::tls_deserialize_with_limits(&mut &b[..], limits).unwrap_err();
+    assert_eq!(
+        Error::LimitExceeded {
+            limit: DecodeLimit::VectorLength,
+            max: 3,
+            actual: 0xffff_ffff
+        },
+        error
+    );
+    assert_eq!(
+        "decode limit exceeded: vector length of 4294967295 is more than 3",
+        error.to_string()
+    );
+    let vec = TlsByteVecU32::tls_deserialize_with_limits(&mut &[0u8, 0, 0, 3, 1, 2, 3][..], limits)
+        .expect("Error decoding within the limits");
+    assert_eq!(&[1, 2, 3], vec.as_slice());
+
+    // The total allocation is shared between vectors.
+    let b = &[3u8, 1, 2, 3, 3, 4, 5, 6] as &[u8];
+    let limits = DecodeLimits::default().with_max_total_allocation(5);
+    let error = <(TlsByteVecU8, TlsByteVecU8)>::tls_deserialize_with_limits(&mut &b[..], limits)
+        .unwrap_err();
+    assert_eq!(
+        &Error::LimitExceeded {
+            limit: DecodeLimit::TotalAllocation,
+            max: 5,
+            actual: 6
+        },
+        error.kind()
+    );
+    assert_eq!(4, error.offset());
+    assert_eq!("1", error.path().unwrap().to_string());
+
+    // The element count is shared between vectors.
+    let b = &[4u8, 0, 1, 0, 2, 2, 0, 3] as &[u8];
+    let limits = DecodeLimits::default().with_max_element_count(2);
+    let mut reader = b;
+    let mut reader = LimitedReader::new(&mut reader, limits);
+    let vec = TlsVecU8::<u16>::tls_deserialize(&mut reader).expect("Error decoding");
+    assert_eq!(&[1, 2], vec.as_slice());
+    let error = TlsVecU8::<u16>::tls_deserialize(&mut reader).unwrap_err();
+    assert_eq!(
+        &Error::LimitExceeded {
+            limit: DecodeLimit::ElementCount,
+            max: 2,
+            actual: 3
+        },
+        error.kind()
+    );
+    assert_eq!("[0]", error.path().unwrap().to_string());
+
+    // A mutable reference to a limited reader is limited as well.
+    let mut reader = b;
+    let mut reader = LimitedReader::new(&mut reader, limits);
+    let mut reader = &mut reader;
+    assert!(reader.budget().is_some());
+    TlsVecU8::<u16>::tls_deserialize(&mut reader).expect("Error decoding");
+    let error = TlsVecU8::<u16>::tls_deserialize(&mut reader).unwrap_err();
+    assert!(matches!(
+        error.kind(),
+        Error::LimitExceeded {
+            limit: DecodeLimit::ElementCount,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn deserialize_partial() {
+    let value = (
+        TlsVecU16::<TlsByteVecU32>::from(vec![
+            TlsByteVecU32::from_slice(&[1, 2, 3]),
+            TlsByteVecU32::from_slice(&[4; 5]),
+        ]),
+        Some(6u8),
+    );
+    let serialized = value.tls_serialize_detached().unwrap();
+
+    // Every prefix of the input asks for more bytes.
+    for len in 0..serialized.len() {
+        match <(TlsVecU16<TlsByteVecU32>, Option<u8>)>::tls_deserialize_partial(&serialized[..len])
+            .unwrap()
+        {
+            Decoded::NeedMore { at_least } => {
+                assert!(at_least > 0);
+                assert!(len + at_least <= serialized.len());
+            }
+            Decoded::Complete { .. } => panic!("Decoded incomplete input of {} bytes", len),
+        }
+    }
+    assert_eq!(
+        Decoded::NeedMore { at_least: 5 },
+        <(TlsVecU16<TlsByteVecU32>, Option<u8>)>::tls_deserialize_partial(&serialized[..13])
+            .unwrap()
+    );
+
+    // Vectors whose length prefix was read ask for all of their missing
+    // content, even if it's read in pieces.
+    let mut large = vec![0x00u8, 0x10, 0x00, 0x00];
+    large.extend_from_slice(&[1; 10]);
+    assert_eq!(
+        Decoded::NeedMore {
+            at_least: (1 << 20) - 10
+        },
+        TlsByteVecU32::tls_deserialize_partial(&large).unwrap()
+    );
+    assert_eq!(
+        Decoded::NeedMore {
+            at_least: (1 << 20) - 10
+        },
+        TlsByteVecU32::tls_deserialize_partial_with_limits(&large, DecodeLimits::default())
+            .unwrap()
+    );
+    assert_eq!(
+        Decoded::NeedMore {
+            at_least: (1 << 20) - 10
+        },
+        TlsVecU32::<u16>::tls_deserialize_partial(&large).unwrap()
+    );
+
+    // Trailing bytes are left for the next value.
+    let mut input = serialized.clone();
+    input.push(7);
+    assert_eq!(
+        Decoded::Complete {
+            value,
+            consumed: serialized.len()
+        },
+        <(TlsVecU16<TlsByteVecU32>, Option<u8>)>::tls_deserialize_partial(&input).unwrap()
+    );
+
+    // Invalid input still fails.
+    let error = Option::<u8>::tls_deserialize_partial(&[2]).unwrap_err();
+    assert_eq!(Error::InvalidOptionTag { tag: 2 }, error);
+    let limits = DecodeLimits::default().with_max_vector_len(4);
+    let error =
+        TlsByteVecU32::tls_deserialize_partial_with_limits(&[0, 0, 1, 0], limits).unwrap_err();
+    assert!(matches!(error, Error::LimitExceeded { .. }));
+
+    // Errors of decoders that run out of input but fail differently are
+    // returned as they are.
+    let error = Strict::tls_deserialize_partial(&[1]).unwrap_err();
+    assert_eq!(Error::DecodingError("truncated".into()), error);
+}
+
+/// A `u16` that reports running out of input as a decoding error.
+#[derive(Debug)]
+struct Strict;
+
+impl Size for Strict {
+    fn tls_serialized_len(&self) -> usize {
+        2
+    }
+}
+
+impl Deserialize for Strict {
+    fn tls_deserialize<R: Reader>(bytes: &mut R) -> Result<Self, Error> {
+        u16::tls_deserialize(bytes)
+            .map(|_| Strict)
+            .map_err(|_| Error::DecodingError("truncated".into()))
+    }
+}
+
+/// A `u16` that is encoded XORed with the mask in the context.
+#[derive(Debug, PartialEq, Clone, Copy)]
+struct Masked(u16);
+
+impl Size for Masked {
+    fn tls_serialized_len(&self) -> usize {
+        2
+    }
+}
+
+impl Serialize for Masked {
+    fn tls_serialize<W: Writer>(&self, writer: &mut W) -> Result<usize, Error> {
+        self.0.tls_serialize(writer)
+    }
+}
+
+impl Deserialize for Masked {
+    fn tls_deserialize<R: Reader>(bytes: &mut R) -> Result<Self, Error> {
+        u16::tls_deserialize(bytes).map(Self)
+    }
+}
+
+impl SerializeWithContext<u16> for Masked {
+    fn tls_serialize_with_context<W: Writer>(
+        &self,
+        writer: &mut W,
+        mask: &u16,
+    ) -> Result<usize, Error> {
+        (self.0 ^ mask).tls_serialize(writer)
+    }
+}
+
+impl DeserializeWithContext<u16> for Masked {
+    fn tls_deserialize_with_context<R: Reader>(bytes: &mut R, mask: &u16) -> Result<Self, Error> {
+        u16::tls_deserialize(bytes).map(|v| Self(v ^ mask))
+    }
+}
+
+#[test]
+fn deserialize_with_context() {
+    let b = &[4u8, 0xff, 0xfe, 0, 1] as &[u8];
+    let v = TlsVecU8::<Masked>::tls_deserialize_with_context(&mut &b[..], &0xffff).unwrap();
+    assert_eq!(&[Masked(1), Masked(0xfffe)], v.as_slice());
+    assert_eq!(b, v.tls_serialize_detached_with_context(&0xffff).unwrap());
+    assert_eq!(
+        b,
+        TlsSliceU8(v.as_slice())
+            .tls_serialize_detached_with_context(&0xffff)
+            .unwrap()
+    );
+    // Without the context the values are not masked.
+    assert_eq!(
+        &[Masked(0xfffe), Masked(1)],
+        TlsVecU8::<Masked>::tls_deserialize(&mut &b[..])
+            .unwrap()
+            .as_slice()
+    );
+
+    let b = &[1u8, 0, 1, 7, 0, 2] as &[u8];
+    let mut reader = b;
+    let value =
+        <(Option<Masked>, u8, Masked)>::tls_deserialize_with_context(&mut reader, &3).unwrap();
+    assert_eq!((Some(Masked(2)), 7, Masked(1)), value);
+    assert!(reader.is_empty());
+    assert_eq!(b, value.tls_serialize_detached_with_context(&3).unwrap());
+
+    let error =
+        TlsVecU8::<Masked>::tls_deserialize_with_context(&mut &[4u8, 0, 1, 0][..], &0).unwrap_err();
+    assert_eq!(&Error::EndOfStream, error.kind());
+    assert_eq!(3, error.offset());
+    assert_eq!("[1]", error.path().unwrap().to_string());
+}
+
+#[test]
+fn alert_description() {
+    // The length of the vector doesn't match its elements.
+    let error = TlsVecU8::<u16>::tls_deserialize(&mut &[3u8, 0, 1, 0][..]).unwrap_err();
+    assert_eq!(
+        &Error::InvalidVectorLength {
+            expected: 4,
+            actual: 3
+        },
+        error.kind()
+    );
+    assert_eq!(AlertDescription::DecodeError, error.alert_description());
+
+    // Errors in context map to the alert of the underlying error.
+    let error = TlsVecU8::<Option<u8>>::tls_deserialize(&mut &[3u8, 0, 5, 0][..]).unwrap_err();
+    assert_eq!(&Error::InvalidOptionTag { tag: 5 }, error.kind());
+    assert_eq!("[1]", error.path().unwrap().to_string());
+    assert_eq!(AlertDescription::DecodeError, error.alert_description());
+
+    let error = u16::tls_deserialize_exact(&[0u8, 1, 2]).unwrap_err();
+    assert_eq!(AlertDescription::DecodeError, error.alert_description());
+    assert_eq!(
+        AlertDescription::IllegalParameter,
+        Error::UnknownEnumValue { value: 7 }
+            .with_type("ContentType")
+            .alert_description()
+    );
+    assert_eq!(
+        AlertDescription::InternalError,
+        Error::EncodingError("too long".to_string()).alert_description()
+    );
+    assert_eq!(47u8, AlertDescription::IllegalParameter.into());
+}
+
+#[test]
+fn signed_and_other_primitives() {
+    let value = (-2i8, -300i16, i32::MIN, i64::MAX);
+    let serialized = value.0.tls_serialize_detached().unwrap();
+    assert_eq!(vec![0xfe], serialized);
+    let serialized = (value.1, value.2, value.3)
+        .tls_serialize_detached()
+        .unwrap();
+    assert_eq!(
+        vec![0xfe, 0xd4, 0x80, 0, 0, 0, 0x7f, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff],
+        serialized
+    );
+    assert_eq!(
+        (value.1, value.2, value.3),
+        <(i16, i32, i64)>::tls_deserialize_exact(&serialized).unwrap()
+    );
+    assert_eq!(14, <(i16, i32, i64)>::TLS_LEN);
+
+    let serialized = (u128::MAX - 1, -1i128).tls_serialize_detached().unwrap();
+    assert_eq!(32, serialized.len());
+    assert_eq!(0xfe, serialized[15]);
+    assert_eq!(
+        (u128::MAX - 1, -1),
+        <(u128, i128)>::tls_deserialize_bytes(&serialized)
+            .unwrap()
+            .0
+    );
+
+    assert_eq!(
+        vec![1u8, 0],
+        (true, false).tls_serialize_detached().unwrap()
+    );
+    assert!(bool::tls_deserialize(&mut &[1u8][..]).unwrap());
+    assert!(!bool::tls_deserialize_exact_bytes(&[0]).unwrap());
+    let error = bool::tls_deserialize(&mut &[2u8][..]).unwrap_err();
+    assert_eq!(Error::InvalidBool { value: 2 }, error);
+    assert_eq!(AlertDescription::DecodeError, error.alert_description());
+    assert_eq!(
+        Error::InvalidBool { value: 0xff },
+        bool::tls_deserialize_bytes(&[0xff]).unwrap_err()
+    );
+
+    // `()` and `PhantomData` have no encoding.
+    let marker = (7u8, (), core::marker::PhantomData::<str>);
+    assert_eq!(1, marker.tls_serialized_len());
+    assert_eq!(vec![7u8], marker.tls_serialize_detached().unwrap());
+    assert_eq!(
+        marker,
+        <(u8, (), core::marker::PhantomData<str>)>::tls_deserialize_exact(&[7]).unwrap()
+    );
+    assert_eq!(0, <()>::TLS_LEN);
+
+    // Vectors of zero-length elements can't make progress and fail instead
+    // of looping forever.
+    let empty = TlsVecU8::<()>::tls_deserialize(&mut &[0u8][..]).unwrap();
+    assert!(empty.is_empty());
+    let error = TlsVecU8::<()>::tls_deserialize(&mut &[1u8, 0][..]).unwrap_err();
+    assert!(matches!(error.kind(), Error::DecodingError(_)));
+    assert_eq!("[0]", error.path().unwrap().to_string());
+    assert!(TlsVecU8::<()>::tls_deserialize_bytes(&[1u8, 0]).is_err());
+}
+
+#[test]
+fn uint_n() {
+    use core::convert::TryFrom;
+
+    assert_eq!(0xff_ffff, U24::MAX);
+    assert_eq!(0xffff_ffff_ffff, U48::MAX);
+    assert_eq!(0xff, UintN::<1>::MAX);
+    assert_eq!(u64::MAX, UintN::<8>::MAX);
+    assert_eq!(Error::InvalidInput, U24::new(1 << 24).unwrap_err());
+    assert!(U24::try_from(0xff_ffffu32).is_ok());
+    assert!(U24::try_from(usize::MAX).is_err());
+    let seq = U48::try_from(0x0102_0304_0506u64).unwrap();
+    assert!(u32::try_from(seq).is_err());
+    assert_eq!(0x0102_0304_0506, u64::from(seq));
+    assert_eq!(
+        5,
+        u32::try_from(U24::tls_deserialize_exact(&[0, 0, 5]).unwrap()).unwrap()
+    );
+
+    let value = (U24::new(0x0a0b0c).unwrap(), seq);
+    let serialized = value.tls_serialize_detached().unwrap();
+    assert_eq!(vec![0x0a, 0x0b, 0x0c, 1, 2, 3, 4, 5, 6], serialized);
+    assert_eq!(9, <(U24, U48)>::TLS_LEN);
+    assert_eq!(
+        value,
+        <(U24, U48)>::tls_deserialize_exact(&serialized).unwrap()
+    );
+    assert_eq!(
+        (value, &[7u8][..]),
+        <(U24, U48)>::tls_deserialize_bytes(&[0x0a, 0x0b, 0x0c, 1, 2, 3, 4, 5, 6, 7]).unwrap()
+    );
+    assert_eq!(
+        Error::EndOfStream,
+        U48::tls_deserialize(&mut &[1u8, 2, 3][..]).unwrap_err()
+    );
+
+    // `uint24` length prefixes.
+    let bytes = TlsByteVecU24::from_slice(&[1, 2, 3]);
+    let serialized = bytes.tls_serialize_detached().unwrap();
+    assert_eq!(vec![0u8, 0, 3, 1, 2, 3], serialized);
+    assert_eq!(
+        bytes,
+        TlsByteVecU24::tls_deserialize_exact(&serialized).unwrap()
+    );
+    assert_eq!(
+        bytes,
+        TlsByteVecU24::tls_deserialize_exact_bytes(&serialized).unwrap()
+    );
+    let v = TlsVecU24::<u16>::from_slice(&[1, 0x203]);
+    let serialized = v.tls_serialize_detached().unwrap();
+    assert_eq!(vec![0u8, 0, 4, 0, 1, 2, 3], serialized);
+    assert_eq!(v, TlsVecU24::tls_deserialize_exact(&serialized).unwrap());
+    assert_eq!(
+        Error::EndOfStream,
+        *TlsVecU24::<u16>::tls_deserialize(&mut &[0u8, 0, 4, 0, 1][..])
+            .unwrap_err()
+            .kind()
+    );
+}
+
+#[test]
+fn uint24_vectors() {
+    // `CertificateEntry certificate_list<0..2^24-1>` with
+    // `opaque cert_data<1..2^24-1>`.
+    let certificates = [
+        TlsByteVecU24::from_slice(&[1, 2, 3]),
+        TlsByteVecU24::from_slice(&[4]),
+    ];
+    let list = TlsSliceU24(&certificates);
+    let serialized = list.tls_serialize_detached().unwrap();
+    assert_eq!(vec![0u8, 0, 10, 0, 0, 3, 1, 2, 3, 0, 0, 1, 4], serialized);
+    assert_eq!(serialized.len(), list.tls_serialized_len());
+    assert_eq!(
+        TlsVecU24::from_slice(&certificates),
+        TlsVecU24::<TlsByteVecU24>::tls_deserialize_exact(&serialized).unwrap()
+    );
+    let (first, remainder) = TlsByteSliceU24::tls_deserialize_bytes(&serialized[3..]).unwrap();
+    assert_eq!(&[1, 2, 3], first.0);
+    assert_eq!(&serialized[9..], remainder);
+
+    let secret = SecretTlsVecU24::<u16>::from_slice(&[1, 2]);
+    let serialized = secret.tls_serialize_detached().unwrap();
+    assert_eq!(vec![0u8, 0, 4, 0, 1, 0, 2], serialized);
+    assert_eq!(
+        secret,
+        SecretTlsVecU24::tls_deserialize_exact(&serialized).unwrap()
+    );
+    assert_eq!(3, SecretTlsVecU24::<u16>::len_len());
+
+    // Content of `2^24` bytes or more doesn't fit into the length prefix.
+    let too_long = vec![0u8; 1 << 24];
+    let expected = Err(Error::InvalidVectorLength {
+        expected: (1 << 24) - 1,
+        actual: 1 << 24,
+    });
+    assert_eq!(
+        expected,
+        TlsByteSliceU24(&too_long).tls_serialize_detached()
+    );
+    assert_eq!(expected, TlsSliceU24(&too_long).tls_serialize_detached());
+    assert_eq!(
+        expected,
+        TlsByteVecU24::from_slice(&too_long).tls_serialize_detached()
+    );
+    assert_eq!(
+        Ok((1 << 24) + 2),
+        TlsByteSliceU24(&too_long[1..])
+            .tls_serialize_detached()
+            .map(|v| v.len())
+    );
+}
+
+#[test]
+fn var_int() {
+    use core::convert::TryFrom;
+
+    // The examples from RFC 9000, Appendix A.1.
+    for (value, encoding) in [
+        (
+            151_288_809_941_952_652u64,
+            &[0xc2u8, 0x19, 0x7c, 0x5e, 0xff, 0x14, 0xe8, 0x8c][..],
+        ),
+        (494_878_333, &[0x9d, 0x7f, 0x3e, 0x7d]),
+        (15_293, &[0x7b, 0xbd]),
+        (37, &[0x25]),
+    ] {
+        let var_int = VarInt::new(value).unwrap();
+        assert_eq!(encoding.len(), var_int.tls_serialized_len());
+        assert_eq!(encoding, &var_int.tls_serialize_detached().unwrap()[..]);
+        assert_eq!(var_int, VarInt::tls_deserialize_exact(encoding).unwrap());
+        assert_eq!(
+            var_int,
+            VarInt::tls_deserialize_exact_bytes(encoding).unwrap()
+        );
+    }
+
+    assert_eq!(
+        Error::NonMinimalVarInt { value: 37 },
+        VarInt::tls_deserialize_exact(&[0x40, 0x25]).unwrap_err()
+    );
+    assert_eq!(
+        Error::NonMinimalVarInt { value: 63 },
+        VarInt::tls_deserialize_bytes(&[0x80, 0, 0, 0x3f]).unwrap_err()
+    );
+    assert_eq!(
+        AlertDescription::DecodeError,
+        Error::NonMinimalVarInt { value: 0 }.alert_description()
+    );
+    assert_eq!(
+        Error::EndOfStream,
+        *VarInt::tls_deserialize(&mut &[0x40u8][..])
+            .unwrap_err()
+            .kind()
+    );
+    assert_eq!(
+        1,
+        VarInt::tls_deserialize_bytes(&[0x40]).unwrap_err().offset()
+    );
+    assert_eq!(Error::InvalidInput, VarInt::new(1 << 62).unwrap_err());
+    assert_eq!(
+        vec![0xffu8; 8],
+        VarInt::new(VarInt::MAX)
+            .unwrap()
+            .tls_serialize_detached()
+            .unwrap()
+    );
+    assert_eq!(16_383, u64::from(VarInt::from(16_383u16)));
+    assert!(VarInt::try_from(u64::MAX).is_err());
+
+    // Vectors with a variable-length prefix.
+    let bytes = VLBytes::from_slice(&[7; 100]);
+    let serialized = bytes.tls_serialize_detached().unwrap();
+    assert_eq!(102, bytes.tls_serialized_len());
+    assert_eq!(&[0x40, 100, 7], &serialized[..3]);
+    assert_eq!(bytes, VLBytes::tls_deserialize_exact(&serialized).unwrap());
+    assert_eq!(
+        bytes,
+        VLBytes::tls_deserialize_exact_bytes(&serialized).unwrap()
+    );
+    let mut buf = [0u8; 102];
+    assert_eq!(102, bytes.tls_serialize_to_slice(&mut buf).unwrap());
+    assert_eq!(&serialized[..], &buf[..]);
+    let error = VLBytes::tls_deserialize(&mut &serialized[..50]).unwrap_err();
+    assert_eq!(&Error::EndOfStream, error.kind());
+    assert_eq!(2, error.offset());
+
+    let v = TlsVarVec::<(u8, VLBytes)>::from(vec![
+        (1, VLBytes::from_slice(&[2, 3])),
+        (4, VLBytes::default()),
+    ]);
+    let serialized = v.tls_serialize_detached().unwrap();
+    assert_eq!(vec![6u8, 1, 2, 2, 3, 4, 0], serialized);
+    assert_eq!(v, TlsVarVec::tls_deserialize_exact(&serialized).unwrap());
+    assert_eq!(
+        v,
+        TlsVarVec::tls_deserialize_exact_bytes(&serialized).unwrap()
+    );
+    let error =
+        TlsVarVec::<(u8, VLBytes)>::tls_deserialize(&mut &[5u8, 1, 2, 2, 3, 4][..]).unwrap_err();
+    assert_eq!("[1].1", error.path().unwrap().to_string());
+
+    // MLS doesn't allow the 8 byte encoding for vector lengths.
+    let b = &[0xc0u8, 0, 0, 0, 0x40, 0, 0, 0] as &[u8];
+    assert!(matches!(
+        VLBytes::tls_deserialize(&mut &b[..]).unwrap_err(),
+        Error::InvalidVectorLength { .. }
+    ));
+    assert!(matches!(
+        TlsVarVec::<u8>::tls_deserialize_bytes(b).unwrap_err(),
+        Error::InvalidVectorLength { .. }
+    ));
+    // Non-minimal length prefixes are rejected as well.
+    assert_eq!(
+        &Error::NonMinimalVarInt { value: 1 },
+        VLBytes::tls_deserialize(&mut &[0x40u8, 1, 0][..])
+            .unwrap_err()
+            .kind()
+    );
+    let limits = DecodeLimits::default().with_max_vector_len(1);
+    let mut reader = &[2u8, 0, 1][..];
+    assert!(matches!(
+        TlsVarVec::<u8>::tls_deserialize_with_limits(&mut reader, limits).unwrap_err(),
+        Error::LimitExceeded {
+            limit: DecodeLimit::VectorLength,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn arrays() {
+    // Byte arrays of any length, e.g. an uncompressed P-521 point.
+    let point = [4u8; 133];
+    let serialized = point.tls_serialize_detached().unwrap();
+    assert_eq!(&point[..], &serialized[..]);
+    assert_eq!(133, <[u8; 133]>::TLS_LEN);
+    assert_eq!(
+        point,
+        <[u8; 133]>::tls_deserialize_exact(&serialized).unwrap()
+    );
+    assert_eq!(
+        (point, &[5u8][..]),
+        <[u8; 133]>::tls_deserialize_bytes(&[&serialized[..], &[5]].concat()).unwrap()
+    );
+    let mut buf = [0u8; 256];
+    assert_eq!(256, [7u8; 256].tls_serialize_to_slice(&mut buf).unwrap());
+    assert_eq!([7u8; 256], buf);
+    assert_eq!(
+        Error::EndOfStream,
+        <[u8; 256]>::tls_deserialize(&mut &buf[..255]).unwrap_err()
+    );
+    assert_eq!(0, [0u8; 0].tls_serialized_len());
+
+    // Arrays of other types.
+    let value = [1u16, 2, 0x304, 5];
+    let serialized = value.tls_serialize_detached().unwrap();
+    assert_eq!(vec![0u8, 1, 0, 2, 3, 4, 0, 5], serialized);
+    assert_eq!(8, <[u16; 4]>::TLS_LEN);
+    assert_eq!(
+        value,
+        <[u16; 4]>::tls_deserialize_exact(&serialized).unwrap()
+    );
+    assert_eq!(
+        value,
+        <[u16; 4]>::tls_deserialize_exact_bytes(&serialized).unwrap()
+    );
+    let nested = [[1u8, 2], [3, 4], [5, 6]];
+    assert_eq!(
+        nested,
+        <[[u8; 2]; 3]>::tls_deserialize_exact(&nested.tls_serialize_detached().unwrap()).unwrap()
+    );
+
+    // Elements with a variable length.
+    let value = [TlsVecU8::from_slice(&[1u8]), TlsVecU8::from_slice(&[2, 3])];
+    assert_eq!(None, <[TlsVecU8<u8>; 2]>::tls_fixed_len());
+    let serialized = value.tls_serialize_detached().unwrap();
+    assert_eq!(vec![1u8, 1, 2, 2, 3], serialized);
+    assert_eq!(
+        value,
+        <[TlsVecU8<u8>; 2]>::tls_deserialize_exact(&serialized).unwrap()
+    );
+    let error = <[TlsVecU8<u8>; 2]>::tls_deserialize(&mut &serialized[..4]).unwrap_err();
+    assert_eq!(&Error::EndOfStream, error.kind());
+    assert_eq!("[1][1]", error.path().unwrap().to_string());
+    assert_eq!(4, error.offset());
+    let error = <[bool; 3]>::tls_deserialize_bytes(&[1, 0, 2]).unwrap_err();
+    assert_eq!(&Error::InvalidBool { value: 2 }, error.kind());
+    assert_eq!("[2]", error.path().unwrap().to_string());
+}
+
+#[test]
+fn tuples() {
+    type Twelve = (
+        u8,
+        u16,
+        bool,
+        u8,
+        U24,
+        u8,
+        u8,
+        u8,
+        u8,
+        u8,
+        TlsVecU8<u8>,
+        u32,
+    );
+    let value: Twelve = (
+        1,
+        2,
+        true,
+        4,
+        U24::new(5).unwrap(),
+        6,
+        7,
+        8,
+        9,
+        10,
+        TlsVecU8::from_slice(&[11, 12]),
+        13,
+    );
+    assert_eq!(None, Twelve::tls_fixed_len());
+    let serialized = value.tls_serialize_detached().unwrap();
+    assert_eq!(
+        vec![1u8, 0, 2, 1, 4, 0, 0, 5, 6, 7, 8, 9, 10, 2, 11, 12, 0, 0, 0, 13],
+        serialized
+    );
+    assert_eq!(serialized.len(), value.tls_serialized_len());
+    assert_eq!(value, Twelve::tls_deserialize_exact(&serialized).unwrap());
+    assert_eq!(
+        value,
+        Twelve::tls_deserialize_exact_bytes(&serialized).unwrap()
+    );
+    assert_eq!(7, <(u8, u16, u32)>::TLS_LEN);
+
+    // Errors carry the offset and index of the failing element.
+    let mut invalid = serialized.clone();
+    invalid[3] = 2;
+    let error = Twelve::tls_deserialize(&mut invalid.as_slice()).unwrap_err();
+    assert_eq!(&Error::InvalidBool { value: 2 }, error.kind());
+    assert_eq!("2", error.path().unwrap().to_string());
+    assert_eq!(3, error.offset());
+    // The content of the vector starts after its length at offset 13.
+    let error = Twelve::tls_deserialize_bytes(&serialized[..15]).unwrap_err();
+    assert_eq!(&Error::EndOfStream, error.kind());
+    assert_eq!("10", error.path().unwrap().to_string());
+    assert_eq!(14, error.offset());
+}
+
+#[test]
+fn pointers() {
+    use std::{borrow::Cow, rc::Rc, sync::Arc};
+
+    // A recursive list.
+    #[derive(Debug, PartialEq)]
+    struct List(u8, Option<Box<List>>);
+
+    impl Size for List {
+        fn tls_serialized_len(&self) -> usize {
+            (&self.0, &self.1).tls_serialized_len()
+        }
+    }
+
+    impl Serialize for List {
+        fn tls_serialize<W: Writer>(&self, writer: &mut W) -> Result<usize, Error> {
+            (&self.0, &self.1).tls_serialize(writer)
+        }
+    }
+
+    impl Deserialize for List {
+        fn tls_deserialize<R: Reader>(bytes: &mut R) -> Result<Self, Error> {
+            let (head, tail) = <(u8, Option<Box<List>>)>::tls_deserialize(bytes)?;
+            Ok(Self(head, tail))
+        }
+    }
+
+    let list = List(1, Some(Box::new(List(2, None))));
+    let serialized = list.tls_serialize_detached().unwrap();
+    assert_eq!(vec![1u8, 1, 2, 0], serialized);
+    assert_eq!(list, List::tls_deserialize_exact(&serialized).unwrap());
+    let error = List::tls_deserialize(&mut &[1u8, 1, 2, 2][..]).unwrap_err();
+    assert_eq!(&Error::InvalidOptionTag { tag: 2 }, error.kind());
+    assert_eq!("1.1", error.path().unwrap().to_string());
+
+    // Shared and borrowed values are encoded like the value itself.
+    let shared = Rc::new(TlsVecU8::from_slice(&[1u16, 2]));
+    let serialized = shared.tls_serialize_detached().unwrap();
+    assert_eq!(vec![4u8, 0, 1, 0, 2], serialized);
+    assert_eq!(
+        shared,
+        Rc::<TlsVecU8<u16>>::tls_deserialize_exact(&serialized).unwrap()
+    );
+    assert_eq!(
+        Arc::new(0x102u16),
+        Arc::<u16>::tls_deserialize_exact_bytes(&[1, 2]).unwrap()
+    );
+    assert_eq!(2, <Box<u16>>::TLS_LEN);
+    let borrowed: Cow<[u8; 2]> = Cow::Borrowed(&[1, 2]);
+    assert_eq!(vec![1u8, 2], borrowed.tls_serialize_detached().unwrap());
+    let decoded = Cow::<[u8; 2]>::tls_deserialize_exact(&[1, 2]).unwrap();
+    assert!(matches!(decoded, Cow::Owned([1, 2])));
+    let mut value = 5u8;
+    assert_eq!(
+        vec![5u8, 6],
+        (&mut value, &6u8).tls_serialize_detached().unwrap()
+    );
+}
+
+#[test]
+fn strings_and_bytes() {
+    use std::{borrow::Cow, rc::Rc, sync::Arc};
+
+    // Strings and byte vectors have a variable-length integer prefix.
+    let s = String::from("hello");
+    let serialized = s.tls_serialize_detached().unwrap();
+    assert_eq!(b"\x05hello", serialized.as_slice());
+    assert_eq!(serialized, "hello".tls_serialize_detached().unwrap());
+    assert_eq!(s, String::tls_deserialize_exact(&serialized).unwrap());
+    assert_eq!(
+        ("hello", &[][..]),
+        <&str>::tls_deserialize_bytes(&serialized).unwrap()
+    );
+    let long = vec![7u8; 100];
+    let serialized = long.tls_serialize_detached().unwrap();
+    assert_eq!(&[0x40, 100], &serialized[..2]);
+    assert_eq!(serialized, long[..].tls_serialize_detached().unwrap());
+    assert_eq!(long, Vec::<u8>::tls_deserialize_exact(&serialized).unwrap());
+
+    // Invalid UTF-8 is rejected.
+    let error = String::tls_deserialize_exact(&[3, b'a', 0xff, b'b']).unwrap_err();
+    assert_eq!(Error::InvalidUtf8 { valid_up_to: 1 }, error);
+    assert_eq!(
+        error,
+        <&str>::tls_deserialize_bytes(&[3, b'a', 0xff, b'b']).unwrap_err()
+    );
+
+    // `Cow` decodes into the owned value.
+    let borrowed: Cow<str> = Cow::Borrowed("cow");
+    let serialized = borrowed.tls_serialize_detached().unwrap();
+    let decoded = Cow::<str>::tls_deserialize_exact(&serialized).unwrap();
+    assert!(matches!(&decoded, Cow::Owned(s) if s == "cow"));
+    let bytes: Cow<[u8]> = Cow::Borrowed(&[1, 2]);
+    let serialized = bytes.tls_serialize_detached().unwrap();
+    assert_eq!(
+        bytes,
+        Cow::<[u8]>::tls_deserialize_exact(&serialized).unwrap()
+    );
+
+    // Smart pointers to unsized byte slices and strings.
+    let boxed: Box<[u8]> = Box::from(&[3u8, 4, 5][..]);
+    let serialized = boxed.tls_serialize_detached().unwrap();
+    assert_eq!(vec![3u8, 3, 4, 5], serialized);
+    assert_eq!(
+        boxed,
+        Box::<[u8]>::tls_deserialize_exact(&serialized).unwrap()
+    );
+    let (decoded, remainder) = Box::<[u8]>::tls_deserialize_bytes(&serialized).unwrap();
+    assert_eq!(boxed, decoded);
+    assert!(remainder.is_empty());
+    let shared: Arc<str> = Arc::from("shared");
+    let serialized = shared.tls_serialize_detached().unwrap();
+    assert_eq!(
+        shared,
+        Arc::<str>::tls_deserialize_exact(&serialized).unwrap()
+    );
+    assert_eq!(
+        Rc::<str>::from("shared"),
+        Rc::<str>::tls_deserialize_exact(&serialized).unwrap()
+    );
+}
+
+#[test]
+fn generic_vectors() {
+    // The aliases are the generic vectors with a fixed prefix.
+    let v: TlsVecU16<u16> = TlsVec::<u16, u16>::from_slice(&[1, 2]);
+    let serialized = v.tls_serialize_detached().unwrap();
+    assert_eq!(vec![0u8, 4, 0, 1, 0, 2], serialized);
+    assert_eq!(
+        v,
+        TlsVec::<u16, u16>::tls_deserialize_exact(&serialized).unwrap()
+    );
+    assert_eq!(2, TlsVec::<u16, u16>::len_len());
+
+    // Any length prefix works with the same vector types.
+    let v = TlsVec::<u16, VarInt>::from(vec![1, 2]);
+    assert_eq!(vec![4u8, 0, 1, 0, 2], v.tls_serialize_detached().unwrap());
+    let v = TlsByteVec::<U24>::from(vec![1, 2]);
+    assert_eq!(vec![0u8, 0, 2, 1, 2], v.tls_serialize_detached().unwrap());
+    assert_eq!(
+        Error::InvalidVectorLength {
+            expected: 255,
+            actual: 256
+        },
+        TlsSlice::<u8, u8>::new(&[0; 256])
+            .tls_serialize_detached()
+            .unwrap_err()
+    );
+    assert_eq!(
+        TlsByteSlice::<u16>::new(&[1, 2]).tls_serialize_detached(),
+        TlsByteSliceU16(&[1, 2]).tls_serialize_detached()
+    );
+
+    // Byte vectors and generic vectors convert the same way.
+    let bytes = TlsByteVecU8::from(&[1u8, 2][..]);
+    let elements = TlsVecU8::from(&[1u8, 2][..]);
+    assert_eq!(bytes.as_slice(), elements.as_slice());
+    assert_eq!(Vec::from(bytes), Vec::from(elements));
+    assert_eq!(
+        vec![1u8, 2, 3],
+        Vec::from((1..=3).collect::<TlsByteVecU16>())
+    );
+}
+
+#[test]
+fn bounded_vectors() {
+    // opaque legacy_session_id<0..32>;
+    type SessionId = TlsByteVec<Bounded<u8, 0, 32>>;
+    // CipherSuite cipher_suites<2..2^16-2>;
+    type CipherSuites = TlsVec<u16, Bounded<u16, 2, 65534>>;
+
+    assert_eq!(32, <Bounded<u8, 0, 32>>::MAX_LEN);
+    assert_eq!(255, <Bounded<u8, 0, 1000>>::MAX_LEN);
+    assert_eq!(2, CipherSuites::len_len());
+
+    let suites = CipherSuites::from_slice(&[0x1301, 0x1302]);
+    let serialized = suites.tls_serialize_detached().unwrap();
+    assert_eq!(vec![0u8, 4, 0x13, 1, 0x13, 2], serialized);
+    assert_eq!(
+        suites,
+        CipherSuites::tls_deserialize_exact(&serialized).unwrap()
+    );
+    assert_eq!(
+        suites,
+        CipherSuites::tls_deserialize_exact_bytes(&serialized).unwrap()
+    );
+
+    // Out of bounds on encode.
+    let out_of_bounds = Error::VectorLengthOutOfBounds {
+        min: 2,
+        max: 65534,
+        actual: 0,
+    };
+    assert_eq!(
+        out_of_bounds,
+        CipherSuites::default()
+            .tls_serialize_detached()
+            .unwrap_err()
+    );
+    assert_eq!(
+        out_of_bounds,
+        TlsSlice::<u16, Bounded<u16, 2, 65534>>::new(&[])
+            .tls_serialize_detached()
+            .unwrap_err()
+    );
+    assert_eq!(
+        Error::VectorLengthOutOfBounds {
+            min: 0,
+            max: 32,
+            actual: 33
+        },
+        SessionId::from_slice(&[0; 33])
+            .tls_serialize_detached()
+            .unwrap_err()
+    );
+    assert_eq!(
+        Error::VectorLengthOutOfBounds {
+            min: 0,
+            max: 32,
+            actual: 33
+        },
+        SessionId::from_slice(&[0; 33])
+            .tls_serialize_to_slice(&mut [0; 64])
+            .unwrap_err()
+    );
+
+    // Out of bounds on decode, before the content is read.
+    assert_eq!(
+        &out_of_bounds,
+        CipherSuites::tls_deserialize(&mut &[0u8, 0][..])
+            .unwrap_err()
+            .kind()
+    );
+    assert_eq!(
+        &out_of_bounds,
+        CipherSuites::tls_deserialize_bytes(&[0u8, 0])
+            .unwrap_err()
+            .kind()
+    );
+    let error = SessionId::tls_deserialize(&mut &[33u8][..]).unwrap_err();
+    assert_eq!(
+        &Error::VectorLengthOutOfBounds {
+            min: 0,
+            max: 32,
+            actual: 33
+        },
+        error.kind()
+    );
+    assert_eq!(AlertDescription::DecodeError, error.alert_description());
+
+    // The content must be a multiple of the element length.
+    let misaligned = Error::InvalidVectorLength {
+        expected: 4,
+        actual: 3,
+    };
+    assert_eq!(
+        &misaligned,
+        CipherSuites::tls_deserialize(&mut &[0u8, 3, 0x13, 1, 0x13][..])
+            .unwrap_err()
+            .kind()
+    );
+    assert_eq!(
+        &misaligned,
+        CipherSuites::tls_deserialize_bytes(&[0u8, 3, 0x13, 1, 0x13])
+            .unwrap_err()
+            .kind()
+    );
+}
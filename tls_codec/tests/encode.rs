@@ -1,4 +1,8 @@
-use tls_codec::{Serialize, TlsVecU16};
+use tls_codec::{
+    Bounded, DynSerialize, Error, SecretTlsVecU24, Serialize, Size, TlsByteSlice, TlsByteSliceU16,
+    TlsByteSliceU8, TlsByteVec, TlsByteVecU16, TlsSlice, TlsSliceU8, TlsVarVec, TlsVecU16,
+    TlsVecU8, VLBytes, VecWriter, VectoredWriter, Writer,
+};
 
 #[test]
 fn serialize_primitives() {
@@ -21,3 +25,387 @@ fn serialize_tls_vec() {
     let b = [1u8, 0, 4, 77, 88, 1, 99];
     assert_eq!(&b[..], &v[..]);
 }
+
+/// A writer that is not a `std::io::Write`.
+struct Sink(Vec<u8>);
+
+impl Writer for Sink {
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        self.0.extend_from_slice(bytes);
+        Ok(())
+    }
+}
+
+#[test]
+fn serialize_custom_writer() {
+    let mut sink = Sink(Vec::new());
+    let written = (77u8, TlsVecU16::<u16>::from_slice(&[1, 2]))
+        .tls_serialize(&mut sink)
+        .expect("Error encoding tuple");
+    assert_eq!(7, written);
+    assert_eq!(&[77u8, 0, 4, 0, 1, 0, 2][..], &sink.0[..]);
+}
+
+#[test]
+fn serialize_to_slice() {
+    let mut out = [0u8; 16];
+    assert_eq!(2, 0x0102u16.tls_serialize_to_slice(&mut out).unwrap());
+    assert_eq!(&[1, 2], &out[..2]);
+    assert_eq!(3, [3u8, 4, 5].tls_serialize_to_slice(&mut out).unwrap());
+    assert_eq!(&[3, 4, 5], &out[..3]);
+
+    let value = TlsByteVecU16::from_slice(&[6, 7, 8]);
+    assert_eq!(5, value.tls_serialize_to_slice(&mut out[1..]).unwrap());
+    assert_eq!(&[3, 0, 3, 6, 7, 8, 0], &out[..7]);
+
+    // Types without a specialized implementation produce the same output.
+    let value = (Some(9u8), TlsVecU16::<u16>::from_slice(&[1, 2]));
+    let written = value.tls_serialize_to_slice(&mut out).unwrap();
+    assert_eq!(value.tls_serialize_detached().unwrap(), &out[..written]);
+
+    // Nothing is written if the buffer is too short.
+    let mut out = [0u8; 6];
+    assert!(matches!(
+        value.tls_serialize_to_slice(&mut out),
+        Err(Error::InvalidWriteLength(_))
+    ));
+    assert_eq!([0u8; 6], out);
+    assert!(matches!(
+        TlsByteVecU16::from_slice(&[1; 5]).tls_serialize_to_slice(&mut out),
+        Err(Error::InvalidWriteLength(_))
+    ));
+    assert!(matches!(
+        7u64.tls_serialize_to_slice(&mut out),
+        Err(Error::InvalidWriteLength(_))
+    ));
+}
+
+#[test]
+fn serialize_arrays() {
+    let mut out = [0u8; 8];
+    let bytes = [1u8, 2, 3, 4];
+    assert_eq!(4, bytes.tls_serialize_to_slice(&mut out[1..]).unwrap());
+    assert_eq!([0, 1, 2, 3, 4, 0, 0, 0], out);
+    assert_eq!(
+        4,
+        [0x0506u16, 0x0708]
+            .tls_serialize_to_slice(&mut out)
+            .unwrap()
+    );
+    assert_eq!([5, 6, 7, 8, 4, 0, 0, 0], out);
+    assert!(matches!(
+        [9u8; 9].tls_serialize_to_slice(&mut out),
+        Err(Error::InvalidWriteLength(_))
+    ));
+    assert!(matches!(
+        [9u16; 5].tls_serialize_to_slice(&mut out),
+        Err(Error::InvalidWriteLength(_))
+    ));
+    assert_eq!([5, 6, 7, 8, 4, 0, 0, 0], out);
+
+    // Long byte arrays are referenced by a vectored writer.
+    let value = (7u8, [1u8; 300]);
+    let mut writer = VectoredWriter::new();
+    assert_eq!(301, value.tls_serialize_vectored(&mut writer).unwrap());
+    let slices: Vec<&[u8]> = writer.slices().collect();
+    assert_eq!(2, slices.len());
+    assert_eq!(&[7u8], slices[0]);
+    assert!(std::ptr::eq(&value.1[..], slices[1]));
+
+    // Arrays pass the vectored writer on to their elements.
+    let value = [TlsByteSliceU16(&[2u8; 300]), TlsByteSliceU16(&[3])];
+    let mut writer = VectoredWriter::new();
+    assert_eq!(305, value.tls_serialize_vectored(&mut writer).unwrap());
+    assert_eq!(value.tls_serialize_detached().unwrap(), writer.to_vec());
+    assert!(writer.slices().any(|slice| std::ptr::eq(value[0].0, slice)));
+}
+
+#[test]
+fn serialize_patched() {
+    let v = TlsVecU16::from(vec![
+        (1u8, TlsVecU8::<u16>::from_slice(&[2, 3])),
+        (4u8, TlsVecU8::<u16>::from_slice(&[])),
+    ]);
+    let expected = vec![0u8, 8, 1, 4, 0, 2, 0, 3, 4, 0];
+
+    // Writing to a vector directly buffers the elements of the outer vector.
+    let mut plain = Vec::new();
+    assert_eq!(10, v.tls_serialize(&mut plain).unwrap());
+    assert_eq!(expected, plain);
+
+    // A `VecWriter` patches the length prefixes instead.
+    let mut writer = VecWriter(vec![0xff]);
+    assert_eq!(10, v.tls_serialize(&mut writer).unwrap());
+    assert_eq!(0xff, writer.0[0]);
+    assert_eq!(expected, writer.0[1..]);
+    assert_eq!(expected, v.tls_serialize_detached().unwrap());
+
+    let mut out = [0u8; 11];
+    assert_eq!(10, v.tls_serialize_to_slice(&mut out).unwrap());
+    assert_eq!(expected, out[..10]);
+
+    // Writers that can't patch fail if asked to.
+    assert!(matches!(
+        Sink(Vec::new()).patch_bytes(0, &[1]),
+        Err(Error::EncodingError(_))
+    ));
+    assert!(matches!(
+        VecWriter(vec![0]).patch_bytes(0, &[1, 2]),
+        Err(Error::EncodingError(_))
+    ));
+}
+
+#[test]
+fn serialize_vectored() {
+    use std::io::Write;
+
+    let payload = vec![7u8; 300];
+    let value = (
+        TlsVecU16::from(vec![
+            Some(TlsByteVecU16::from(payload.clone())),
+            None,
+            Some(TlsByteVecU16::from_slice(&[1, 2])),
+        ]),
+        TlsByteSliceU16(&payload),
+    );
+    let expected = value.tls_serialize_detached().unwrap();
+
+    let mut writer = VectoredWriter::new();
+    let written = value.tls_serialize_vectored(&mut writer).unwrap();
+    assert_eq!(expected.len(), written);
+    assert_eq!(written, writer.len());
+    assert_eq!(expected, writer.to_vec());
+
+    // The two long byte vectors are borrowed, everything else is copied.
+    let slices: Vec<&[u8]> = writer.slices().collect();
+    assert_eq!(4, slices.len());
+    assert_eq!(&[1u8, 53, 1, 1, 44], slices[0]);
+    assert!(std::ptr::eq(
+        value.0[0].as_ref().unwrap().as_slice(),
+        slices[1]
+    ));
+    assert_eq!(&[0u8, 1, 0, 2, 1, 2, 1, 44], slices[2]);
+    assert!(std::ptr::eq(value.1 .0, slices[3]));
+
+    let mut out = Vec::new();
+    let io_slices = writer.io_slices();
+    assert_eq!(written, out.write_vectored(&io_slices).unwrap());
+    assert_eq!(expected, out);
+
+    // Short byte vectors are copied.
+    let mut writer = VectoredWriter::new().with_min_borrow_len(1024);
+    assert_eq!(1024, writer.min_borrow_len());
+    value.tls_serialize_vectored(&mut writer).unwrap();
+    assert_eq!(1, writer.slices().count());
+    assert_eq!(expected, writer.to_vec());
+
+    // Vectors that are too long fail.
+    let mut writer = VectoredWriter::new();
+    assert!(writer.is_empty());
+    assert_eq!(
+        Err(Error::InvalidVectorLength {
+            expected: 255,
+            actual: 300
+        }),
+        TlsByteSliceU8(&payload).tls_serialize_vectored(&mut writer)
+    );
+}
+
+/// A byte that panics if its length is computed.
+struct Unmeasured(u8);
+
+impl Size for Unmeasured {
+    fn tls_serialized_len(&self) -> usize {
+        panic!("the length of an element was computed")
+    }
+}
+
+impl Serialize for Unmeasured {
+    fn tls_serialize<W: Writer>(&self, writer: &mut W) -> Result<usize, Error> {
+        self.0.tls_serialize(writer)
+    }
+}
+
+#[test]
+fn serialize_single_pass() {
+    // Writers that can patch don't compute the length of the elements.
+    let v = TlsVecU16::from(vec![
+        TlsVecU8::from(vec![Unmeasured(1), Unmeasured(2)]),
+        TlsVecU8::from(vec![]),
+    ]);
+    let expected = vec![0u8, 4, 2, 1, 2, 0];
+
+    let mut writer = VecWriter::default();
+    assert_eq!(6, v.tls_serialize(&mut writer).unwrap());
+    assert_eq!(expected, writer.0);
+
+    let mut writer = VectoredWriter::new();
+    assert_eq!(6, v.tls_serialize_vectored(&mut writer).unwrap());
+    assert_eq!(expected, writer.to_vec());
+
+    // Other writers get the same output, streamed after computing the length.
+    let v = TlsVecU16::from(vec![TlsVecU8::from(vec![1u8, 2]), TlsVecU8::from(vec![])]);
+    let mut plain = Vec::new();
+    assert_eq!(6, v.tls_serialize(&mut plain).unwrap());
+    assert_eq!(expected, plain);
+
+    // Variable-length prefixes can't be patched.
+    let v = TlsVarVec::from(vec![TlsVarVec::from(vec![3u8])]);
+    let expected = vec![2u8, 1, 3];
+
+    let mut writer = VecWriter::default();
+    assert_eq!(3, v.tls_serialize(&mut writer).unwrap());
+    assert_eq!(expected, writer.0);
+
+    let mut writer = VectoredWriter::new();
+    assert_eq!(3, v.tls_serialize_vectored(&mut writer).unwrap());
+    assert_eq!(expected, writer.to_vec());
+}
+
+/// A value that claims to be as long as the address space.
+#[derive(Debug)]
+struct Huge;
+
+impl Size for Huge {
+    fn tls_serialized_len(&self) -> usize {
+        usize::MAX
+    }
+}
+
+impl Serialize for Huge {
+    fn tls_serialize<W: Writer>(&self, _: &mut W) -> Result<usize, Error> {
+        Ok(usize::MAX)
+    }
+}
+
+#[test]
+fn serialized_len_overflow() {
+    assert_eq!(Ok(usize::MAX), Huge.tls_serialized_len_checked());
+    assert!(matches!(
+        (Huge, 1u8).tls_serialized_len_checked(),
+        Err(Error::EncodingError(_))
+    ));
+    assert!(matches!(
+        (1u8, 2u16, Huge).tls_serialized_len_checked(),
+        Err(Error::EncodingError(_))
+    ));
+    assert!(matches!(
+        Some(Huge).tls_serialized_len_checked(),
+        Err(Error::EncodingError(_))
+    ));
+
+    // Vectors fail instead of writing a wrong length prefix.
+    let v = TlsSliceU8(&[Huge, Huge]);
+    assert!(matches!(
+        v.tls_serialized_len_checked(),
+        Err(Error::EncodingError(_))
+    ));
+    let mut out = Vec::new();
+    assert!(matches!(
+        v.tls_serialize(&mut out),
+        Err(Error::EncodingError(_))
+    ));
+    assert!(out.is_empty());
+    assert!(matches!(
+        (Huge, 1u8).tls_serialize_detached(),
+        Err(Error::EncodingError(_))
+    ));
+
+    // Content that is too long for the length prefix is an error, too.
+    assert_eq!(
+        Err(Error::InvalidVectorLength {
+            expected: 255,
+            actual: 256
+        }),
+        TlsSliceU8(&[0u8; 256]).tls_serialize_detached()
+    );
+    assert_eq!(
+        Err(Error::InvalidVectorLength {
+            expected: 255,
+            actual: 256
+        }),
+        TlsByteSliceU8(&[0u8; 256]).tls_serialize_detached()
+    );
+}
+
+#[test]
+fn serialize_dyn() {
+    let extensions: Vec<Box<dyn DynSerialize>> = vec![
+        Box::new(7u32),
+        Box::new(TlsVecU8::<u16>::from_slice(&[1, 2])),
+        Box::new(TlsByteSliceU8(&[3])),
+        Box::new((5u8, None::<u8>)),
+    ];
+    let extensions = TlsVecU16::from(extensions);
+    let expected = vec![0u8, 13, 0, 0, 0, 7, 4, 0, 1, 0, 2, 1, 3, 5, 0];
+    assert_eq!(expected.len(), extensions.tls_serialized_len());
+    // Patching the length prefixes goes through the trait objects.
+    assert_eq!(expected, extensions.tls_serialize_detached().unwrap());
+    let mut v = Vec::new();
+    assert_eq!(expected.len(), extensions.tls_serialize(&mut v).unwrap());
+    assert_eq!(expected, v);
+
+    let mut v = Vec::new();
+    let written = extensions[1].tls_serialize_dyn(&mut v).unwrap();
+    assert_eq!(5, written);
+    assert_eq!(vec![4u8, 0, 1, 0, 2], v);
+}
+
+#[test]
+fn debug_names() {
+    assert_eq!(
+        "TlsVecU8 { vec: [1, 2] }",
+        format!("{:?}", TlsVecU8::<u16>::from_slice(&[1, 2]))
+    );
+    assert_eq!(
+        "SecretTlsVecU24 { vec: [3] }",
+        format!("{:?}", SecretTlsVecU24::<u8>::from_slice(&[3]))
+    );
+    assert_eq!(
+        "TlsByteVecU16 { vec: [4] }",
+        format!("{:?}", TlsByteVecU16::from_slice(&[4]))
+    );
+    assert_eq!(
+        "TlsByteVecU8 { vec: [5] }",
+        format!("{:?}", TlsByteVec::<Bounded<u8, 0, 32>>::from_slice(&[5]))
+    );
+    assert_eq!(
+        "TlsVarVec { vec: [6] }",
+        format!("{:?}", TlsVarVec::<u8>::from_slice(&[6]))
+    );
+    assert_eq!(
+        "VLBytes { vec: [7] }",
+        format!("{:?}", VLBytes::from_slice(&[7]))
+    );
+    assert_eq!(
+        "TlsByteSliceU16([8])",
+        format!("{:?}", TlsByteSliceU16(&[8]))
+    );
+}
+
+#[test]
+fn slice_newtypes() {
+    let values = [1u16, 2];
+    let slice = TlsSliceU8(&values);
+    let TlsSliceU8(inner) = slice;
+    assert_eq!(&values, inner);
+
+    let bytes = TlsByteSliceU16(&[3, 4]);
+    match bytes {
+        TlsByteSliceU16([3, rest @ ..]) => assert_eq!(&[4], rest),
+        _ => panic!("unexpected slice"),
+    }
+
+    // The newtypes convert from and into the generic slices.
+    let generic: TlsSlice<u16, u8> = slice.into();
+    assert_eq!(
+        generic.tls_serialize_detached().unwrap(),
+        TlsSliceU8::from(generic).tls_serialize_detached().unwrap()
+    );
+    let generic: TlsByteSlice<u16> = bytes.into();
+    assert_eq!(bytes, TlsByteSliceU16::from(generic));
+    assert_eq!(
+        bytes.tls_serialize_detached().unwrap(),
+        generic.tls_serialize_detached().unwrap()
+    );
+}
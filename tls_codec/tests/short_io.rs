@@ -0,0 +1,259 @@
+//! Readers and writers that transfer one byte at a time and interrupt every
+//! other call, to check that all codecs read and write exactly.
+#![cfg(feature = "std")]
+
+use std::{
+    fmt::Debug,
+    io::{self, Read, Write},
+    rc::Rc,
+};
+
+use tls_codec::{
+    Deserialize, Error, SecretTlsVecU16, SecretTlsVecU32, SecretTlsVecU8, Serialize, TlsByteVecU16,
+    TlsByteVecU24, TlsByteVecU32, TlsByteVecU8, TlsSliceU16, TlsVarVec, TlsVecU16, TlsVecU24,
+    TlsVecU32, TlsVecU8, VLBytes, VarInt, U24, U48,
+};
+
+/// A reader that returns at most one byte per call.
+struct OneByteReader<'a> {
+    bytes: &'a [u8],
+    interrupt: bool,
+}
+
+impl<'a> OneByteReader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self {
+            bytes,
+            interrupt: true,
+        }
+    }
+
+    /// Move the next byte into `buf` and return the number of bytes read.
+    fn read_one(&mut self, buf: &mut [u8]) -> usize {
+        match (self.bytes.split_first(), buf.first_mut()) {
+            (Some((byte, tail)), Some(out)) => {
+                *out = *byte;
+                self.bytes = tail;
+                1
+            }
+            _ => 0,
+        }
+    }
+}
+
+impl<'a> Read for OneByteReader<'a> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if interrupted(&mut self.interrupt) {
+            return Err(io::ErrorKind::Interrupted.into());
+        }
+        Ok(self.read_one(buf))
+    }
+}
+
+/// A writer that accepts at most one byte per call.
+#[derive(Default)]
+struct OneByteWriter {
+    bytes: Vec<u8>,
+    interrupt: bool,
+}
+
+impl OneByteWriter {
+    /// Append the first byte of `buf` and return the number of bytes written.
+    fn write_one(&mut self, buf: &[u8]) -> usize {
+        match buf.first() {
+            Some(byte) => {
+                self.bytes.push(*byte);
+                1
+            }
+            None => 0,
+        }
+    }
+}
+
+impl Write for OneByteWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if interrupted(&mut self.interrupt) {
+            return Err(io::ErrorKind::Interrupted.into());
+        }
+        Ok(self.write_one(buf))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Interrupt every other call.
+fn interrupted(interrupt: &mut bool) -> bool {
+    *interrupt = !*interrupt;
+    *interrupt
+}
+
+/// Encode `value` one byte at a time and check that it decodes one byte at a
+/// time to the same value, and that a truncated encoding is rejected.
+fn roundtrip<T: Serialize + Deserialize + PartialEq + Debug>(value: &T) {
+    let mut writer = OneByteWriter::default();
+    let written = value.tls_serialize(&mut writer).unwrap();
+    assert_eq!(value.tls_serialize_detached().unwrap(), writer.bytes);
+    assert_eq!(writer.bytes.len(), written);
+    assert_eq!(written, value.tls_serialized_len());
+
+    let mut reader = OneByteReader::new(&writer.bytes);
+    assert_eq!(value, &T::tls_deserialize(&mut reader).unwrap());
+    assert!(reader.bytes.is_empty());
+
+    if let Some((_, truncated)) = writer.bytes.split_last() {
+        let error = T::tls_deserialize(&mut OneByteReader::new(truncated)).unwrap_err();
+        assert_eq!(&Error::EndOfStream, error.kind());
+    }
+}
+
+#[test]
+fn primitives() {
+    roundtrip(&0x12u8);
+    roundtrip(&0x1234u16);
+    roundtrip(&0x1234_5678u32);
+    roundtrip(&0x1234_5678_9abc_def0u64);
+    roundtrip(&u128::MAX);
+    roundtrip(&-2i8);
+    roundtrip(&i16::MIN);
+    roundtrip(&-3i32);
+    roundtrip(&i64::MAX);
+    roundtrip(&-4i128);
+    roundtrip(&true);
+    roundtrip(&Some(5u16));
+    roundtrip(&Option::<u16>::None);
+    roundtrip(&U24::new(0x01_02_03).unwrap());
+    roundtrip(&U48::new(0x01_02_03_04_05_06).unwrap());
+    roundtrip(&VarInt::new(0x3fff_ffff).unwrap());
+    roundtrip(&VarInt::new(0x4000_0000).unwrap());
+}
+
+#[test]
+fn compound_types() {
+    roundtrip(&[1u8; 33]);
+    roundtrip(&[1u16, 2, 3]);
+    roundtrip(&(1u8, 2u16, [3u8; 4], Some(5u32)));
+    roundtrip(&Box::new(6u64));
+    roundtrip(&Rc::new((7u8, 8u16)));
+}
+
+#[test]
+fn vectors() {
+    let bytes: Vec<u8> = (0..=255).collect();
+    let values: Vec<u16> = (0..100).collect();
+
+    roundtrip(&TlsVecU8::from_slice(&values[..10]));
+    roundtrip(&TlsVecU16::from_slice(&values));
+    roundtrip(&TlsVecU24::from_slice(&values));
+    roundtrip(&TlsVecU32::from_slice(&values));
+    roundtrip(&TlsVecU16::<TlsVecU8<u8>>::from(vec![
+        TlsVecU8::from_slice(&bytes[..3]),
+        TlsVecU8::from_slice(&[]),
+        TlsVecU8::from_slice(&bytes[3..9]),
+    ]));
+    roundtrip(&SecretTlsVecU8::from_slice(&bytes[..100]));
+    roundtrip(&SecretTlsVecU16::from_slice(&values));
+    roundtrip(&SecretTlsVecU32::from_slice(&values));
+    roundtrip(&TlsByteVecU8::from_slice(&bytes[..255]));
+    roundtrip(&TlsByteVecU16::from_slice(&bytes));
+    roundtrip(&TlsByteVecU24::from_slice(&bytes));
+    roundtrip(&TlsByteVecU32::from_slice(&bytes));
+    roundtrip(&VLBytes::new(bytes.clone()));
+    roundtrip(&TlsVarVec::from(values.clone()));
+
+    // Slices are only serialized.
+    let mut writer = OneByteWriter::default();
+    TlsSliceU16(&values[..]).tls_serialize(&mut writer).unwrap();
+    assert_eq!(
+        TlsVecU16::<u16>::tls_deserialize(&mut OneByteReader::new(&writer.bytes)).unwrap(),
+        TlsVecU16::from_slice(&values)
+    );
+}
+
+#[cfg(feature = "futures-io")]
+mod async_io {
+    use std::{
+        pin::Pin,
+        task::{Context, Poll},
+    };
+
+    use futures::executor::block_on;
+    use tls_codec::{AsyncDeserialize, AsyncSerialize, FuturesIo};
+
+    use super::*;
+
+    // Return `Poll::Pending` every other poll, like a socket that isn't
+    // ready yet.
+    impl<'a> futures_io::AsyncRead for OneByteReader<'a> {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut [u8],
+        ) -> Poll<io::Result<usize>> {
+            let this = self.get_mut();
+            if interrupted(&mut this.interrupt) {
+                cx.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+            Poll::Ready(Ok(this.read_one(buf)))
+        }
+    }
+
+    impl futures_io::AsyncWrite for OneByteWriter {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<io::Result<usize>> {
+            let this = self.get_mut();
+            if interrupted(&mut this.interrupt) {
+                cx.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+            Poll::Ready(Ok(this.write_one(buf)))
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    async fn roundtrip_async<T>(value: &T)
+    where
+        T: AsyncSerialize + AsyncDeserialize + Serialize + PartialEq + Debug,
+    {
+        let mut writer = FuturesIo(OneByteWriter::default());
+        let written = value.tls_serialize_async(&mut writer).await.unwrap();
+        assert_eq!(value.tls_serialize_detached().unwrap(), writer.0.bytes);
+        assert_eq!(writer.0.bytes.len(), written);
+
+        let mut reader = FuturesIo(OneByteReader::new(&writer.0.bytes));
+        assert_eq!(value, &T::tls_deserialize_async(&mut reader).await.unwrap());
+        assert!(reader.0.bytes.is_empty());
+
+        if let Some((_, truncated)) = writer.0.bytes.split_last() {
+            let mut reader = FuturesIo(OneByteReader::new(truncated));
+            let error = T::tls_deserialize_async(&mut reader).await.unwrap_err();
+            assert_eq!(&Error::EndOfStream, error.kind());
+        }
+    }
+
+    #[test]
+    fn async_roundtrip() {
+        block_on(async {
+            roundtrip_async(&0x1234_5678u32).await;
+            roundtrip_async(&-5i64).await;
+            roundtrip_async(&U24::new(7).unwrap()).await;
+            roundtrip_async(&VarInt::new(0x4000).unwrap()).await;
+            roundtrip_async(&(1u8, Some(2u16), [3u8; 5])).await;
+            roundtrip_async(&Box::new(TlsVecU24::from_slice(&[1u16, 2, 3]))).await;
+            roundtrip_async(&TlsByteVecU16::from_slice(&[4; 300])).await;
+            roundtrip_async(&TlsVarVec::from(vec![5u32, 6])).await;
+        });
+    }
+}
@@ -7,8 +7,8 @@ use quote::{quote, ToTokens};
 use syn::{
     self, parenthesized,
     parse::{ParseStream, Parser, Result},
-    parse_macro_input, Data, DeriveInput, Fields, FieldsNamed, FieldsUnnamed, Generics, Ident,
-    Index,
+    parse_macro_input, parse_quote, Data, DeriveInput, Fields, FieldsNamed, FieldsUnnamed,
+    GenericParam, Generics, Ident, Index, Lifetime, LifetimeDef, Token, Type,
 };
 
 #[derive(Clone)]
@@ -18,6 +18,7 @@ struct Struct {
     generics: Generics,
     field_idents: Vec<Option<Ident>>,
     field_paths: Vec<TokenStream2>,
+    field_types: Vec<Type>,
 }
 
 #[derive(Clone)]
@@ -27,6 +28,7 @@ struct TupleStruct {
     generics: Generics,
     field_indices: Vec<Index>,
     field_paths: Vec<TokenStream2>,
+    field_types: Vec<Type>,
 }
 
 #[derive(Clone)]
@@ -60,7 +62,7 @@ fn parse_ast(ast: DeriveInput) -> Result<TlsStruct> {
                     syn::Type::Path(mut p) => {
                         let path = &mut p.path;
                         // Convert generic arguments in the path to const arguments.
-                        path.segments.iter_mut().for_each(|mut p| {
+                        path.segments.iter_mut().for_each(|p| {
                             if let syn::PathArguments::AngleBracketed(ab) = &mut p.arguments {
                                 let mut ab = ab.clone();
                                 ab.colon2_token = Some(syn::token::Colon2::default());
@@ -91,12 +93,14 @@ fn parse_ast(ast: DeriveInput) -> Result<TlsStruct> {
                     ),
                 });
                 let field_paths: Vec<TokenStream2> = paths.collect();
+                let field_types: Vec<Type> = named.iter().map(|f| f.ty.clone()).collect();
                 Ok(TlsStruct::Struct(Struct {
                     call_site,
                     ident: ident.clone(),
                     generics: generics.clone(),
                     field_idents,
                     field_paths,
+                    field_types,
                 }))
             }
             #[allow(unused_variables)]
@@ -111,7 +115,7 @@ fn parse_ast(ast: DeriveInput) -> Result<TlsStruct> {
                     syn::Type::Path(mut p) => {
                         let path = &mut p.path;
                         // Convert generic arguments in the path to const arguments.
-                        path.segments.iter_mut().for_each(|mut p| {
+                        path.segments.iter_mut().for_each(|p| {
                             if let syn::PathArguments::AngleBracketed(ab) = &mut p.arguments {
                                 let mut ab = ab.clone();
                                 ab.colon2_token = Some(syn::token::Colon2::default());
@@ -127,12 +131,14 @@ fn parse_ast(ast: DeriveInput) -> Result<TlsStruct> {
                 });
 
                 let field_paths: Vec<TokenStream2> = paths.collect();
+                let field_types: Vec<Type> = unnamed.iter().map(|f| f.ty.clone()).collect();
                 Ok(TlsStruct::TupleStruct(TupleStruct {
                     call_site,
                     ident: ident.clone(),
                     generics: generics.clone(),
                     field_indices,
                     field_paths,
+                    field_types,
                 }))
             }
             _ => unimplemented!(),
@@ -199,29 +205,206 @@ fn parse_ast(ast: DeriveInput) -> Result<TlsStruct> {
     }
 }
 
-#[proc_macro_derive(TlsSize)]
+/// How a field gets the context in `TlsSerializeWithContext` and
+/// `TlsDeserializeWithContext`.
+#[derive(Clone)]
+enum FieldContext {
+    /// Pass on the context of the struct.
+    Inherit,
+    /// `#[tls_codec(with_context = expr)]` passes `&(expr)` instead.
+    With(TokenStream2),
+    /// `#[tls_codec(without_context)]` uses `Serialize` and `Deserialize`.
+    Without,
+}
+
+/// The `tls_codec` attributes of a type deriving `TlsSerializeWithContext` or
+/// `TlsDeserializeWithContext`.
+struct ContextAttrs {
+    /// `#[tls_codec(context = Type)]` on the type.
+    /// The impls are generic over the context if it's not set.
+    context: Option<Type>,
+    /// The context of each field of a struct.
+    fields: Vec<FieldContext>,
+}
+
+fn parse_context_attrs(ast: &DeriveInput) -> Result<ContextAttrs> {
+    let mut context = None;
+    for attr in ast.attrs.iter().filter(|a| a.path.is_ident("tls_codec")) {
+        attr.parse_args_with(|input: ParseStream| {
+            let key: Ident = input.parse()?;
+            if key == "fixed_size" {
+                return Ok(());
+            }
+            if key != "context" {
+                return Err(syn::Error::new(
+                    key.span(),
+                    "expected `context = Type` in #[tls_codec(...)]",
+                ));
+            }
+            input.parse::<Token![=]>()?;
+            context = Some(input.parse()?);
+            Ok(())
+        })?;
+    }
+
+    let fields = match &ast.data {
+        Data::Struct(st) => st.fields.iter().collect(),
+        _ => Vec::new(),
+    };
+    let fields = fields
+        .into_iter()
+        .map(|field| {
+            let mut field_context = FieldContext::Inherit;
+            for attr in field.attrs.iter().filter(|a| a.path.is_ident("tls_codec")) {
+                field_context = attr.parse_args_with(|input: ParseStream| {
+                    let key: Ident = input.parse()?;
+                    if key == "without_context" {
+                        Ok(FieldContext::Without)
+                    } else if key == "with_context" {
+                        input.parse::<Token![=]>()?;
+                        Ok(FieldContext::With(input.parse()?))
+                    } else {
+                        Err(syn::Error::new(
+                            key.span(),
+                            "expected `with_context = expr` or `without_context` in #[tls_codec(...)]",
+                        ))
+                    }
+                })?;
+            }
+            Ok(field_context)
+        })
+        .collect::<Result<_>>()?;
+    Ok(ContextAttrs { context, fields })
+}
+
+/// Check for `#[tls_codec(fixed_size)]` on a type deriving `TlsSize`, which
+/// implements `FixedSize` for a struct of fixed-size fields.
+///
+/// The derive doesn't guess this from the names of the field types, which
+/// may refer to other types than those of `tls_codec`.
+fn parse_fixed_size_attr(ast: &DeriveInput) -> Result<bool> {
+    let mut fixed_size = false;
+    for attr in ast.attrs.iter().filter(|a| a.path.is_ident("tls_codec")) {
+        attr.parse_args_with(|input: ParseStream| {
+            let key: Ident = input.parse()?;
+            if key == "fixed_size" {
+                fixed_size = true;
+            }
+            // Other keys are parsed by the derives that use them.
+            input.parse::<TokenStream2>()?;
+            Ok(())
+        })?;
+    }
+    Ok(fixed_size)
+}
+
+/// Check if `tokens` mention one of the type parameters of `generics`.
+fn mentions_type_param(tokens: TokenStream2, generics: &Generics) -> bool {
+    tokens.into_iter().any(|token| match token {
+        proc_macro2::TokenTree::Ident(ident) => generics.type_params().any(|p| p.ident == ident),
+        proc_macro2::TokenTree::Group(group) => mentions_type_param(group.stream(), generics),
+        _ => false,
+    })
+}
+
+/// Implement `FixedSize` for a struct that is marked with
+/// `#[tls_codec(fixed_size)]`.
+///
+/// Fields with a generic type are required to be `FixedSize` and other
+/// fields fail to compile if they aren't.
+fn impl_fixed_size(
+    ident: &Ident,
+    generics: &Generics,
+    field_types: &[Type],
+    fixed_size: bool,
+) -> TokenStream2 {
+    if !fixed_size {
+        return TokenStream2::new();
+    }
+    let mut impl_generics = generics.clone();
+    let where_clause = impl_generics.make_where_clause();
+    for ty in field_types {
+        if mentions_type_param(ty.to_token_stream(), generics) {
+            where_clause
+                .predicates
+                .push(parse_quote! { #ty: tls_codec::FixedSize });
+        }
+    }
+    let (impl_generics, _, where_clause) = impl_generics.split_for_impl();
+    let (_, ty_generics, _) = generics.split_for_impl();
+    quote! {
+        impl #impl_generics tls_codec::FixedSize for #ident #ty_generics #where_clause {
+            const TLS_LEN: usize = #(<#field_types as tls_codec::FixedSize>::TLS_LEN + )* 0;
+        }
+    }
+}
+
+#[proc_macro_derive(TlsSize, attributes(tls_codec))]
 pub fn size_macro_derive(input: TokenStream) -> TokenStream {
     let ast = parse_macro_input!(input as DeriveInput);
+    let fixed_size = match parse_fixed_size_attr(&ast) {
+        Ok(fixed_size) => fixed_size,
+        Err(e) => return e.to_compile_error().into(),
+    };
     let parsed_ast = parse_ast(ast).unwrap();
-    impl_tls_size(parsed_ast).into()
+    impl_tls_size(parsed_ast, fixed_size).into()
 }
 
 #[proc_macro_derive(TlsSerialize)]
 pub fn serialize_macro_derive(input: TokenStream) -> TokenStream {
     let ast = parse_macro_input!(input as DeriveInput);
     let parsed_ast = parse_ast(ast).unwrap();
-    impl_serialize(parsed_ast).into()
+    #[cfg(feature = "async")]
+    let async_impl = impl_async_serialize(parsed_ast.clone());
+    #[cfg(not(feature = "async"))]
+    let async_impl = TokenStream2::new();
+    let sync_impl = impl_serialize(parsed_ast);
+    quote! { #sync_impl #async_impl }.into()
 }
 
 #[proc_macro_derive(TlsDeserialize)]
 pub fn deserialize_macro_derive(input: TokenStream) -> TokenStream {
     let ast = parse_macro_input!(input as DeriveInput);
     let parsed_ast = parse_ast(ast).unwrap();
-    impl_deserialize(parsed_ast).into()
+    #[cfg(feature = "async")]
+    let async_impl = impl_async_deserialize(parsed_ast.clone());
+    #[cfg(not(feature = "async"))]
+    let async_impl = TokenStream2::new();
+    let sync_impl = impl_deserialize(parsed_ast);
+    quote! { #sync_impl #async_impl }.into()
+}
+
+#[proc_macro_derive(TlsSerializeWithContext, attributes(tls_codec))]
+pub fn serialize_with_context_macro_derive(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+    let attrs = match parse_context_attrs(&ast) {
+        Ok(attrs) => attrs,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    let parsed_ast = parse_ast(ast).unwrap();
+    impl_serialize_with_context(parsed_ast, attrs).into()
+}
+
+#[proc_macro_derive(TlsDeserializeWithContext, attributes(tls_codec))]
+pub fn deserialize_with_context_macro_derive(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+    let attrs = match parse_context_attrs(&ast) {
+        Ok(attrs) => attrs,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    let parsed_ast = parse_ast(ast).unwrap();
+    impl_deserialize_with_context(parsed_ast, attrs).into()
+}
+
+#[proc_macro_derive(TlsDeserializeBytes)]
+pub fn deserialize_bytes_macro_derive(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+    let parsed_ast = parse_ast(ast).unwrap();
+    impl_deserialize_bytes(parsed_ast).into()
 }
 
 #[allow(unused_variables)]
-fn impl_tls_size(parsed_ast: TlsStruct) -> TokenStream2 {
+fn impl_tls_size(parsed_ast: TlsStruct, fixed_size: bool) -> TokenStream2 {
     match parsed_ast {
         TlsStruct::Struct(Struct {
             call_site,
@@ -229,23 +412,34 @@ fn impl_tls_size(parsed_ast: TlsStruct) -> TokenStream2 {
             generics,
             field_idents,
             field_paths,
+            field_types,
         }) => {
+            let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+            let fixed_size_impl = impl_fixed_size(&ident, &generics, &field_types, fixed_size);
             quote! {
-                impl#generics tls_codec::Size for #ident#generics {
+                impl #impl_generics tls_codec::Size for #ident #ty_generics #where_clause {
                     #[inline]
                     fn tls_serialized_len(&self) -> usize {
                         #(self.#field_idents.tls_serialized_len() + )*
                         0
                     }
-                }
 
-                impl#generics tls_codec::Size for &#ident#generics {
                     #[inline]
-                    fn tls_serialized_len(&self) -> usize {
-                        #(self.#field_idents.tls_serialized_len() + )*
-                        0
+                    fn tls_serialized_len_checked(&self) -> core::result::Result<usize, tls_codec::Error> {
+                        let len = 0usize;
+                        #(let len = tls_codec::__private::add_len(len, self.#field_idents.tls_serialized_len_checked()?)?;)*
+                        core::result::Result::Ok(len)
+                    }
+
+                    #[inline]
+                    fn tls_fixed_len() -> core::option::Option<usize> {
+                        let len = 0usize;
+                        #(let len = len.checked_add(<#field_types as tls_codec::Size>::tls_fixed_len()?)?;)*
+                        core::option::Option::Some(len)
                     }
                 }
+
+                #fixed_size_impl
             }
         }
         TlsStruct::TupleStruct(TupleStruct {
@@ -254,23 +448,34 @@ fn impl_tls_size(parsed_ast: TlsStruct) -> TokenStream2 {
             generics,
             field_indices,
             field_paths,
+            field_types,
         }) => {
+            let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+            let fixed_size_impl = impl_fixed_size(&ident, &generics, &field_types, fixed_size);
             quote! {
-                impl#generics tls_codec::Size for #ident#generics {
+                impl #impl_generics tls_codec::Size for #ident #ty_generics #where_clause {
                     #[inline]
                     fn tls_serialized_len(&self) -> usize {
                         #(self.#field_indices.tls_serialized_len() + )*
                         0
                     }
-                }
 
-                impl#generics tls_codec::Size for &#ident#generics {
                     #[inline]
-                    fn tls_serialized_len(&self) -> usize {
-                        #(self.#field_indices.tls_serialized_len() + )*
-                        0
+                    fn tls_serialized_len_checked(&self) -> core::result::Result<usize, tls_codec::Error> {
+                        let len = 0usize;
+                        #(let len = tls_codec::__private::add_len(len, self.#field_indices.tls_serialized_len_checked()?)?;)*
+                        core::result::Result::Ok(len)
+                    }
+
+                    #[inline]
+                    fn tls_fixed_len() -> core::option::Option<usize> {
+                        let len = 0usize;
+                        #(let len = len.checked_add(<#field_types as tls_codec::Size>::tls_fixed_len()?)?;)*
+                        core::option::Option::Some(len)
                     }
                 }
+
+                #fixed_size_impl
             }
         }
         TlsStruct::Enum(Enum {
@@ -282,20 +487,23 @@ fn impl_tls_size(parsed_ast: TlsStruct) -> TokenStream2 {
             discriminants,
             matched,
         }) => {
+            let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
             quote! {
-                impl#generics tls_codec::Size for #ident#generics {
+                impl #impl_generics tls_codec::Size for #ident #ty_generics #where_clause {
                     #[inline]
                     fn tls_serialized_len(&self) -> usize {
-                        std::mem::size_of::<#repr>()
+                        core::mem::size_of::<#repr>()
                     }
-                }
 
-                impl#generics tls_codec::Size for &#ident#generics {
                     #[inline]
-                    fn tls_serialized_len(&self) -> usize {
-                        std::mem::size_of::<#repr>()
+                    fn tls_fixed_len() -> core::option::Option<usize> {
+                        core::option::Option::Some(core::mem::size_of::<#repr>())
                     }
                 }
+
+                impl #impl_generics tls_codec::FixedSize for #ident #ty_generics #where_clause {
+                    const TLS_LEN: usize = core::mem::size_of::<#repr>();
+                }
             }
         }
     }
@@ -310,43 +518,32 @@ fn impl_serialize(parsed_ast: TlsStruct) -> TokenStream2 {
             generics,
             field_idents,
             field_paths,
+            field_types,
         }) => {
+            let to_slice = quote! {
+                fn tls_serialize_to_checked_slice(&self, out: &mut [u8]) -> core::result::Result<usize, tls_codec::Error> {
+                    let mut written = 0usize;
+                    #(written += tls_codec::Serialize::tls_serialize_to_checked_slice(&self.#field_idents, &mut out[written..])?;)*
+                    Ok(written)
+                }
+
+                fn tls_serialize_vectored<'__tls_codec>(&'__tls_codec self, writer: &mut tls_codec::VectoredWriter<'__tls_codec>) -> core::result::Result<usize, tls_codec::Error> {
+                    let mut written = 0usize;
+                    #(written += tls_codec::Serialize::tls_serialize_vectored(&self.#field_idents, writer)?;)*
+                    Ok(written)
+                }
+            };
+            let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
             quote! {
-                impl#generics tls_codec::Serialize for #ident#generics {
-                    fn tls_serialize<W: std::io::Write>(&self, writer: &mut W) -> core::result::Result<usize, tls_codec::Error> {
+                impl #impl_generics tls_codec::Serialize for #ident #ty_generics #where_clause {
+                    #to_slice
+
+                    fn tls_serialize<W: tls_codec::Writer>(&self, writer: &mut W) -> core::result::Result<usize, tls_codec::Error> {
                         let mut written = 0usize;
                         #(
                             written += self.#field_idents.tls_serialize(writer)?;
                         )*
-                        if cfg!(debug_assertions) {
-                            let expected_written = self.tls_serialized_len();
-                            debug_assert_eq!(written, expected_written, "Expected to serialize {} bytes but only {} were generated.", expected_written, written);
-                            if written != expected_written {
-                                Err(tls_codec::Error::EncodingError(format!("Expected to serialize {} bytes but only {} were generated.", expected_written, written)))
-                            } else {
-                                Ok(written)
-                            }
-                        } else {
-                            Ok(written)
-                        }
-                    }
-                }
-
-                impl#generics tls_codec::Serialize for &#ident#generics {
-                    fn tls_serialize<W: std::io::Write>(&self, writer: &mut W) -> core::result::Result<usize, tls_codec::Error> {
-                        let mut written = 0usize;
-                        #(written += self.#field_idents.tls_serialize(writer)?;)*
-                        if cfg!(debug_assertions) {
-                            let expected_written = self.tls_serialized_len();
-                            debug_assert_eq!(written, expected_written, "Expected to serialize {} bytes but only {} were generated.", expected_written, written);
-                            if written != expected_written {
-                                Err(tls_codec::Error::EncodingError(format!("Expected to serialize {} bytes but only {} were generated.", expected_written, written)))
-                            } else {
-                                Ok(written)
-                            }
-                        } else {
-                            Ok(written)
-                        }
+                        Ok(written)
                     }
                 }
             }
@@ -357,41 +554,30 @@ fn impl_serialize(parsed_ast: TlsStruct) -> TokenStream2 {
             generics,
             field_indices,
             field_paths,
+            field_types,
         }) => {
-            quote! {
-                impl#generics tls_codec::Serialize for #ident#generics {
-                    fn tls_serialize<W: std::io::Write>(&self, writer: &mut W) -> core::result::Result<usize, tls_codec::Error> {
-                        let mut written = 0usize;
-                        #(written += self.#field_indices.tls_serialize(writer)?;)*
-                        if cfg!(debug_assertions) {
-                            let expected_written = self.tls_serialized_len();
-                            debug_assert_eq!(written, expected_written, "Expected to serialize {} bytes but only {} were generated.", expected_written, written);
-                            if written != expected_written {
-                                Err(tls_codec::Error::EncodingError(format!("Expected to serialize {} bytes but only {} were generated.", expected_written, written)))
-                            } else {
-                                Ok(written)
-                            }
-                        } else {
-                            Ok(written)
-                        }
-                    }
+            let to_slice = quote! {
+                fn tls_serialize_to_checked_slice(&self, out: &mut [u8]) -> core::result::Result<usize, tls_codec::Error> {
+                    let mut written = 0usize;
+                    #(written += tls_codec::Serialize::tls_serialize_to_checked_slice(&self.#field_indices, &mut out[written..])?;)*
+                    Ok(written)
                 }
 
-                impl#generics tls_codec::Serialize for &#ident#generics {
-                    fn tls_serialize<W: std::io::Write>(&self, writer: &mut W) -> core::result::Result<usize, tls_codec::Error> {
+                fn tls_serialize_vectored<'__tls_codec>(&'__tls_codec self, writer: &mut tls_codec::VectoredWriter<'__tls_codec>) -> core::result::Result<usize, tls_codec::Error> {
+                    let mut written = 0usize;
+                    #(written += tls_codec::Serialize::tls_serialize_vectored(&self.#field_indices, writer)?;)*
+                    Ok(written)
+                }
+            };
+            let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+            quote! {
+                impl #impl_generics tls_codec::Serialize for #ident #ty_generics #where_clause {
+                    #to_slice
+
+                    fn tls_serialize<W: tls_codec::Writer>(&self, writer: &mut W) -> core::result::Result<usize, tls_codec::Error> {
                         let mut written = 0usize;
                         #(written += self.#field_indices.tls_serialize(writer)?;)*
-                        if cfg!(debug_assertions) {
-                            let expected_written = self.tls_serialized_len();
-                            debug_assert_eq!(written, expected_written, "Expected to serialize {} bytes but only {} were generated.", expected_written, written);
-                            if written != expected_written {
-                                Err(tls_codec::Error::EncodingError(format!("Expected to serialize {} bytes but only {} were generated.", expected_written, written)))
-                            } else {
-                                Ok(written)
-                            }
-                        } else {
-                            Ok(written)
-                        }
+                        Ok(written)
                     }
                 }
             }
@@ -405,18 +591,20 @@ fn impl_serialize(parsed_ast: TlsStruct) -> TokenStream2 {
             discriminants,
             matched,
         }) => {
-            quote! {
-                impl#generics tls_codec::Serialize for #ident#generics {
-                    fn tls_serialize<W: std::io::Write>(&self, writer: &mut W) -> core::result::Result<usize, tls_codec::Error> {
-                        let enum_value: #repr = match self {
-                            #(#parsed_variants)*
-                        };
-                        enum_value.tls_serialize(writer)
-                    }
+            let to_slice = quote! {
+                fn tls_serialize_to_checked_slice(&self, out: &mut [u8]) -> core::result::Result<usize, tls_codec::Error> {
+                    let enum_value: #repr = match self {
+                        #(#parsed_variants)*
+                    };
+                    tls_codec::Serialize::tls_serialize_to_checked_slice(&enum_value, out)
                 }
+            };
+            let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+            quote! {
+                impl #impl_generics tls_codec::Serialize for #ident #ty_generics #where_clause {
+                    #to_slice
 
-                impl#generics tls_codec::Serialize for &#ident#generics {
-                    fn tls_serialize<W: std::io::Write>(&self, writer: &mut W) -> core::result::Result<usize, tls_codec::Error> {
+                    fn tls_serialize<W: tls_codec::Writer>(&self, writer: &mut W) -> core::result::Result<usize, tls_codec::Error> {
                         let enum_value: #repr = match self {
                             #(#parsed_variants)*
                         };
@@ -437,12 +625,20 @@ fn impl_deserialize(parsed_ast: TlsStruct) -> TokenStream2 {
             generics,
             field_idents,
             field_paths,
+            field_types,
         }) => {
+            let type_name = ident.to_string();
+            let field_names = named_field_names(&field_idents);
+            let field_values = field_value_idents(field_idents.len());
+            let field_offsets = field_offsets(&field_values);
+            let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
             quote! {
-                impl tls_codec::Deserialize for #ident {
-                    fn tls_deserialize<R: std::io::Read>(bytes: &mut R) -> core::result::Result<Self, tls_codec::Error> {
+                impl #impl_generics tls_codec::Deserialize for #ident #ty_generics #where_clause {
+                    fn tls_deserialize<R: tls_codec::Reader>(bytes: &mut R) -> core::result::Result<Self, tls_codec::Error> {
+                        #(let #field_values = #field_paths::tls_deserialize(bytes)
+                            .map_err(|e| e.with_field(#field_offsets, #field_names).with_type(#type_name))?;)*
                         Ok(Self {
-                            #(#field_idents: #field_paths::tls_deserialize(bytes)?,)*
+                            #(#field_idents: #field_values,)*
                         })
                     }
                 }
@@ -454,12 +650,20 @@ fn impl_deserialize(parsed_ast: TlsStruct) -> TokenStream2 {
             generics,
             field_indices,
             field_paths,
+            field_types,
         }) => {
+            let type_name = ident.to_string();
+            let field_names = unnamed_field_names(&field_indices);
+            let field_values = field_value_idents(field_indices.len());
+            let field_offsets = field_offsets(&field_values);
+            let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
             quote! {
-                impl tls_codec::Deserialize for #ident {
-                    fn tls_deserialize<R: std::io::Read>(bytes: &mut R) -> core::result::Result<Self, tls_codec::Error> {
+                impl #impl_generics tls_codec::Deserialize for #ident #ty_generics #where_clause {
+                    fn tls_deserialize<R: tls_codec::Reader>(bytes: &mut R) -> core::result::Result<Self, tls_codec::Error> {
+                        #(let #field_values = #field_paths::tls_deserialize(bytes)
+                            .map_err(|e| e.with_field(#field_offsets, #field_names).with_type(#type_name))?;)*
                         Ok(Self(
-                            #(#field_paths::tls_deserialize(bytes)?,)*
+                            #(#field_values,)*
                         ))
                     }
                 }
@@ -474,19 +678,18 @@ fn impl_deserialize(parsed_ast: TlsStruct) -> TokenStream2 {
             discriminants,
             matched,
         }) => {
+            let unknown_value = unknown_enum_value(&repr);
+            let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
             quote! {
-                impl tls_codec::Deserialize for #ident {
+                impl #impl_generics tls_codec::Deserialize for #ident #ty_generics #where_clause {
                     #[allow(non_upper_case_globals)]
-                    fn tls_deserialize<R: std::io::Read>(bytes: &mut R) -> core::result::Result<Self, tls_codec::Error> {
+                    fn tls_deserialize<R: tls_codec::Reader>(bytes: &mut R) -> core::result::Result<Self, tls_codec::Error> {
                         #(#discriminants)*
 
                         let value = #repr::tls_deserialize(bytes)?;
                         match value {
                             #(#matched)*
-                            // XXX: This assumes non-exhaustive matches only.
-                            _ => {
-                                Err(tls_codec::Error::DecodingError(format!("Unmatched value {:?} in tls_deserialize", value)))
-                            },
+                            _ => Err(#unknown_value),
                         }
                     }
                 }
@@ -494,3 +697,570 @@ fn impl_deserialize(parsed_ast: TlsStruct) -> TokenStream2 {
         }
     }
 }
+
+/// Require `#bound` of the type parameters for an async trait, whose futures
+/// are only `Send` if the value is `Sync` when serializing and `Send` when
+/// deserializing.
+#[cfg(feature = "async")]
+fn with_type_param_bound(generics: &Generics, bound: TokenStream2) -> Generics {
+    let mut bounded = generics.clone();
+    let where_clause = bounded.make_where_clause();
+    for param in generics.type_params() {
+        let ident = &param.ident;
+        where_clause
+            .predicates
+            .push(parse_quote! { #ident: #bound });
+    }
+    bounded
+}
+
+#[cfg(feature = "async")]
+#[allow(unused_variables)]
+fn impl_async_serialize(parsed_ast: TlsStruct) -> TokenStream2 {
+    let (ident, generics, body) = match parsed_ast {
+        TlsStruct::Struct(Struct {
+            ident,
+            generics,
+            field_idents,
+            ..
+        }) => {
+            let body = quote! {
+                let mut written = 0usize;
+                #(written += tls_codec::AsyncSerialize::tls_serialize_async(&self.#field_idents, writer).await?;)*
+                Ok(written)
+            };
+            (ident, generics, body)
+        }
+        TlsStruct::TupleStruct(TupleStruct {
+            ident,
+            generics,
+            field_indices,
+            ..
+        }) => {
+            let body = quote! {
+                let mut written = 0usize;
+                #(written += tls_codec::AsyncSerialize::tls_serialize_async(&self.#field_indices, writer).await?;)*
+                Ok(written)
+            };
+            (ident, generics, body)
+        }
+        TlsStruct::Enum(Enum {
+            ident,
+            generics,
+            repr,
+            parsed_variants,
+            ..
+        }) => {
+            let body = quote! {
+                let enum_value: #repr = match self {
+                    #(#parsed_variants)*
+                };
+                tls_codec::AsyncSerialize::tls_serialize_async(&enum_value, writer).await
+            };
+            (ident, generics, body)
+        }
+    };
+    let generics = with_type_param_bound(&generics, quote! { Sync });
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    quote! {
+        impl #impl_generics tls_codec::AsyncSerialize for #ident #ty_generics #where_clause {
+            async fn tls_serialize_async<W: tls_codec::AsyncWriter>(&self, writer: &mut W) -> core::result::Result<usize, tls_codec::Error> {
+                #body
+            }
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+#[allow(unused_variables)]
+fn impl_async_deserialize(parsed_ast: TlsStruct) -> TokenStream2 {
+    match parsed_ast {
+        TlsStruct::Struct(Struct {
+            ident,
+            generics,
+            field_idents,
+            field_types,
+            ..
+        }) => {
+            let type_name = ident.to_string();
+            let field_names = named_field_names(&field_idents);
+            let field_values = field_value_idents(field_idents.len());
+            let field_offsets = field_offsets(&field_values);
+            let generics = with_type_param_bound(&generics, quote! { Send });
+            let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+            quote! {
+                impl #impl_generics tls_codec::AsyncDeserialize for #ident #ty_generics #where_clause {
+                    async fn tls_deserialize_async<R: tls_codec::AsyncReader>(bytes: &mut R) -> core::result::Result<Self, tls_codec::Error> {
+                        #(let #field_values = <#field_types as tls_codec::AsyncDeserialize>::tls_deserialize_async(bytes).await
+                            .map_err(|e| e.with_field(#field_offsets, #field_names).with_type(#type_name))?;)*
+                        Ok(Self {
+                            #(#field_idents: #field_values,)*
+                        })
+                    }
+                }
+            }
+        }
+        TlsStruct::TupleStruct(TupleStruct {
+            ident,
+            generics,
+            field_indices,
+            field_types,
+            ..
+        }) => {
+            let type_name = ident.to_string();
+            let field_names = unnamed_field_names(&field_indices);
+            let field_values = field_value_idents(field_indices.len());
+            let field_offsets = field_offsets(&field_values);
+            let generics = with_type_param_bound(&generics, quote! { Send });
+            let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+            quote! {
+                impl #impl_generics tls_codec::AsyncDeserialize for #ident #ty_generics #where_clause {
+                    async fn tls_deserialize_async<R: tls_codec::AsyncReader>(bytes: &mut R) -> core::result::Result<Self, tls_codec::Error> {
+                        #(let #field_values = <#field_types as tls_codec::AsyncDeserialize>::tls_deserialize_async(bytes).await
+                            .map_err(|e| e.with_field(#field_offsets, #field_names).with_type(#type_name))?;)*
+                        Ok(Self(
+                            #(#field_values,)*
+                        ))
+                    }
+                }
+            }
+        }
+        TlsStruct::Enum(Enum {
+            ident,
+            generics,
+            repr,
+            discriminants,
+            matched,
+            ..
+        }) => {
+            let unknown_value = unknown_enum_value(&repr);
+            let generics = with_type_param_bound(&generics, quote! { Send });
+            let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+            quote! {
+                impl #impl_generics tls_codec::AsyncDeserialize for #ident #ty_generics #where_clause {
+                    #[allow(non_upper_case_globals)]
+                    async fn tls_deserialize_async<R: tls_codec::AsyncReader>(bytes: &mut R) -> core::result::Result<Self, tls_codec::Error> {
+                        #(#discriminants)*
+
+                        let value = <#repr as tls_codec::AsyncDeserialize>::tls_deserialize_async(bytes).await?;
+                        match value {
+                            #(#matched)*
+                            _ => Err(#unknown_value),
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Get the generics for a `SerializeWithContext` or `DeserializeWithContext`
+/// impl and the context type.
+///
+/// Without `#[tls_codec(context = Type)]` the impl is generic over the context
+/// `__C` and requires that the fields passing it on implement `bound` for it.
+fn context_generics(
+    generics: &Generics,
+    attrs: &ContextAttrs,
+    field_types: &[Type],
+    bound: TokenStream2,
+) -> (Generics, TokenStream2) {
+    let mut impl_generics = generics.clone();
+    let context = match &attrs.context {
+        Some(context) => return (impl_generics, context.to_token_stream()),
+        None => quote! { __C },
+    };
+    impl_generics.params.push(parse_quote! { __C: ?Sized });
+    let where_clause = impl_generics.make_where_clause();
+    for (ty, field_context) in field_types.iter().zip(attrs.fields.iter()) {
+        if let FieldContext::Inherit = field_context {
+            where_clause
+                .predicates
+                .push(parse_quote! { #ty: #bound<#context> });
+        }
+    }
+    (impl_generics, context)
+}
+
+/// Get the expressions that serialize the `members` of `self` in the
+/// `context`.
+fn serialize_with_context_fields(
+    members: &[TokenStream2],
+    attrs: &ContextAttrs,
+) -> Vec<TokenStream2> {
+    members
+        .iter()
+        .zip(attrs.fields.iter())
+        .map(|(member, field_context)| match field_context {
+            FieldContext::Inherit => quote! {
+                tls_codec::SerializeWithContext::tls_serialize_with_context(&self.#member, writer, context)
+            },
+            FieldContext::With(expr) => quote! {
+                tls_codec::SerializeWithContext::tls_serialize_with_context(&self.#member, writer, &(#expr))
+            },
+            FieldContext::Without => quote! {
+                tls_codec::Serialize::tls_serialize(&self.#member, writer)
+            },
+        })
+        .collect()
+}
+
+/// Get the expressions that deserialize fields of the `field_types` in the
+/// `context`.
+fn deserialize_with_context_fields(
+    field_types: &[Type],
+    attrs: &ContextAttrs,
+    context: &TokenStream2,
+) -> Vec<TokenStream2> {
+    field_types
+        .iter()
+        .zip(attrs.fields.iter())
+        .map(|(ty, field_context)| match field_context {
+            FieldContext::Inherit => quote! {
+                <#ty as tls_codec::DeserializeWithContext<#context>>::tls_deserialize_with_context(bytes, context)
+            },
+            FieldContext::With(expr) => quote! {
+                <#ty as tls_codec::DeserializeWithContext<_>>::tls_deserialize_with_context(bytes, &(#expr))
+            },
+            FieldContext::Without => quote! {
+                <#ty as tls_codec::Deserialize>::tls_deserialize(bytes)
+            },
+        })
+        .collect()
+}
+
+fn impl_serialize_with_context(parsed_ast: TlsStruct, attrs: ContextAttrs) -> TokenStream2 {
+    let (ident, generics, members, field_types) = match parsed_ast {
+        TlsStruct::Struct(Struct {
+            ident,
+            generics,
+            field_idents,
+            field_types,
+            ..
+        }) => {
+            let members: Vec<TokenStream2> = field_idents.iter().map(|f| quote! { #f }).collect();
+            (ident, generics, members, field_types)
+        }
+        TlsStruct::TupleStruct(TupleStruct {
+            ident,
+            generics,
+            field_indices,
+            field_types,
+            ..
+        }) => {
+            let members: Vec<TokenStream2> = field_indices.iter().map(|i| quote! { #i }).collect();
+            (ident, generics, members, field_types)
+        }
+        TlsStruct::Enum(Enum {
+            ident,
+            generics,
+            repr,
+            parsed_variants,
+            ..
+        }) => {
+            // The enum value doesn't depend on the context.
+            let (impl_generics, context) = context_generics(
+                &generics,
+                &attrs,
+                &[],
+                quote! { tls_codec::SerializeWithContext },
+            );
+            let (impl_generics, _, where_clause) = impl_generics.split_for_impl();
+            let (_, ty_generics, _) = generics.split_for_impl();
+            return quote! {
+                impl #impl_generics tls_codec::SerializeWithContext<#context> for #ident #ty_generics #where_clause {
+                    fn tls_serialize_with_context<W: tls_codec::Writer>(&self, writer: &mut W, _context: &#context) -> core::result::Result<usize, tls_codec::Error> {
+                        let enum_value: #repr = match self {
+                            #(#parsed_variants)*
+                        };
+                        tls_codec::Serialize::tls_serialize(&enum_value, writer)
+                    }
+                }
+            };
+        }
+    };
+    let (impl_generics, context) = context_generics(
+        &generics,
+        &attrs,
+        &field_types,
+        quote! { tls_codec::SerializeWithContext },
+    );
+    let (impl_generics, _, where_clause) = impl_generics.split_for_impl();
+    let (_, ty_generics, _) = generics.split_for_impl();
+    let fields = serialize_with_context_fields(&members, &attrs);
+    let body = quote! {
+        #[allow(unused_variables)]
+        fn tls_serialize_with_context<W: tls_codec::Writer>(&self, writer: &mut W, context: &#context) -> core::result::Result<usize, tls_codec::Error> {
+            let mut written = 0usize;
+            #(written += #fields?;)*
+            Ok(written)
+        }
+    };
+    quote! {
+        #[allow(private_bounds)]
+        impl #impl_generics tls_codec::SerializeWithContext<#context> for #ident #ty_generics #where_clause {
+            #body
+        }
+    }
+}
+
+#[allow(unused_variables)]
+fn impl_deserialize_with_context(parsed_ast: TlsStruct, attrs: ContextAttrs) -> TokenStream2 {
+    match parsed_ast {
+        TlsStruct::Struct(Struct {
+            call_site,
+            ident,
+            generics,
+            field_idents,
+            field_paths,
+            field_types,
+        }) => {
+            let (impl_generics, context) = context_generics(
+                &generics,
+                &attrs,
+                &field_types,
+                quote! { tls_codec::DeserializeWithContext },
+            );
+            let (impl_generics, _, where_clause) = impl_generics.split_for_impl();
+            let (_, ty_generics, _) = generics.split_for_impl();
+            let type_name = ident.to_string();
+            let field_names = named_field_names(&field_idents);
+            let field_values = field_value_idents(field_idents.len());
+            let field_offsets = field_offsets(&field_values);
+            let fields = deserialize_with_context_fields(&field_types, &attrs, &context);
+            quote! {
+                #[allow(private_bounds)]
+                impl #impl_generics tls_codec::DeserializeWithContext<#context> for #ident #ty_generics #where_clause {
+                    #[allow(unused_variables)]
+                    fn tls_deserialize_with_context<R: tls_codec::Reader>(bytes: &mut R, context: &#context) -> core::result::Result<Self, tls_codec::Error> {
+                        #(let #field_values = #fields
+                            .map_err(|e| e.with_field(#field_offsets, #field_names).with_type(#type_name))?;)*
+                        Ok(Self {
+                            #(#field_idents: #field_values,)*
+                        })
+                    }
+                }
+            }
+        }
+        TlsStruct::TupleStruct(TupleStruct {
+            call_site,
+            ident,
+            generics,
+            field_indices,
+            field_paths,
+            field_types,
+        }) => {
+            let (impl_generics, context) = context_generics(
+                &generics,
+                &attrs,
+                &field_types,
+                quote! { tls_codec::DeserializeWithContext },
+            );
+            let (impl_generics, _, where_clause) = impl_generics.split_for_impl();
+            let (_, ty_generics, _) = generics.split_for_impl();
+            let type_name = ident.to_string();
+            let field_names = unnamed_field_names(&field_indices);
+            let field_values = field_value_idents(field_indices.len());
+            let field_offsets = field_offsets(&field_values);
+            let fields = deserialize_with_context_fields(&field_types, &attrs, &context);
+            quote! {
+                #[allow(private_bounds)]
+                impl #impl_generics tls_codec::DeserializeWithContext<#context> for #ident #ty_generics #where_clause {
+                    #[allow(unused_variables)]
+                    fn tls_deserialize_with_context<R: tls_codec::Reader>(bytes: &mut R, context: &#context) -> core::result::Result<Self, tls_codec::Error> {
+                        #(let #field_values = #fields
+                            .map_err(|e| e.with_field(#field_offsets, #field_names).with_type(#type_name))?;)*
+                        Ok(Self(
+                            #(#field_values,)*
+                        ))
+                    }
+                }
+            }
+        }
+        TlsStruct::Enum(Enum {
+            call_site,
+            ident,
+            generics,
+            repr,
+            parsed_variants,
+            discriminants,
+            matched,
+        }) => {
+            // The enum value doesn't depend on the context.
+            let (impl_generics, context) = context_generics(
+                &generics,
+                &attrs,
+                &[],
+                quote! { tls_codec::DeserializeWithContext },
+            );
+            let (impl_generics, _, where_clause) = impl_generics.split_for_impl();
+            let (_, ty_generics, _) = generics.split_for_impl();
+            quote! {
+                impl #impl_generics tls_codec::DeserializeWithContext<#context> for #ident #ty_generics #where_clause {
+                    fn tls_deserialize_with_context<R: tls_codec::Reader>(bytes: &mut R, _context: &#context) -> core::result::Result<Self, tls_codec::Error> {
+                        <Self as tls_codec::Deserialize>::tls_deserialize(bytes)
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Get the names of named fields for error paths.
+fn named_field_names(field_idents: &[Option<Ident>]) -> Vec<String> {
+    field_idents
+        .iter()
+        .map(|f| f.as_ref().map(Ident::to_string).unwrap_or_default())
+        .collect()
+}
+
+/// Get the names of unnamed fields for error paths.
+fn unnamed_field_names(field_indices: &[Index]) -> Vec<String> {
+    field_indices.iter().map(|i| i.index.to_string()).collect()
+}
+
+/// Get the identifiers of the local variables holding the decoded fields.
+fn field_value_idents(num_fields: usize) -> Vec<Ident> {
+    (0..num_fields)
+        .map(|i| quote::format_ident!("field_{}", i))
+        .collect()
+}
+
+/// Get the error for a decoded enum `value` that doesn't match a variant.
+///
+/// Signed values are widened through the unsigned type of the same width,
+/// such that the error holds their encoded bits instead of sign-extending
+/// them, e.g. `0xff` for `-1i8`.
+fn unknown_enum_value(repr: &Ident) -> TokenStream2 {
+    let repr_name = repr.to_string();
+    let value = match repr_name.strip_prefix('i') {
+        Some(width) => {
+            let unsigned = Ident::new(&format!("u{}", width), repr.span());
+            quote! { value as #unsigned as u64 }
+        }
+        None => quote! { value as u64 },
+    };
+    quote! { tls_codec::Error::UnknownEnumValue { value: #value } }
+}
+
+/// Get the expressions for the offset of each field when decoding from a
+/// `Reader`.
+///
+/// The offset of a field is the serialized length of all previous fields.
+/// It is only computed when decoding the field fails.
+fn field_offsets(field_values: &[Ident]) -> Vec<TokenStream2> {
+    (0..field_values.len())
+        .map(|i| {
+            let previous = &field_values[..i];
+            quote! { 0 #(+ tls_codec::Size::tls_serialized_len(&#previous))* }
+        })
+        .collect()
+}
+
+/// Get the lifetime of the input for `DeserializeBytes`.
+///
+/// This is the first lifetime of the type if it has one.
+/// Otherwise a new lifetime is added to the generics used for the `impl`.
+fn deserialize_bytes_lifetime(generics: &Generics) -> (Lifetime, Generics) {
+    let mut impl_generics = generics.clone();
+    match generics.lifetimes().next() {
+        Some(lifetime) => (lifetime.lifetime.clone(), impl_generics),
+        None => {
+            let lifetime = Lifetime::new("'tls_codec", Span::call_site());
+            impl_generics.params.insert(
+                0,
+                GenericParam::Lifetime(LifetimeDef::new(lifetime.clone())),
+            );
+            (lifetime, impl_generics)
+        }
+    }
+}
+
+#[allow(unused_variables)]
+fn impl_deserialize_bytes(parsed_ast: TlsStruct) -> TokenStream2 {
+    match parsed_ast {
+        TlsStruct::Struct(Struct {
+            call_site,
+            ident,
+            generics,
+            field_idents,
+            field_paths,
+            field_types,
+        }) => {
+            let (lifetime, impl_generics) = deserialize_bytes_lifetime(&generics);
+            let (impl_generics, _, _) = impl_generics.split_for_impl();
+            let (_, ty_generics, where_clause) = generics.split_for_impl();
+            let type_name = ident.to_string();
+            let field_names = named_field_names(&field_idents);
+            let field_values = field_value_idents(field_idents.len());
+            quote! {
+                impl #impl_generics tls_codec::DeserializeBytes<#lifetime> for #ident #ty_generics #where_clause {
+                    fn tls_deserialize_bytes(bytes: &#lifetime [u8]) -> core::result::Result<(Self, &#lifetime [u8]), tls_codec::Error> {
+                        let remainder = bytes;
+                        #(let (#field_values, remainder) = <#field_types as tls_codec::DeserializeBytes<#lifetime>>::tls_deserialize_bytes(remainder)
+                            .map_err(|e| e.with_field(bytes.len() - remainder.len(), #field_names).with_type(#type_name))?;)*
+                        Ok((Self {
+                            #(#field_idents: #field_values,)*
+                        }, remainder))
+                    }
+                }
+            }
+        }
+        TlsStruct::TupleStruct(TupleStruct {
+            call_site,
+            ident,
+            generics,
+            field_indices,
+            field_paths,
+            field_types,
+        }) => {
+            let (lifetime, impl_generics) = deserialize_bytes_lifetime(&generics);
+            let (impl_generics, _, _) = impl_generics.split_for_impl();
+            let (_, ty_generics, where_clause) = generics.split_for_impl();
+            let type_name = ident.to_string();
+            let field_names = unnamed_field_names(&field_indices);
+            let field_values = field_value_idents(field_indices.len());
+            quote! {
+                impl #impl_generics tls_codec::DeserializeBytes<#lifetime> for #ident #ty_generics #where_clause {
+                    fn tls_deserialize_bytes(bytes: &#lifetime [u8]) -> core::result::Result<(Self, &#lifetime [u8]), tls_codec::Error> {
+                        let remainder = bytes;
+                        #(let (#field_values, remainder) = <#field_types as tls_codec::DeserializeBytes<#lifetime>>::tls_deserialize_bytes(remainder)
+                            .map_err(|e| e.with_field(bytes.len() - remainder.len(), #field_names).with_type(#type_name))?;)*
+                        Ok((Self(
+                            #(#field_values,)*
+                        ), remainder))
+                    }
+                }
+            }
+        }
+        TlsStruct::Enum(Enum {
+            call_site,
+            ident,
+            generics,
+            repr,
+            parsed_variants,
+            discriminants,
+            matched,
+        }) => {
+            let (lifetime, impl_generics) = deserialize_bytes_lifetime(&generics);
+            let (impl_generics, _, _) = impl_generics.split_for_impl();
+            let (_, ty_generics, where_clause) = generics.split_for_impl();
+            let unknown_value = unknown_enum_value(&repr);
+            quote! {
+                impl #impl_generics tls_codec::DeserializeBytes<#lifetime> for #ident #ty_generics #where_clause {
+                    #[allow(non_upper_case_globals)]
+                    fn tls_deserialize_bytes(bytes: &#lifetime [u8]) -> core::result::Result<(Self, &#lifetime [u8]), tls_codec::Error> {
+                        #(#discriminants)*
+
+                        let (value, remainder) = <#repr as tls_codec::DeserializeBytes<#lifetime>>::tls_deserialize_bytes(bytes)?;
+                        let result: core::result::Result<Self, tls_codec::Error> = match value {
+                            #(#matched)*
+                            _ => Err(#unknown_value),
+                        };
+                        result.map(|value| (value, remainder))
+                    }
+                }
+            }
+        }
+    }
+}
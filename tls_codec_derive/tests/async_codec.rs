@@ -0,0 +1,127 @@
+#![cfg(feature = "async")]
+
+use futures::executor::block_on;
+use tls_codec::{AsyncDeserialize, AsyncSerialize, Serialize, Size, TlsByteVecU8, TlsVecU32};
+use tls_codec_derive::{TlsDeserialize, TlsSerialize, TlsSize};
+
+#[derive(TlsSerialize, TlsDeserialize, TlsSize, Debug, PartialEq, Clone, Copy)]
+#[repr(u16)]
+enum SecretType {
+    Group = 1,
+    Path = 2,
+}
+
+#[derive(TlsSerialize, TlsDeserialize, TlsSize, Debug, PartialEq, Clone)]
+struct EncryptedGroupSecrets {
+    key_package_hash: TlsByteVecU8,
+    secret_type: SecretType,
+}
+
+#[derive(TlsSerialize, TlsDeserialize, TlsSize, Debug, PartialEq)]
+struct Welcome {
+    version: u8,
+    secrets: TlsVecU32<EncryptedGroupSecrets>,
+}
+
+#[derive(TlsSerialize, TlsDeserialize, TlsSize, Debug, PartialEq)]
+struct Epoch(u64, Option<[u8; 2]>);
+
+#[test]
+fn async_derive() {
+    block_on(async {
+        let secrets = EncryptedGroupSecrets {
+            key_package_hash: TlsByteVecU8::from_slice(&[1, 2]),
+            secret_type: SecretType::Path,
+        };
+        let welcome = Welcome {
+            version: 1,
+            secrets: vec![secrets; 4].into(),
+        };
+        let mut serialized = Vec::new();
+        let written = welcome.tls_serialize_async(&mut serialized).await.unwrap();
+        assert_eq!(welcome.tls_serialized_len(), written);
+        assert_eq!(welcome.tls_serialize_detached().unwrap(), serialized);
+        let deserialized = Welcome::tls_deserialize_async(&mut serialized.as_slice())
+            .await
+            .unwrap();
+        assert_eq!(welcome, deserialized);
+
+        let epoch = Epoch(7, Some([8, 9]));
+        let mut serialized = Vec::new();
+        epoch.tls_serialize_async(&mut serialized).await.unwrap();
+        let deserialized = Epoch::tls_deserialize_async(&mut serialized.as_slice())
+            .await
+            .unwrap();
+        assert_eq!(epoch, deserialized);
+    });
+}
+
+#[test]
+fn async_derive_errors() {
+    block_on(async {
+        let secrets = EncryptedGroupSecrets {
+            key_package_hash: TlsByteVecU8::from_slice(&[1, 2]),
+            secret_type: SecretType::Group,
+        };
+        let welcome = Welcome {
+            version: 1,
+            secrets: vec![secrets; 4].into(),
+        };
+        let mut serialized = welcome.tls_serialize_detached().unwrap();
+
+        // Break the `secret_type` of the last secret.
+        serialized[23..25].copy_from_slice(&[0, 6]);
+        let error = Welcome::tls_deserialize_async(&mut serialized.as_slice())
+            .await
+            .unwrap_err();
+        assert_eq!(23, error.offset());
+        assert_eq!(
+            "Welcome.secrets[3].secret_type",
+            error.path().unwrap().to_string()
+        );
+
+        // Decoding stops at the end of the input.
+        let error = Welcome::tls_deserialize_async(&mut &serialized[..12])
+            .await
+            .unwrap_err();
+        assert_eq!(11, error.offset());
+        assert_eq!(&tls_codec::Error::EndOfStream, error.kind());
+    });
+}
+
+#[derive(TlsSerialize, TlsDeserialize, TlsSize, Debug, PartialEq)]
+struct TreeNode {
+    value: u16,
+    child: Option<Box<TreeNode>>,
+}
+
+#[test]
+fn async_recursive_struct() {
+    block_on(async {
+        let node = TreeNode {
+            value: 1,
+            child: Some(Box::new(TreeNode {
+                value: 2,
+                child: Some(Box::new(TreeNode {
+                    value: 3,
+                    child: None,
+                })),
+            })),
+        };
+        let mut serialized = Vec::new();
+        let written = node.tls_serialize_async(&mut serialized).await.unwrap();
+        assert_eq!(node.tls_serialize_detached().unwrap(), serialized);
+        assert_eq!(node.tls_serialized_len(), written);
+
+        let deserialized = TreeNode::tls_deserialize_async(&mut serialized.as_slice())
+            .await
+            .unwrap();
+        assert_eq!(node, deserialized);
+
+        let error = TreeNode::tls_deserialize_async(&mut &serialized[..5])
+            .await
+            .unwrap_err();
+        assert_eq!(&tls_codec::Error::EndOfStream, error.kind());
+        assert_eq!("TreeNode.child.child", error.path().unwrap().to_string());
+    });
+}
@@ -1,9 +1,27 @@
-use tls_codec::{Deserialize, Serialize, Size, TlsSliceU16, TlsVecU16, TlsVecU32, TlsVecU8};
-use tls_codec_derive::{TlsDeserialize, TlsSerialize, TlsSize};
+use tls_codec::{
+    Deserialize, DeserializeBytes, DeserializeWithContext, FixedSize, Reader, Serialize,
+    SerializeWithContext, Size, TlsByteSliceU16, TlsByteVecU24, TlsByteVecU8, TlsSliceU16,
+    TlsVarVec, TlsVecU16, TlsVecU32, TlsVecU8, VLBytes, Writer, U24, U48,
+};
+use tls_codec_derive::{
+    TlsDeserialize, TlsDeserializeBytes, TlsDeserializeWithContext, TlsSerialize,
+    TlsSerializeWithContext, TlsSize,
+};
 
-#[derive(TlsDeserialize, Debug, PartialEq, Clone, Copy, TlsSize, TlsSerialize)]
+#[derive(
+    TlsDeserialize,
+    TlsDeserializeBytes,
+    Debug,
+    PartialEq,
+    Clone,
+    Copy,
+    TlsSize,
+    TlsSerialize,
+    Default,
+)]
 #[repr(u16)]
 pub enum ExtensionType {
+    #[default]
     Reserved = 0,
     Capabilities = 1,
     Lifetime = 2,
@@ -13,13 +31,9 @@ pub enum ExtensionType {
     SomethingElse = 500,
 }
 
-impl Default for ExtensionType {
-    fn default() -> Self {
-        Self::Reserved
-    }
-}
-
-#[derive(TlsDeserialize, Debug, PartialEq, TlsSerialize, TlsSize, Clone, Default)]
+#[derive(
+    TlsDeserialize, TlsDeserializeBytes, Debug, PartialEq, TlsSerialize, TlsSize, Clone, Default,
+)]
 pub struct ExtensionStruct {
     extension_type: ExtensionType,
     extension_data: TlsVecU32<u8>,
@@ -38,7 +52,7 @@ pub struct ArrayWrap {
 #[derive(TlsSerialize, TlsDeserialize, TlsSize, Debug, PartialEq)]
 pub struct TupleStruct1(ExtensionStruct);
 
-#[derive(TlsSerialize, TlsDeserialize, TlsSize, Debug, PartialEq)]
+#[derive(TlsSerialize, TlsDeserialize, TlsDeserializeBytes, TlsSize, Debug, PartialEq)]
 pub struct TupleStruct(ExtensionStruct, u8);
 
 #[test]
@@ -137,6 +151,9 @@ fn simple_struct() {
     };
     let deserialized = ExtensionTypeVec::tls_deserialize(&mut b).unwrap();
     assert_eq!(extension, deserialized);
+
+    let deserialized = DeserializeOnlyStruct::tls_deserialize(&mut &[0u8, 7][..]).unwrap();
+    assert!(deserialized == DeserializeOnlyStruct(7));
 }
 
 #[derive(TlsDeserialize, Clone, TlsSize, PartialEq)]
@@ -220,3 +237,655 @@ fn kat_mls_key_package() {
         serialized_key_package.as_slice()
     );
 }
+
+#[derive(TlsSerialize, TlsDeserializeBytes, TlsSize, Debug, PartialEq)]
+struct BorrowedExtension<'a> {
+    extension_type: ExtensionType,
+    extension_data: TlsByteSliceU16<'a>,
+}
+
+#[derive(TlsSerialize, TlsDeserializeBytes, TlsSize, Debug, PartialEq)]
+struct BorrowedMessage<'a> {
+    version: u8,
+    first_extension: BorrowedExtension<'a>,
+    second_extension: BorrowedExtension<'a>,
+    trailer: TlsByteVecU8,
+}
+
+#[test]
+fn deserialize_bytes() {
+    let ext = ExtensionStruct {
+        extension_type: ExtensionType::KeyId,
+        extension_data: TlsVecU32::from_slice(&[1, 2, 3, 4, 5]),
+    };
+    let t = TupleStruct(ext, 5);
+    let mut serialized = t.tls_serialize_detached().unwrap();
+    serialized.extend_from_slice(&[0xAA, 0xBB]);
+    let (deserialized, remainder) = TupleStruct::tls_deserialize_bytes(&serialized).unwrap();
+    assert_eq!(t, deserialized);
+    assert_eq!(&[0xAA, 0xBB], remainder);
+
+    let (variant, remainder) = ExtensionType::tls_deserialize_bytes(&[1u8, 244, 7]).unwrap();
+    assert_eq!(ExtensionType::SomethingElse, variant);
+    assert_eq!(&[7], remainder);
+    assert!(ExtensionType::tls_deserialize_bytes(&[0u8, 6]).is_err());
+}
+
+#[test]
+fn deserialize_bytes_borrowed() {
+    let bytes = [1u8, 0, 3, 0, 3, 1, 2, 3, 0, 5, 0, 1, 9, 2, 0xCA, 0xFE];
+    let (message, remainder) = BorrowedMessage::tls_deserialize_bytes(&bytes).unwrap();
+    assert!(remainder.is_empty());
+    assert_eq!(1, message.version);
+    assert_eq!(ExtensionType::KeyId, message.first_extension.extension_type);
+    assert_eq!(&[1, 2, 3], message.first_extension.extension_data.0);
+    assert_eq!(
+        ExtensionType::RatchetTree,
+        message.second_extension.extension_type
+    );
+    assert_eq!(&[9], message.second_extension.extension_data.0);
+    assert_eq!(&[0xCA, 0xFE], message.trailer.as_slice());
+
+    // The byte slices point into the input.
+    assert!(core::ptr::eq(
+        message.first_extension.extension_data.0.as_ptr(),
+        &bytes[5]
+    ));
+    assert_eq!(bytes.to_vec(), message.tls_serialize_detached().unwrap());
+
+    // Truncated input
+    assert!(BorrowedMessage::tls_deserialize_bytes(&bytes[..bytes.len() - 1]).is_err());
+}
+
+#[derive(TlsSerialize, TlsDeserialize, TlsDeserializeBytes, TlsSize, Debug, PartialEq, Clone)]
+struct EncryptedGroupSecrets {
+    key_package_hash: TlsByteVecU8,
+    encrypted_group_secrets: ExtensionType,
+}
+
+#[derive(TlsSerialize, TlsDeserialize, TlsDeserializeBytes, TlsSize, Debug, PartialEq)]
+struct Welcome {
+    version: u8,
+    secrets: TlsVecU32<EncryptedGroupSecrets>,
+}
+
+#[test]
+fn error_path() {
+    let secrets = EncryptedGroupSecrets {
+        key_package_hash: TlsByteVecU8::from_slice(&[1, 2]),
+        encrypted_group_secrets: ExtensionType::KeyId,
+    };
+    let welcome = Welcome {
+        version: 1,
+        secrets: vec![secrets; 4].into(),
+    };
+    let mut serialized = welcome.tls_serialize_detached().unwrap();
+
+    // Break the `encrypted_group_secrets` of the last secret.
+    // Its offset is 1 (version) + 4 (length) + 3 * 5 (secrets) + 3 (hash).
+    serialized[23..25].copy_from_slice(&[0, 6]);
+    let error = Welcome::tls_deserialize(&mut serialized.as_slice()).unwrap_err();
+    assert_eq!(23, error.offset());
+    assert_eq!(
+        "Welcome.secrets[3].encrypted_group_secrets",
+        error.path().unwrap().to_string()
+    );
+    assert_eq!(
+        &tls_codec::Error::UnknownEnumValue { value: 6 },
+        error.kind()
+    );
+    assert_eq!(
+        tls_codec::AlertDescription::IllegalParameter,
+        error.alert_description()
+    );
+    let bytes_error = Welcome::tls_deserialize_bytes(&serialized).unwrap_err();
+    assert_eq!(error.offset(), bytes_error.offset());
+    assert_eq!(error.path(), bytes_error.path());
+
+    // Truncate the input in the middle of the hash of the second secret.
+    let error = Welcome::tls_deserialize(&mut &serialized[..12]).unwrap_err();
+    assert_eq!(11, error.offset());
+    assert_eq!(
+        "Welcome.secrets[1].key_package_hash",
+        error.path().unwrap().to_string()
+    );
+    assert_eq!(&tls_codec::Error::EndOfStream, error.kind());
+    assert_eq!(
+        "unexpected end of stream at byte 11 in `Welcome.secrets[1].key_package_hash`",
+        error.to_string()
+    );
+
+    // Without a `Reader` the content of the vector is checked up front.
+    let error = Welcome::tls_deserialize_bytes(&serialized[..12]).unwrap_err();
+    assert_eq!(5, error.offset());
+    assert_eq!("Welcome.secrets", error.path().unwrap().to_string());
+    assert_eq!(&tls_codec::Error::EndOfStream, error.kind());
+}
+
+#[test]
+fn decode_limits() {
+    let secrets = EncryptedGroupSecrets {
+        key_package_hash: TlsByteVecU8::from_slice(&[1, 2]),
+        encrypted_group_secrets: ExtensionType::KeyId,
+    };
+    let welcome = Welcome {
+        version: 1,
+        secrets: vec![secrets; 4].into(),
+    };
+    let serialized = welcome.tls_serialize_detached().unwrap();
+
+    let limits = tls_codec::DecodeLimits::default().with_max_element_count(4);
+    let deserialized = Welcome::tls_deserialize_with_limits(&mut serialized.as_slice(), limits)
+        .expect("Error decoding within the limits");
+    assert_eq!(welcome, deserialized);
+
+    // The limits apply to vectors in nested structs.
+    let limits = tls_codec::DecodeLimits::default().with_max_element_count(3);
+    let error =
+        Welcome::tls_deserialize_with_limits(&mut serialized.as_slice(), limits).unwrap_err();
+    assert_eq!(20, error.offset());
+    assert_eq!("Welcome.secrets[3]", error.path().unwrap().to_string());
+    assert!(matches!(
+        error.kind(),
+        tls_codec::Error::LimitExceeded {
+            limit: tls_codec::DecodeLimit::ElementCount,
+            ..
+        }
+    ));
+
+    let limits = tls_codec::DecodeLimits::default().with_max_vector_len(1);
+    let error =
+        Welcome::tls_deserialize_with_limits(&mut serialized.as_slice(), limits).unwrap_err();
+    assert_eq!(1, error.offset());
+    assert_eq!("Welcome.secrets", error.path().unwrap().to_string());
+}
+
+#[derive(TlsSerialize, TlsDeserialize, TlsSize, Debug, PartialEq)]
+struct SharedMessage {
+    epoch: u64,
+    payload: tls_codec::TlsBytesU32,
+}
+
+#[test]
+fn deserialize_tls_bytes() {
+    let message = SharedMessage {
+        epoch: 3,
+        payload: tls_codec::TlsBytesU32::from_slice(&[7; 100]),
+    };
+    let serialized = bytes::Bytes::from(message.tls_serialize_detached().unwrap());
+
+    let mut reader = tls_codec::BytesIo(serialized.clone());
+    let deserialized = SharedMessage::tls_deserialize(&mut reader).unwrap();
+    assert_eq!(message, deserialized);
+    // The payload points into the input buffer.
+    assert_eq!(
+        serialized[12..].as_ptr(),
+        deserialized.payload.as_slice().as_ptr()
+    );
+}
+
+#[test]
+fn deserialize_partial() {
+    let secrets = EncryptedGroupSecrets {
+        key_package_hash: TlsByteVecU8::from_slice(&[1, 2]),
+        encrypted_group_secrets: ExtensionType::KeyId,
+    };
+    let welcome = Welcome {
+        version: 1,
+        secrets: vec![secrets; 2].into(),
+    };
+    let serialized = welcome.tls_serialize_detached().unwrap();
+
+    // Feed the message in fragments of 4 bytes.
+    let mut received = Vec::new();
+    let mut fragments = serialized.chunks(4);
+    let (deserialized, consumed) = loop {
+        match Welcome::tls_deserialize_partial(&received).unwrap() {
+            tls_codec::Decoded::Complete { value, consumed } => break (value, consumed),
+            tls_codec::Decoded::NeedMore { at_least } => {
+                assert!(received.len() + at_least <= serialized.len());
+                received.extend_from_slice(fragments.next().unwrap());
+            }
+        }
+    };
+    assert_eq!(welcome, deserialized);
+    assert_eq!(serialized.len(), consumed);
+}
+
+#[derive(TlsSerialize, TlsDeserialize, TlsSize, Debug, PartialEq)]
+#[tls_codec(fixed_size)]
+pub struct FixedStruct {
+    extension_type: ExtensionType,
+    version: u8,
+    id: u32,
+    data: [u8; 8],
+}
+
+#[derive(TlsSerialize, TlsDeserialize, TlsSize, Debug, PartialEq)]
+#[tls_codec(fixed_size)]
+pub struct FixedTupleStruct(FixedStruct, u64);
+
+/// A struct of fixed-size primitives, which implements `FixedSize` with the
+/// attribute like any other struct.
+#[derive(TlsSerialize, TlsDeserialize, TlsSize, Debug, PartialEq)]
+#[tls_codec(fixed_size)]
+pub struct KnownFixedStruct {
+    version: u8,
+    values: [u16; 2],
+    flag: bool,
+    len: U24,
+    marker: core::marker::PhantomData<Vec<u8>>,
+}
+
+/// A generic struct that is fixed-size if its field is.
+#[derive(TlsSize)]
+#[tls_codec(fixed_size)]
+pub struct FixedWrapper<T: Size>(T);
+
+#[test]
+fn fixed_size() {
+    assert_eq!(2, ExtensionType::TLS_LEN);
+    assert_eq!(15, FixedStruct::TLS_LEN);
+    assert_eq!(23, FixedTupleStruct::TLS_LEN);
+    assert_eq!(9, KnownFixedStruct::TLS_LEN);
+    assert_eq!(15, FixedWrapper::<FixedStruct>::TLS_LEN);
+    assert_eq!(None, FixedWrapper::<TlsVecU8<u8>>::tls_fixed_len());
+    assert_eq!(Some(23), FixedTupleStruct::tls_fixed_len());
+    assert_eq!(None, ExtensionStruct::tls_fixed_len());
+    assert_eq!(None, TupleStruct::tls_fixed_len());
+
+    let value = FixedTupleStruct(
+        FixedStruct {
+            extension_type: ExtensionType::KeyId,
+            version: 1,
+            id: 2,
+            data: [3; 8],
+        },
+        4,
+    );
+    let mut buf = [0u8; FixedTupleStruct::TLS_LEN];
+    assert_eq!(
+        FixedTupleStruct::TLS_LEN,
+        value.tls_serialize_to_slice(&mut buf).unwrap()
+    );
+    assert_eq!(
+        value,
+        FixedTupleStruct::tls_deserialize(&mut &buf[..]).unwrap()
+    );
+
+    // The vector length must be a multiple of the element length.
+    let b = &[3u8, 0, 1, 0] as &[u8];
+    assert_eq!(
+        tls_codec::Error::InvalidVectorLength {
+            expected: 4,
+            actual: 3
+        },
+        ExtensionTypeVec::tls_deserialize(&mut &b[..])
+            .unwrap_err()
+            .kind()
+            .clone()
+    );
+}
+
+#[test]
+fn deserialize_exact() {
+    let b = &[0u8, 3, 0, 0, 0, 1, 7, 9] as &[u8];
+    let value = ExtensionStruct::tls_deserialize_exact(&b[..7]).unwrap();
+    assert_eq!(ExtensionType::KeyId, value.extension_type);
+    assert_eq!(
+        value,
+        ExtensionStruct::tls_deserialize_exact_bytes(&b[..7]).unwrap()
+    );
+    assert_eq!(
+        tls_codec::Error::TrailingData { remaining: 1 },
+        ExtensionStruct::tls_deserialize_exact(b).unwrap_err()
+    );
+    assert_eq!(
+        tls_codec::Error::TrailingData { remaining: 1 },
+        ExtensionStruct::tls_deserialize_exact_bytes(b).unwrap_err()
+    );
+}
+
+/// The negotiated parameters that some values depend on.
+struct Suite {
+    hash_len: usize,
+}
+
+/// A hash without length prefix whose length is given by the context.
+#[derive(Debug, PartialEq)]
+struct Hash(Vec<u8>);
+
+impl Size for Hash {
+    fn tls_serialized_len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+impl SerializeWithContext<usize> for Hash {
+    fn tls_serialize_with_context<W: Writer>(
+        &self,
+        writer: &mut W,
+        hash_len: &usize,
+    ) -> Result<usize, tls_codec::Error> {
+        if self.0.len() != *hash_len {
+            return Err(tls_codec::Error::EncodingError(
+                "wrong hash length".to_string(),
+            ));
+        }
+        writer.write_bytes(&self.0)?;
+        Ok(self.0.len())
+    }
+}
+
+impl DeserializeWithContext<usize> for Hash {
+    fn tls_deserialize_with_context<R: Reader>(
+        bytes: &mut R,
+        hash_len: &usize,
+    ) -> Result<Self, tls_codec::Error> {
+        let mut hash = vec![0; *hash_len];
+        bytes.read_bytes(&mut hash)?;
+        Ok(Self(hash))
+    }
+}
+
+#[derive(TlsSize, TlsSerializeWithContext, TlsDeserializeWithContext, Debug, PartialEq)]
+#[tls_codec(context = Suite)]
+struct ParentHash(#[tls_codec(with_context = context.hash_len)] Hash);
+
+#[derive(TlsSize, TlsSerializeWithContext, TlsDeserializeWithContext, Debug, PartialEq)]
+#[tls_codec(context = Suite)]
+struct Commit {
+    epoch: u64,
+    #[tls_codec(with_context = context.hash_len)]
+    confirmation_tag: Hash,
+    #[tls_codec(without_context)]
+    proposals: TlsVecU8<u16>,
+    parent: Option<ParentHash>,
+}
+
+#[derive(
+    TlsSize, TlsDeserialize, TlsSerializeWithContext, TlsDeserializeWithContext, Debug, PartialEq,
+)]
+#[repr(u8)]
+enum NodeType {
+    Leaf = 1,
+    Parent = 2,
+}
+
+/// Generic over the context and passes it on to all fields.
+#[derive(TlsSize, TlsSerializeWithContext, TlsDeserializeWithContext, Debug, PartialEq)]
+struct Node {
+    node_type: NodeType,
+    commit: Option<Commit>,
+    flags: u8,
+}
+
+#[test]
+fn with_context() {
+    let suite = Suite { hash_len: 2 };
+    let node = Node {
+        node_type: NodeType::Parent,
+        commit: Some(Commit {
+            epoch: 7,
+            confirmation_tag: Hash(vec![1, 2]),
+            proposals: TlsVecU8::from_slice(&[3]),
+            parent: Some(ParentHash(Hash(vec![4, 5]))),
+        }),
+        flags: 6,
+    };
+    let serialized = node.tls_serialize_detached_with_context(&suite).unwrap();
+    assert_eq!(
+        vec![2, 1, 0, 0, 0, 0, 0, 0, 0, 7, 1, 2, 2, 0, 3, 1, 4, 5, 6],
+        serialized
+    );
+    assert_eq!(node.tls_serialized_len(), serialized.len());
+    let deserialized =
+        Node::tls_deserialize_with_context(&mut serialized.as_slice(), &suite).unwrap();
+    assert_eq!(node, deserialized);
+
+    let error = Node::tls_deserialize_with_context(&mut &serialized[..17], &suite).unwrap_err();
+    assert_eq!(&tls_codec::Error::EndOfStream, error.kind());
+    assert_eq!(16, error.offset());
+    assert_eq!("Node.commit.parent.0", error.path().unwrap().to_string());
+
+    let error = node
+        .tls_serialize_detached_with_context(&Suite { hash_len: 3 })
+        .unwrap_err();
+    assert_eq!(
+        tls_codec::Error::EncodingError("wrong hash length".to_string()),
+        error
+    );
+}
+
+/// A key that is typed by a marker.
+#[derive(
+    TlsSerialize, TlsDeserialize, TlsDeserializeBytes, TlsSize, Debug, PartialEq, Clone, Copy,
+)]
+#[tls_codec(fixed_size)]
+struct TypedKey<T> {
+    id: i32,
+    public: bool,
+    kind: SignedKind,
+    marker: core::marker::PhantomData<T>,
+}
+
+#[derive(
+    TlsSerialize, TlsDeserialize, TlsDeserializeBytes, TlsSize, Debug, PartialEq, Clone, Copy,
+)]
+#[repr(i8)]
+enum SignedKind {
+    Negative = -1,
+    Positive = 1,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+struct Signature;
+
+#[test]
+fn generic_marker() {
+    let key = TypedKey::<Signature> {
+        id: -2,
+        public: true,
+        kind: SignedKind::Negative,
+        marker: core::marker::PhantomData,
+    };
+    let serialized = key.tls_serialize_detached().unwrap();
+    assert_eq!(vec![0xff, 0xff, 0xff, 0xfe, 1, 0xff], serialized);
+    assert_eq!(6, TypedKey::<Signature>::TLS_LEN);
+    assert_eq!(
+        key,
+        TypedKey::<Signature>::tls_deserialize(&mut serialized.as_slice()).unwrap()
+    );
+    assert_eq!(
+        key,
+        TypedKey::<Signature>::tls_deserialize_exact_bytes(&serialized).unwrap()
+    );
+
+    let error = TypedKey::<Signature>::tls_deserialize(&mut &[0u8, 0, 0, 1, 2, 1][..]).unwrap_err();
+    assert_eq!(&tls_codec::Error::InvalidBool { value: 2 }, error.kind());
+    assert_eq!("TypedKey.public", error.path().unwrap().to_string());
+    let error = TypedKey::<Signature>::tls_deserialize(&mut &[0u8, 0, 0, 1, 1, 0][..]).unwrap_err();
+    assert_eq!(
+        &tls_codec::Error::UnknownEnumValue { value: 0 },
+        error.kind()
+    );
+
+    // Unknown signed values hold their encoded bits, and like the tag of an
+    // `Option` the error of a top-level enum has no context.
+    let error = SignedKind::tls_deserialize(&mut &[0xfeu8][..]).unwrap_err();
+    assert_eq!(tls_codec::Error::UnknownEnumValue { value: 0xfe }, error);
+    assert_eq!(
+        error,
+        SignedKind::tls_deserialize_bytes(&[0xfe]).unwrap_err()
+    );
+    let error =
+        TypedKey::<Signature>::tls_deserialize(&mut &[0u8, 0, 0, 1, 1, 0x80][..]).unwrap_err();
+    assert_eq!(
+        &tls_codec::Error::UnknownEnumValue { value: 0x80 },
+        error.kind()
+    );
+    assert_eq!("TypedKey.kind", error.path().unwrap().to_string());
+}
+
+/// A TLS handshake message with its `uint24` length.
+#[derive(TlsSerialize, TlsDeserialize, TlsDeserializeBytes, TlsSize, Debug, PartialEq)]
+struct Handshake {
+    msg_type: u8,
+    body: TlsByteVecU24,
+}
+
+/// The start of a DTLS record header.
+#[derive(TlsSerialize, TlsDeserialize, TlsSize, Debug, PartialEq)]
+#[tls_codec(fixed_size)]
+struct RecordNumber {
+    epoch: u16,
+    sequence_number: U48,
+}
+
+#[test]
+fn uint24_and_uint48() {
+    let handshake = Handshake {
+        msg_type: 1,
+        body: TlsByteVecU24::from_slice(&[3, 3, 0]),
+    };
+    let serialized = handshake.tls_serialize_detached().unwrap();
+    assert_eq!(vec![1u8, 0, 0, 3, 3, 3, 0], serialized);
+    assert_eq!(
+        handshake,
+        Handshake::tls_deserialize_exact(&serialized).unwrap()
+    );
+    assert_eq!(
+        handshake,
+        Handshake::tls_deserialize_exact_bytes(&serialized).unwrap()
+    );
+    let error = Handshake::tls_deserialize(&mut &[1u8, 0, 1, 0, 3][..]).unwrap_err();
+    assert_eq!(&tls_codec::Error::EndOfStream, error.kind());
+    assert_eq!("Handshake.body", error.path().unwrap().to_string());
+
+    let record = RecordNumber {
+        epoch: 1,
+        sequence_number: U48::new(0x1_0000_0002).unwrap(),
+    };
+    assert_eq!(8, RecordNumber::TLS_LEN);
+    let serialized = record.tls_serialize_detached().unwrap();
+    assert_eq!(vec![0u8, 1, 0, 1, 0, 0, 0, 2], serialized);
+    assert_eq!(
+        record,
+        RecordNumber::tls_deserialize_exact(&serialized).unwrap()
+    );
+    assert_eq!(
+        U24::new(3).unwrap(),
+        U24::tls_deserialize_exact(&[0, 0, 3]).unwrap()
+    );
+}
+
+/// An MLS `Extension` with a variable-length vector.
+#[derive(TlsSerialize, TlsDeserialize, TlsDeserializeBytes, TlsSize, Debug, PartialEq, Clone)]
+struct MlsExtension {
+    extension_type: u16,
+    extension_data: VLBytes,
+}
+
+#[test]
+fn var_vec() {
+    let extensions = TlsVarVec::from(vec![
+        MlsExtension {
+            extension_type: 1,
+            extension_data: VLBytes::from_slice(&[0xab; 70]),
+        },
+        MlsExtension {
+            extension_type: 2,
+            extension_data: VLBytes::default(),
+        },
+    ]);
+    let serialized = extensions.tls_serialize_detached().unwrap();
+    assert_eq!(79, serialized.len());
+    assert_eq!(&[0x40, 77, 0, 1, 0x40, 70, 0xab], &serialized[..7]);
+    assert_eq!(&[0, 2, 0], &serialized[76..]);
+    assert_eq!(
+        extensions,
+        TlsVarVec::<MlsExtension>::tls_deserialize_exact(&serialized).unwrap()
+    );
+    assert_eq!(
+        extensions,
+        TlsVarVec::<MlsExtension>::tls_deserialize_exact_bytes(&serialized).unwrap()
+    );
+
+    let error = TlsVarVec::<MlsExtension>::tls_deserialize(&mut &serialized[..20]).unwrap_err();
+    assert_eq!(&tls_codec::Error::EndOfStream, error.kind());
+    assert_eq!(
+        "MlsExtension[0].extension_data",
+        error.path().unwrap().to_string()
+    );
+}
+
+/// A struct with arrays of any length and element type.
+#[derive(TlsSerialize, TlsDeserialize, TlsDeserializeBytes, TlsSize, Debug, PartialEq)]
+#[tls_codec(fixed_size)]
+struct ArrayStruct<const N: usize> {
+    extension_types: [ExtensionType; 2],
+    values: [u16; 4],
+    key: [u8; N],
+}
+
+#[test]
+fn array_fields() {
+    let value = ArrayStruct::<133> {
+        extension_types: [ExtensionType::KeyId, ExtensionType::ParentHash],
+        values: [1, 2, 3, 4],
+        key: [9; 133],
+    };
+    assert_eq!(145, ArrayStruct::<133>::TLS_LEN);
+    let serialized = value.tls_serialize_detached().unwrap();
+    assert_eq!(145, serialized.len());
+    assert_eq!(&[0, 3, 0, 4, 0, 1, 0, 2], &serialized[..8]);
+    assert_eq!(
+        value,
+        ArrayStruct::<133>::tls_deserialize_exact(&serialized).unwrap()
+    );
+    assert_eq!(
+        value,
+        ArrayStruct::<133>::tls_deserialize_exact_bytes(&serialized).unwrap()
+    );
+
+    let error = ArrayStruct::<2>::tls_deserialize(&mut &[0u8, 3, 0, 9][..]).unwrap_err();
+    assert_eq!(
+        &tls_codec::Error::UnknownEnumValue { value: 9 },
+        error.kind()
+    );
+    assert_eq!(
+        "ArrayStruct.extension_types[1]",
+        error.path().unwrap().to_string()
+    );
+}
+
+/// A recursive structure with a boxed optional child.
+#[derive(TlsSerialize, TlsDeserialize, TlsDeserializeBytes, TlsSize, Debug, PartialEq)]
+struct TreeNode {
+    value: u16,
+    child: Option<Box<TreeNode>>,
+}
+
+#[test]
+fn recursive_struct() {
+    let node = TreeNode {
+        value: 1,
+        child: Some(Box::new(TreeNode {
+            value: 2,
+            child: None,
+        })),
+    };
+    assert_eq!(None, TreeNode::tls_fixed_len());
+    let serialized = node.tls_serialize_detached().unwrap();
+    assert_eq!(vec![0u8, 1, 1, 0, 2, 0], serialized);
+    assert_eq!(node, TreeNode::tls_deserialize_exact(&serialized).unwrap());
+    assert_eq!(
+        node,
+        TreeNode::tls_deserialize_exact_bytes(&serialized).unwrap()
+    );
+
+    let error = TreeNode::tls_deserialize(&mut &serialized[..5]).unwrap_err();
+    assert_eq!(&tls_codec::Error::EndOfStream, error.kind());
+    assert_eq!("TreeNode.child.child", error.path().unwrap().to_string());
+    assert_eq!(5, error.offset());
+}
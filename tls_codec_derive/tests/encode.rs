@@ -1,4 +1,7 @@
-use tls_codec::{SecretTlsVecU16, Serialize, Size, TlsSliceU16, TlsVecU16, TlsVecU32};
+use tls_codec::{
+    SecretTlsVecU16, Serialize, Size, TlsByteVecU32, TlsByteVecU8, TlsSliceU16, TlsVecU16,
+    TlsVecU32, VectoredWriter,
+};
 use tls_codec_derive::{TlsSerialize, TlsSize};
 
 #[derive(TlsSerialize, TlsSize, Debug)]
@@ -50,7 +53,7 @@ fn lifetime_struct() {
 
     let some_default_value = SomeValue { val: value };
     let values = vec![some_default_value; 33];
-    let ref_values: Vec<&SomeValue> = values.iter().map(|v| v).collect();
+    let ref_values: Vec<&SomeValue> = values.iter().collect();
     let ref_values_slice = TlsSliceU16(&ref_values);
     let s = StructWithDoubleLifetime {
         value: &ref_values_slice,
@@ -103,3 +106,191 @@ fn lifetimes() {
     let serialized = do_some_serializing(&s);
     assert_eq!(vec![0, 4, 1, 2, 3, 4], serialized);
 }
+
+#[test]
+fn serialize_to_slice() {
+    let extension = ExtensionStruct {
+        extension_type: ExtensionType::KeyId,
+        extension_data: TlsVecU32::from_slice(&[1, 2, 3, 4, 5]),
+        additional_data: Some(SecretTlsVecU16::from_slice(&[6, 7])),
+    };
+    let value = TupleStruct(extension, 8);
+    let serialized = value.tls_serialize_detached().unwrap();
+
+    let mut out = [0u8; 32];
+    let written = value.tls_serialize_to_slice(&mut out).unwrap();
+    assert_eq!(serialized.len(), written);
+    assert_eq!(&serialized[..], &out[..written]);
+    assert_eq!(
+        2,
+        ExtensionType::SomethingElse
+            .tls_serialize_to_slice(&mut out)
+            .unwrap()
+    );
+    assert_eq!(&[1, 244], &out[..2]);
+
+    let mut out = [0u8; 16];
+    assert!(matches!(
+        value.tls_serialize_to_slice(&mut out),
+        Err(tls_codec::Error::InvalidWriteLength(_))
+    ));
+    assert_eq!([0u8; 16], out);
+}
+
+std::thread_local! {
+    static MEASURED: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+/// A byte that counts how often its length is computed.
+#[derive(Debug)]
+pub struct Counted(u8);
+
+impl Size for Counted {
+    fn tls_serialized_len(&self) -> usize {
+        MEASURED.with(|measured| measured.set(measured.get() + 1));
+        1
+    }
+}
+
+impl Serialize for Counted {
+    fn tls_serialize<W: tls_codec::Writer>(
+        &self,
+        writer: &mut W,
+    ) -> Result<usize, tls_codec::Error> {
+        self.0.tls_serialize(writer)
+    }
+}
+
+#[cfg(feature = "async")]
+impl tls_codec::AsyncSerialize for Counted {
+    async fn tls_serialize_async<W: tls_codec::AsyncWriter>(
+        &self,
+        writer: &mut W,
+    ) -> Result<usize, tls_codec::Error> {
+        self.0.tls_serialize_async(writer).await
+    }
+}
+
+#[derive(TlsSerialize, TlsSize, Debug)]
+pub struct CountedInner(Counted, u8);
+
+#[derive(TlsSerialize, TlsSize, Debug)]
+pub struct CountedOuter {
+    inner: CountedInner,
+    more: [CountedInner; 2],
+}
+
+#[test]
+fn serialize_to_slice_checks_once() {
+    let value = CountedOuter {
+        inner: CountedInner(Counted(1), 2),
+        more: [CountedInner(Counted(3), 4), CountedInner(Counted(5), 6)],
+    };
+    let mut out = [0u8; 6];
+    MEASURED.with(|measured| measured.set(0));
+    assert_eq!(6, value.tls_serialize_to_slice(&mut out).unwrap());
+    assert_eq!([1, 2, 3, 4, 5, 6], out);
+    // Every element is measured once to check the length of `out`.
+    assert_eq!(3, MEASURED.with(|measured| measured.get()));
+}
+
+/// A value that claims to be as long as the address space.
+#[derive(Debug)]
+pub struct Huge;
+
+impl Size for Huge {
+    fn tls_serialized_len(&self) -> usize {
+        usize::MAX
+    }
+}
+
+impl Serialize for Huge {
+    fn tls_serialize<W: tls_codec::Writer>(&self, _: &mut W) -> Result<usize, tls_codec::Error> {
+        Ok(usize::MAX)
+    }
+}
+
+#[cfg(feature = "async")]
+impl tls_codec::AsyncSerialize for Huge {
+    async fn tls_serialize_async<W: tls_codec::AsyncWriter>(
+        &self,
+        _: &mut W,
+    ) -> Result<usize, tls_codec::Error> {
+        Ok(usize::MAX)
+    }
+}
+
+#[derive(TlsSerialize, TlsSize, Debug)]
+pub struct HugeStruct {
+    huge: Huge,
+    value: u8,
+}
+
+#[derive(TlsSerialize, TlsSize, Debug)]
+pub struct HugeTupleStruct(u8, Huge);
+
+#[test]
+fn serialized_len_overflow() {
+    let value = HugeStruct {
+        huge: Huge,
+        value: 1,
+    };
+    assert!(matches!(
+        value.tls_serialized_len_checked(),
+        Err(tls_codec::Error::EncodingError(_))
+    ));
+    assert!(matches!(
+        value.tls_serialize_detached(),
+        Err(tls_codec::Error::EncodingError(_))
+    ));
+    assert!(matches!(
+        HugeTupleStruct(1, Huge).tls_serialize_to_slice(&mut [0; 4]),
+        Err(tls_codec::Error::EncodingError(_))
+    ));
+    assert_eq!(
+        Ok(4),
+        (ExtensionType::KeyId, 7u16).tls_serialized_len_checked()
+    );
+}
+
+#[derive(TlsSerialize, TlsSize, Debug)]
+pub struct Ciphertext {
+    epoch: u64,
+    content_type: ExtensionType,
+    aad: TlsByteVecU8,
+    ciphertext: TlsByteVecU32,
+}
+
+#[derive(TlsSerialize, TlsSize)]
+pub struct Ciphertexts<'a>(TlsSliceU16<'a, Ciphertext>, u8);
+
+#[test]
+fn serialize_vectored() {
+    let ciphertext = Ciphertext {
+        epoch: 1,
+        content_type: ExtensionType::KeyId,
+        aad: TlsByteVecU8::from_slice(&[2, 3]),
+        ciphertext: TlsByteVecU32::from(vec![4u8; 1000]),
+    };
+    let mut writer = VectoredWriter::new();
+    let written = ciphertext.tls_serialize_vectored(&mut writer).unwrap();
+    assert_eq!(ciphertext.tls_serialized_len(), written);
+    assert_eq!(
+        ciphertext.tls_serialize_detached().unwrap(),
+        writer.to_vec()
+    );
+    let slices: Vec<&[u8]> = writer.slices().collect();
+    assert_eq!(2, slices.len());
+    assert!(core::ptr::eq(ciphertext.ciphertext.as_slice(), slices[1]));
+
+    let ciphertexts = [ciphertext];
+    let ciphertexts = Ciphertexts(TlsSliceU16(&ciphertexts), 5);
+    let by_ref = &ciphertexts;
+    let mut writer = VectoredWriter::new();
+    <&Ciphertexts as Serialize>::tls_serialize_vectored(&by_ref, &mut writer).unwrap();
+    assert_eq!(
+        ciphertexts.tls_serialize_detached().unwrap(),
+        writer.to_vec()
+    );
+    assert_eq!(3, writer.slices().count());
+}